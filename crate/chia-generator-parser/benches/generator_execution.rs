@@ -0,0 +1,220 @@
+//! Time `BlockParser::parse_full_block` on blocks of standard transaction spends
+//!
+//! Run with `cargo bench --bench generator_execution`. The parser runs the generator and
+//! each puzzle once; `parse_running_twice` keeps the earlier strategy of calling
+//! `run_block_generator2` and then running them all again to read the spends, so the two
+//! are timed side by side. Both validate the block's aggregate signature through the same
+//! pairing cache. Measured on one core with this bench in release mode (ms per block):
+//!
+//! | spends | run twice | run once |
+//! |-------:|----------:|---------:|
+//! |     10 |     8.495 |    8.047 |
+//! |    100 |    69.455 |   67.363 |
+//! |    500 |   324.913 |  301.587 |
+
+#[path = "../tests/common/mod.rs"]
+mod common;
+
+use chia_bls::{aggregate, sign, BlsCache, SecretKey, Signature};
+use chia_consensus::{
+    allocator::make_allocator,
+    consensus_constants::ConsensusConstants,
+    run_block_generator::{run_block_generator2, setup_generator_args},
+};
+use chia_generator_parser::{
+    flags_for_height, identify_puzzle, parse_conditions, parse_create_coins, run_puzzle,
+    BlockParser, Network, SignatureValidation,
+};
+use chia_protocol::{Bytes32, Coin, FullBlock};
+use chia_puzzle_types::standard::{StandardArgs, StandardSolution};
+use chia_puzzles::P2_DELEGATED_PUZZLE_OR_HIDDEN_PUZZLE;
+use clvm_traits::{clvm_list, clvm_quote, ToClvm};
+use clvm_utils::tree_hash;
+use clvm_utils::CurriedProgram;
+use clvmr::{
+    chia_dialect::ChiaDialect,
+    reduction::Reduction,
+    run_program::run_program,
+    serde::{node_from_bytes, node_from_bytes_backrefs, node_to_bytes},
+    Allocator, NodePtr,
+};
+use common::transaction_block;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+const ITERATIONS: u32 = 20;
+
+fn main() {
+    // Both sides validate the aggregate signature, sharing one pairing cache
+    let bls_cache = Arc::new(BlsCache::default());
+    let parser = BlockParser::new(Network::Mainnet).with_signature_validation(bls_cache.clone());
+    let constants = Network::Mainnet.consensus_constants();
+
+    println!("spends | run twice (ms) | run once (ms)");
+    for spend_count in [10, 100, 500] {
+        let (generator, signature) = standard_spends_generator(spend_count, &constants);
+        let mut block = transaction_block(1, Some(generator));
+        block
+            .transactions_info
+            .as_mut()
+            .unwrap()
+            .aggregated_signature = signature;
+
+        let run_twice = time(|| {
+            assert_eq!(
+                parse_running_twice(&block, &constants, &bls_cache),
+                spend_count as usize
+            );
+        });
+        let run_once = time(|| {
+            let parsed = parser.parse_full_block(&block).unwrap();
+            assert_eq!(parsed.signature_validation, SignatureValidation::Valid);
+        });
+
+        println!(
+            "{spend_count:>6} | {:>14.3} | {:>13.3}",
+            run_twice.as_secs_f64() * 1000.0,
+            run_once.as_secs_f64() * 1000.0
+        );
+    }
+}
+
+/// Read a block's spends the way the parser did before puzzles were run once: validate
+/// with `run_block_generator2`, then run the generator and every puzzle again to read
+/// each spend's conditions, returning the number of spends read
+fn parse_running_twice(
+    block: &FullBlock,
+    constants: &ConsensusConstants,
+    bls_cache: &BlsCache,
+) -> usize {
+    let generator = block.transactions_generator.as_ref().unwrap().as_ref();
+    let signature = &block
+        .transactions_info
+        .as_ref()
+        .unwrap()
+        .aggregated_signature;
+    let max_cost = constants.max_block_cost_clvm;
+    let flags = flags_for_height(block.height(), constants);
+    let mut allocator = make_allocator(clvmr::LIMIT_HEAP);
+
+    run_block_generator2(
+        &mut allocator,
+        generator,
+        Vec::<&[u8]>::new(),
+        max_cost,
+        flags,
+        signature,
+        Some(bls_cache),
+        constants,
+    )
+    .unwrap();
+
+    let generator = node_from_bytes_backrefs(&mut allocator, generator).unwrap();
+    let args = setup_generator_args(&mut allocator, Vec::<&[u8]>::new()).unwrap();
+    let dialect = ChiaDialect::new(flags);
+    let Reduction(_, output) =
+        run_program(&mut allocator, &dialect, generator, args, max_cost).unwrap();
+
+    let mut spends = allocator.next(output).unwrap().0;
+    let mut count = 0;
+    while let Some((spend, rest)) = allocator.next(spends) {
+        spends = rest;
+        let [_parent, puzzle, _amount, solution] = spend_fields(&allocator, spend);
+
+        let _puzzle_hash = tree_hash(&allocator, puzzle);
+        let (_, conditions) =
+            run_puzzle(&mut allocator, puzzle, solution, max_cost, flags).unwrap();
+        parse_conditions(&allocator, conditions);
+        parse_create_coins(&allocator, conditions);
+        identify_puzzle(&allocator, puzzle);
+        node_to_bytes(&allocator, puzzle).unwrap();
+        node_to_bytes(&allocator, solution).unwrap();
+        count += 1;
+    }
+    count
+}
+
+/// Parent, puzzle, amount and solution of a spend in a generator's output
+fn spend_fields(allocator: &Allocator, spend: NodePtr) -> [NodePtr; 4] {
+    let mut fields = [NodePtr::NIL; 4];
+    let mut rest = spend;
+    for field in &mut fields {
+        let (first, next) = allocator.next(rest).unwrap();
+        *field = first;
+        rest = next;
+    }
+    fields
+}
+
+/// Average wall time of `f` over `ITERATIONS` runs, after one warm-up run
+fn time(mut f: impl FnMut()) -> Duration {
+    f();
+    let start = Instant::now();
+    for _ in 0..ITERATIONS {
+        f();
+    }
+    start.elapsed() / ITERATIONS
+}
+
+/// A generator of standard transaction spends, each creating two coins, and the aggregate
+/// signature of their `AGG_SIG_ME` conditions
+fn standard_spends_generator(
+    spend_count: u32,
+    constants: &ConsensusConstants,
+) -> (Vec<u8>, Signature) {
+    let mut allocator = Allocator::new();
+    let standard_mod =
+        node_from_bytes(&mut allocator, &P2_DELEGATED_PUZZLE_OR_HIDDEN_PUZZLE).unwrap();
+    let mut signatures = Vec::new();
+
+    let spends: Vec<NodePtr> = (0..spend_count)
+        .map(|index| {
+            let synthetic_key = SecretKey::from_seed(&index.to_be_bytes().repeat(8));
+            let puzzle = CurriedProgram {
+                program: standard_mod,
+                args: StandardArgs::new(synthetic_key.public_key()),
+            }
+            .to_clvm(&mut allocator)
+            .unwrap();
+
+            let conditions = clvm_list!(
+                clvm_list!(51, Bytes32::new([1; 32]), 600),
+                clvm_list!(51, Bytes32::new([2; 32]), 400)
+            );
+            let delegated_puzzle = clvm_quote!(conditions).to_clvm(&mut allocator).unwrap();
+            let solution = StandardSolution {
+                original_public_key: None,
+                delegated_puzzle,
+                solution: (),
+            }
+            .to_clvm(&mut allocator)
+            .unwrap();
+
+            let mut parent = [0; 32];
+            parent[..4].copy_from_slice(&index.to_be_bytes());
+            let coin = Coin::new(
+                Bytes32::new(parent),
+                tree_hash(&allocator, puzzle).into(),
+                1000,
+            );
+
+            // The standard puzzle signs its delegated puzzle's hash with AGG_SIG_ME
+            let mut message = tree_hash(&allocator, delegated_puzzle).to_vec();
+            message.extend_from_slice(&coin.coin_id());
+            message.extend_from_slice(&constants.agg_sig_me_additional_data);
+            signatures.push(sign(&synthetic_key, message));
+
+            clvm_list!(coin.parent_coin_info, puzzle, coin.amount, solution, ())
+                .to_clvm(&mut allocator)
+                .unwrap()
+        })
+        .collect();
+
+    let generator = clvm_quote!(clvm_list!(spends))
+        .to_clvm(&mut allocator)
+        .unwrap();
+    (
+        node_to_bytes(&allocator, generator).unwrap(),
+        aggregate(&signatures),
+    )
+}
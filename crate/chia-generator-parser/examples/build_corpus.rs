@@ -0,0 +1,427 @@
+//! Write the synthetic blocks of the golden-file corpus in `tests/corpus`
+//!
+//! Run with `cargo run --example build_corpus`, then regenerate the expected output with
+//! `UPDATE_CORPUS=1 cargo test --features serde --test corpus`. The blocks are built
+//! rather than captured, so each one exercises a single kind of spend with valid
+//! consensus conditions. They are named `synthetic_<description>` and parsed with mainnet
+//! rules.
+
+#[path = "../tests/common/mod.rs"]
+mod common;
+
+use chia_bls::SecretKey;
+use chia_protocol::{Bytes, Bytes32, Coin, FullBlock};
+use chia_puzzle_types::{
+    cat::{CatArgs, CatSolution},
+    nft::{
+        NftMetadata, NftOwnershipLayerArgs, NftOwnershipLayerSolution,
+        NftRoyaltyTransferPuzzleArgs, NftStateLayerArgs, NftStateLayerSolution,
+    },
+    singleton::{SingletonArgs, SingletonSolution},
+    standard::{StandardArgs, StandardSolution},
+    CoinProof, EveProof, Proof,
+};
+use chia_puzzles::{
+    CAT_PUZZLE, NFT_OWNERSHIP_LAYER, NFT_OWNERSHIP_TRANSFER_PROGRAM_ONE_WAY_CLAIM_WITH_ROYALTIES,
+    NFT_STATE_LAYER, NFT_STATE_LAYER_HASH, P2_DELEGATED_PUZZLE_OR_HIDDEN_PUZZLE, P2_PARENT_HASH,
+    SINGLETON_LAUNCHER_HASH, SINGLETON_TOP_LAYER_V1_1,
+};
+use chia_traits::streamable::Streamable;
+use clvm_traits::{clvm_list, clvm_quote, ToClvm};
+use clvm_utils::{curry_tree_hash, tree_hash, tree_hash_atom, CurriedProgram};
+use clvmr::{
+    serde::{node_from_bytes, node_to_bytes, node_to_bytes_backrefs},
+    Allocator, NodePtr,
+};
+use common::{non_transaction_block, FARMER_PUZZLE_HASH, POOL_PUZZLE_HASH};
+use std::path::Path;
+
+const HEIGHT: u32 = 5_000_000;
+
+/// Metadata updater of DataLayer stores, which returns the new metadata as-is
+const DL_METADATA_UPDATER: [u8; 1] = [11];
+
+fn main() {
+    let corpus = Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/corpus");
+    let mut allocator = Allocator::new();
+
+    let transaction_generator = {
+        let spends = standard_spends(&mut allocator);
+        generator(&mut allocator, clvm_quote!(clvm_list!(spends)), false)
+    };
+    let compressed_generator = {
+        let ref_spend = ref_puzzle_spend(&mut allocator);
+        let spends = plain_spends(&mut allocator);
+        // `(c (c ref_spend (q . spends)) ())`: the spend list, led by the spend built
+        // from the referenced generator
+        let program = clvm_list!(4, clvm_list!(4, ref_spend, clvm_quote!(spends)), ());
+        generator(&mut allocator, program, true)
+    };
+    let cat_generator = {
+        let spend = cat_spend(&mut allocator);
+        generator(&mut allocator, clvm_quote!(clvm_list!(vec![spend])), false)
+    };
+    let nft_generator = {
+        let spend = nft_spend(&mut allocator);
+        generator(&mut allocator, clvm_quote!(clvm_list!(vec![spend])), false)
+    };
+    let datalayer_generator = {
+        let spends = datalayer_spends(&mut allocator);
+        generator(&mut allocator, clvm_quote!(clvm_list!(spends)), false)
+    };
+
+    let mut compressed = transaction_block(HEIGHT + 2, compressed_generator);
+    compressed.transactions_generator_ref_list = vec![HEIGHT + 1];
+
+    let blocks = [
+        ("synthetic_empty", non_transaction_block(HEIGHT)),
+        (
+            "synthetic_transaction",
+            transaction_block(HEIGHT + 1, transaction_generator),
+        ),
+        ("synthetic_compressed_ref", compressed),
+        (
+            "synthetic_nft",
+            transaction_block(HEIGHT + 3, nft_generator),
+        ),
+        (
+            "synthetic_datalayer",
+            transaction_block(HEIGHT + 4, datalayer_generator),
+        ),
+        (
+            "synthetic_cat",
+            transaction_block(HEIGHT + 5, cat_generator),
+        ),
+    ];
+
+    for (name, block) in blocks {
+        let path = corpus.join(format!("{name}.bin"));
+        std::fs::write(&path, block.to_bytes().unwrap()).unwrap();
+        println!("Wrote {}", path.display());
+    }
+}
+
+/// Serialize a generator program, with back references for compressed blocks
+fn generator(
+    allocator: &mut Allocator,
+    program: impl ToClvm<Allocator>,
+    backrefs: bool,
+) -> Vec<u8> {
+    let program = program.to_clvm(allocator).unwrap();
+    if backrefs {
+        node_to_bytes_backrefs(allocator, program).unwrap()
+    } else {
+        node_to_bytes(allocator, program).unwrap()
+    }
+}
+
+/// A coin spend as listed in a generator's output
+fn coin_spend(
+    allocator: &mut Allocator,
+    parent: Bytes32,
+    puzzle: NodePtr,
+    amount: u64,
+    solution: NodePtr,
+) -> NodePtr {
+    clvm_list!(parent, puzzle, amount, solution, ())
+        .to_clvm(allocator)
+        .unwrap()
+}
+
+fn coin(allocator: &Allocator, parent: Bytes32, puzzle: NodePtr, amount: u64) -> Coin {
+    Coin::new(parent, tree_hash(allocator, puzzle).into(), amount)
+}
+
+/// Standard transaction spends paying a hinted coin and a fee, with AGG_SIG_ME
+fn standard_spends(allocator: &mut Allocator) -> Vec<NodePtr> {
+    let standard_mod = node_from_bytes(allocator, &P2_DELEGATED_PUZZLE_OR_HIDDEN_PUZZLE).unwrap();
+
+    (1u8..=3)
+        .map(|index| {
+            let synthetic_key = SecretKey::from_seed(&[index; 32]).public_key();
+            let puzzle = CurriedProgram {
+                program: standard_mod,
+                args: StandardArgs::new(synthetic_key),
+            }
+            .to_clvm(allocator)
+            .unwrap();
+
+            let recipient = Bytes32::new([0x10 + index; 32]);
+            let conditions = clvm_list!(
+                clvm_list!(51, recipient, 900, clvm_list!(recipient)),
+                clvm_list!(51, Bytes32::new([0x20 + index; 32]), 90),
+                clvm_list!(52, 10)
+            );
+            let solution = StandardSolution {
+                original_public_key: None,
+                delegated_puzzle: clvm_quote!(conditions),
+                solution: (),
+            }
+            .to_clvm(allocator)
+            .unwrap();
+
+            coin_spend(allocator, Bytes32::new([index; 32]), puzzle, 1000, solution)
+        })
+        .collect()
+}
+
+/// A spend whose puzzle reveal is read from the block's first generator ref: the puzzle
+/// of the first spend there, a standard transaction
+///
+/// The generator gets the CLVM deserializer at `2` and its refs at `5`, so
+/// `(a (a 2 (c 9 ())) ())` deserializes the first ref and runs it for its spend list.
+fn ref_puzzle_spend(allocator: &mut Allocator) -> NodePtr {
+    let ref_output = clvm_list!(2, clvm_list!(2, 2, clvm_list!(4, 9, ())), ());
+    let ref_puzzle = clvm_list!(5, clvm_list!(6, clvm_list!(5, clvm_list!(5, ref_output))));
+
+    let conditions = clvm_list!(clvm_list!(51, Bytes32::new([0x34; 32]), 990));
+    let solution = StandardSolution {
+        original_public_key: None,
+        delegated_puzzle: clvm_quote!(conditions),
+        solution: (),
+    };
+
+    // `(c (q . parent) (c ref_puzzle (q . (amount solution ()))))`
+    clvm_list!(
+        4,
+        clvm_quote!(Bytes32::new([0x33; 32])),
+        clvm_list!(4, ref_puzzle, clvm_quote!(clvm_list!(1000, solution, ())))
+    )
+    .to_clvm(allocator)
+    .unwrap()
+}
+
+/// Spends of the `1` puzzle, which returns its solution as conditions: one creates a coin
+/// that the other spends in the same block
+fn plain_spends(allocator: &mut Allocator) -> Vec<NodePtr> {
+    let puzzle = allocator.one();
+    let spent = coin(allocator, Bytes32::new([0x30; 32]), puzzle, 1000);
+    let ephemeral = coin(allocator, spent.coin_id(), puzzle, 500);
+
+    let solution = clvm_list!(
+        clvm_list!(51, ephemeral.puzzle_hash, 500),
+        clvm_list!(
+            51,
+            Bytes32::new([0x31; 32]),
+            500,
+            clvm_list!(Bytes::new(b"memo".to_vec()))
+        )
+    )
+    .to_clvm(allocator)
+    .unwrap();
+    let spend = coin_spend(
+        allocator,
+        spent.parent_coin_info,
+        puzzle,
+        spent.amount,
+        solution,
+    );
+
+    let solution = clvm_list!(clvm_list!(51, Bytes32::new([0x32; 32]), 500))
+        .to_clvm(allocator)
+        .unwrap();
+    let ephemeral_spend = coin_spend(
+        allocator,
+        ephemeral.parent_coin_info,
+        puzzle,
+        ephemeral.amount,
+        solution,
+    );
+
+    vec![spend, ephemeral_spend]
+}
+
+/// Issuance of a CAT whose TAIL is `(q)`, with the `1` inner puzzle
+fn cat_spend(allocator: &mut Allocator) -> NodePtr {
+    let tail = clvm_quote!(()).to_clvm(allocator).unwrap();
+    let asset_id = tree_hash(allocator, tail).into();
+    let inner_puzzle = allocator.one();
+    let inner_puzzle_hash = tree_hash(allocator, inner_puzzle).into();
+
+    let cat_mod = node_from_bytes(allocator, &CAT_PUZZLE).unwrap();
+    let puzzle = CurriedProgram {
+        program: cat_mod,
+        args: CatArgs::new(asset_id, inner_puzzle),
+    }
+    .to_clvm(allocator)
+    .unwrap();
+    let cat = coin(allocator, Bytes32::new([0x40; 32]), puzzle, 1000);
+
+    let recipient = Bytes32::new([0x41; 32]);
+    let inner_solution = clvm_list!(
+        clvm_list!(51, recipient, 1000, clvm_list!(recipient)),
+        clvm_list!(51, (), -113, tail, ())
+    );
+    let solution = CatSolution {
+        inner_puzzle_solution: inner_solution,
+        lineage_proof: None,
+        prev_coin_id: cat.coin_id(),
+        this_coin_info: cat,
+        next_coin_proof: CoinProof {
+            parent_coin_info: cat.parent_coin_info,
+            inner_puzzle_hash,
+            amount: cat.amount,
+        },
+        prev_subtotal: 0,
+        extra_delta: 0,
+    }
+    .to_clvm(allocator)
+    .unwrap();
+
+    coin_spend(
+        allocator,
+        cat.parent_coin_info,
+        puzzle,
+        cat.amount,
+        solution,
+    )
+}
+
+/// The launcher id and eve proof of a singleton launched from `parent_parent`
+fn launcher(parent_parent: Bytes32) -> (Bytes32, Proof) {
+    let launcher = Coin::new(parent_parent, SINGLETON_LAUNCHER_HASH.into(), 1);
+    let proof = Proof::Eve(EveProof {
+        parent_parent_coin_info: parent_parent,
+        parent_amount: 1,
+    });
+    (launcher.coin_id(), proof)
+}
+
+/// Eve spend of a singleton wrapping `inner_puzzle`
+fn singleton_spend(
+    allocator: &mut Allocator,
+    parent_parent: Bytes32,
+    inner_puzzle: NodePtr,
+    inner_solution: NodePtr,
+) -> NodePtr {
+    let (launcher_id, lineage_proof) = launcher(parent_parent);
+    let singleton_mod = node_from_bytes(allocator, &SINGLETON_TOP_LAYER_V1_1).unwrap();
+    let puzzle = CurriedProgram {
+        program: singleton_mod,
+        args: SingletonArgs::new(launcher_id, inner_puzzle),
+    }
+    .to_clvm(allocator)
+    .unwrap();
+    let solution = SingletonSolution {
+        lineage_proof,
+        amount: 1,
+        inner_solution,
+    }
+    .to_clvm(allocator)
+    .unwrap();
+
+    coin_spend(allocator, launcher_id, puzzle, 1, solution)
+}
+
+/// Eve spend of an NFT with royalties, owned by the `1` puzzle
+fn nft_spend(allocator: &mut Allocator) -> NodePtr {
+    let parent_parent = Bytes32::new([0x50; 32]);
+    let (launcher_id, _) = launcher(parent_parent);
+
+    let transfer_mod = node_from_bytes(
+        allocator,
+        &NFT_OWNERSHIP_TRANSFER_PROGRAM_ONE_WAY_CLAIM_WITH_ROYALTIES,
+    )
+    .unwrap();
+    let transfer_program = CurriedProgram {
+        program: transfer_mod,
+        args: NftRoyaltyTransferPuzzleArgs::new(launcher_id, Bytes32::new([0x51; 32]), 300),
+    }
+    .to_clvm(allocator)
+    .unwrap();
+    let owner = allocator.one();
+    let ownership_mod = node_from_bytes(allocator, &NFT_OWNERSHIP_LAYER).unwrap();
+    let ownership = CurriedProgram {
+        program: ownership_mod,
+        args: NftOwnershipLayerArgs::new(None, transfer_program, owner),
+    }
+    .to_clvm(allocator)
+    .unwrap();
+
+    let metadata = NftMetadata {
+        data_uris: vec!["https://example.com/nft.png".to_string()],
+        data_hash: Some(Bytes32::new([0x52; 32])),
+        ..Default::default()
+    };
+    let state_mod = node_from_bytes(allocator, &NFT_STATE_LAYER).unwrap();
+    let state = CurriedProgram {
+        program: state_mod,
+        args: NftStateLayerArgs::new(metadata, ownership),
+    }
+    .to_clvm(allocator)
+    .unwrap();
+
+    let owner_hash = Bytes32::from(tree_hash_atom(&[1]));
+    let inner_solution = NftStateLayerSolution {
+        inner_solution: NftOwnershipLayerSolution {
+            inner_solution: clvm_list!(clvm_list!(51, owner_hash, 1, clvm_list!(owner_hash))),
+        },
+    }
+    .to_clvm(allocator)
+    .unwrap();
+
+    singleton_spend(allocator, parent_parent, state, inner_solution)
+}
+
+/// Eve spend of a DataLayer store updating its root, and a coin announcing a mirror
+fn datalayer_spends(allocator: &mut Allocator) -> Vec<NodePtr> {
+    let parent_parent = Bytes32::new([0x60; 32]);
+    let (launcher_id, _) = launcher(parent_parent);
+    let new_root = Bytes32::new([0x62; 32]);
+
+    let owner = allocator.one();
+    let updater = allocator.new_atom(&DL_METADATA_UPDATER).unwrap();
+    let updater_hash = Bytes32::from(tree_hash_atom(&DL_METADATA_UPDATER));
+    let state_mod = node_from_bytes(allocator, &NFT_STATE_LAYER).unwrap();
+    let state = CurriedProgram {
+        program: state_mod,
+        args: NftStateLayerArgs {
+            mod_hash: NFT_STATE_LAYER_HASH.into(),
+            metadata: Bytes32::new([0x61; 32]),
+            metadata_updater_puzzle_hash: updater_hash,
+            inner_puzzle: owner,
+        },
+    }
+    .to_clvm(allocator)
+    .unwrap();
+
+    // The updater returns its solution: the new metadata and updater puzzle hash, then
+    // extra conditions
+    let updater_solution = clvm_list!(clvm_list!(new_root, updater_hash), ());
+    let owner_hash = Bytes32::from(tree_hash_atom(&[1]));
+    let inner_solution = NftStateLayerSolution {
+        inner_solution: clvm_list!(
+            clvm_list!(51, owner_hash, 1, clvm_list!(launcher_id)),
+            clvm_list!(-24, updater, updater_solution)
+        ),
+    }
+    .to_clvm(allocator)
+    .unwrap();
+    let store_spend = singleton_spend(allocator, parent_parent, state, inner_solution);
+
+    let mirror_puzzle_hash = Bytes32::from(curry_tree_hash(
+        P2_PARENT_HASH.into(),
+        &[tree_hash_atom(&[1])],
+    ));
+    let memos = clvm_list!(launcher_id, Bytes::new(b"https://example.com/dl".to_vec()));
+    let puzzle = allocator.one();
+    let solution = clvm_list!(clvm_list!(51, mirror_puzzle_hash, 1, memos))
+        .to_clvm(allocator)
+        .unwrap();
+    let mirror_spend = coin_spend(allocator, Bytes32::new([0x63; 32]), puzzle, 1, solution);
+
+    vec![store_spend, mirror_spend]
+}
+
+/// A fixture transaction block around `generator`, claiming the previous block's rewards
+fn transaction_block(height: u32, generator: Vec<u8>) -> FullBlock {
+    let mut block = common::transaction_block(height, Some(generator));
+    let reward_claims = vec![
+        Coin::new(Bytes32::new([0x72; 32]), POOL_PUZZLE_HASH, 250_000_000_000),
+        Coin::new(Bytes32::new([0x73; 32]), FARMER_PUZZLE_HASH, 31_250_000_000),
+    ];
+    if let Some(transactions_info) = &mut block.transactions_info {
+        transactions_info.reward_claims_incorporated = reward_claims;
+    }
+    block
+}
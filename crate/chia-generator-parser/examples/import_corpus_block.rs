@@ -0,0 +1,108 @@
+//! Add a block captured from a network to the golden-file corpus in `tests/corpus`
+//!
+//! Run with `cargo run --example import_corpus_block -- <name> <file>`, where `<name>` is
+//! `mainnet_<description>` or `testnet11_<description>` and `<file>` holds the block as
+//! a full node's `get_block` RPC response, the `block` object of one, or the serialized
+//! `FullBlock` in hex. Import the blocks a compressed block refers to first, under the
+//! same network, so its generator refs resolve.
+//!
+//! The block must parse cleanly and pass every commitment check, which a block that
+//! wasn't captured as-is doesn't. Then regenerate the expected output with
+//! `UPDATE_CORPUS=1 cargo test --features serde --test corpus`.
+
+use chia_generator_parser::{BlockParser, InMemoryGeneratorCache, Network};
+use chia_protocol::FullBlock;
+use chia_traits::Streamable;
+use std::path::Path;
+use std::sync::Arc;
+
+fn main() {
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    let [name, file] = args.as_slice() else {
+        eprintln!("usage: import_corpus_block <mainnet|testnet11>_<description> <file>");
+        std::process::exit(2);
+    };
+
+    let network = match name.split('_').next() {
+        Some("mainnet") => Network::Mainnet,
+        Some("testnet11") => Network::Testnet11,
+        _ => fail(&format!("{name} doesn't start with mainnet_ or testnet11_")),
+    };
+    let contents = std::fs::read_to_string(file)
+        .unwrap_or_else(|e| fail(&format!("Failed to read {file}: {e}")));
+    let block = read_block(contents.trim()).unwrap_or_else(|e| fail(&e));
+
+    // Generator refs resolve from the blocks already captured on the same network
+    let corpus = Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/corpus");
+    let source = name.split('_').next().unwrap_or_default();
+    let generator_cache = InMemoryGeneratorCache::new();
+    for entry in std::fs::read_dir(&corpus).unwrap() {
+        let path = entry.unwrap().path();
+        let stem = path.file_stem().unwrap().to_string_lossy();
+        if path.extension().is_some_and(|extension| extension == "bin")
+            && stem.split('_').next() == Some(source)
+        {
+            let bytes = std::fs::read(&path).unwrap();
+            generator_cache.insert_block(&FullBlock::from_bytes(&bytes).unwrap());
+        }
+    }
+
+    let parser = BlockParser::new(network).with_generator_lookup(Arc::new(generator_cache));
+    let parsed = parser
+        .parse_full_block(&block)
+        .unwrap_or_else(|e| fail(&format!("Failed to parse the block: {e}")));
+    if !parsed.diagnostics.is_clean() {
+        fail(&format!(
+            "The block didn't parse cleanly: {:?}",
+            parsed.diagnostics.failures
+        ));
+    }
+
+    let verification = parser
+        .verify_block(&block, &parsed)
+        .unwrap_or_else(|e| fail(&format!("Failed to verify the block: {e}")));
+    if !verification.is_valid() {
+        fail(&format!(
+            "The block doesn't match its commitments, failed: {:?}, unverifiable: {:?}",
+            verification.failed_checks(),
+            verification.unverifiable_checks()
+        ));
+    }
+
+    let path = corpus.join(format!("{name}.bin"));
+    std::fs::write(&path, block.to_bytes().unwrap()).unwrap();
+    println!(
+        "Wrote block {} ({}) to {}: {} spends, {} generator refs",
+        parsed.height,
+        parsed.header_hash,
+        path.display(),
+        parsed.coin_spends.len(),
+        block.transactions_generator_ref_list.len()
+    );
+}
+
+/// A block from a `get_block` response, its `block` object, or hex
+fn read_block(contents: &str) -> Result<FullBlock, String> {
+    if !contents.starts_with('{') {
+        let bytes = hex::decode(contents.trim_start_matches("0x"))
+            .map_err(|e| format!("Failed to decode the block hex: {e}"))?;
+        return FullBlock::from_bytes(&bytes)
+            .map_err(|e| format!("Failed to deserialize the block: {e}"));
+    }
+
+    // Read straight into the block, as `serde_json::Value` can't hold its u128 fields
+    if let Ok(response) = serde_json::from_str::<GetBlockResponse>(contents) {
+        return Ok(response.block);
+    }
+    serde_json::from_str(contents).map_err(|e| format!("Failed to read the block JSON: {e}"))
+}
+
+#[derive(serde::Deserialize)]
+struct GetBlockResponse {
+    block: FullBlock,
+}
+
+fn fail(message: &str) -> ! {
+    eprintln!("{message}");
+    std::process::exit(1);
+}
@@ -0,0 +1,116 @@
+use crate::{
+    puzzles::puzzle_mod_hash,
+    types::{CoinSpendInfo, PuzzleModUsage, PuzzleRevealSize},
+};
+use chia_protocol::Bytes32;
+use clvmr::{
+    serde::{node_from_bytes, node_from_bytes_backrefs_record, serialized_length, ObjectCache},
+    Allocator,
+};
+use std::collections::HashMap;
+
+/// Number of puzzle reveals listed in `GeneratorAnalysis::largest_puzzle_reveals`
+pub const LARGEST_PUZZLE_REVEALS: usize = 10;
+
+/// Whether serialized CLVM uses back references, and its size without them; `None` when
+/// it doesn't deserialize
+pub fn back_reference_stats(program: &[u8]) -> Option<(bool, u64)> {
+    let mut allocator = Allocator::new();
+    let (node, backrefs) = node_from_bytes_backrefs_record(&mut allocator, program).ok()?;
+    let mut lengths = ObjectCache::new(serialized_length);
+    let expanded_size = *lengths.get_or_calculate(&allocator, &node, None)?;
+    Some((!backrefs.is_empty(), expanded_size))
+}
+
+/// The `count` largest puzzle reveals of `spends`, largest first
+pub fn largest_puzzle_reveals(spends: &[CoinSpendInfo], count: usize) -> Vec<PuzzleRevealSize> {
+    let mut reveals: Vec<PuzzleRevealSize> = spends
+        .iter()
+        .map(|spend| PuzzleRevealSize {
+            coin_id: spend.coin.coin_id,
+            puzzle_hash: spend.coin.puzzle_hash,
+            size_bytes: spend.puzzle_reveal.len() as u32,
+        })
+        .collect();
+    // Stable, so equal sizes keep spend order
+    reveals.sort_by_key(|reveal| std::cmp::Reverse(reveal.size_bytes));
+    reveals.truncate(count);
+    reveals
+}
+
+/// Outermost puzzle mods revealed by more than one of `spends`, most revealed first
+pub fn repeated_puzzle_mods(spends: &[CoinSpendInfo]) -> Vec<PuzzleModUsage> {
+    let mut allocator = Allocator::new();
+    let mut usage: HashMap<Bytes32, PuzzleModUsage> = HashMap::new();
+    for spend in spends {
+        let Ok(puzzle) = node_from_bytes(&mut allocator, spend.puzzle_reveal.as_ref()) else {
+            continue;
+        };
+        let mod_hash = puzzle_mod_hash(&allocator, puzzle);
+        let entry = usage.entry(mod_hash).or_insert_with(|| PuzzleModUsage {
+            mod_hash,
+            kind: spend.puzzle_kind.name().to_string(),
+            spend_count: 0,
+            reveal_bytes: 0,
+        });
+        entry.spend_count += 1;
+        entry.reveal_bytes += spend.puzzle_reveal.len() as u64;
+    }
+
+    let mut repeated: Vec<PuzzleModUsage> = usage
+        .into_values()
+        .filter(|usage| usage.spend_count > 1)
+        .collect();
+    repeated.sort_by(|a, b| {
+        b.spend_count
+            .cmp(&a.spend_count)
+            .then(b.reveal_bytes.cmp(&a.reveal_bytes))
+            .then(a.mod_hash.cmp(&b.mod_hash))
+    });
+    repeated
+}
+
+/// Shannon entropy of `data`, in bits per byte
+pub fn entropy(data: &[u8]) -> f64 {
+    if data.is_empty() {
+        return 0.0;
+    }
+
+    let mut freq = [0u32; 256];
+    for &byte in data {
+        freq[byte as usize] += 1;
+    }
+
+    let len = data.len() as f64;
+    freq.iter()
+        .filter(|&&count| count > 0)
+        .map(|&count| {
+            let p = count as f64 / len;
+            -p * p.log2()
+        })
+        .sum()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_back_reference_stats() {
+        // (a . a) with the second atom a back reference to the first
+        let atom = [&[0x8a][..], &[7; 10]].concat();
+        let plain = [&[0xff][..], &atom, &atom].concat();
+        let backref = [&[0xff][..], &atom, &[0xfe, 0x02]].concat();
+
+        assert_eq!(back_reference_stats(&plain), Some((false, 23)));
+        assert_eq!(back_reference_stats(&backref), Some((true, 23)));
+        assert_eq!(back_reference_stats(&[0xff]), None);
+    }
+
+    #[test]
+    fn test_entropy() {
+        assert_eq!(entropy(&[]), 0.0);
+        assert_eq!(entropy(&[7; 100]), 0.0);
+        assert_eq!(entropy(&[0, 1, 2, 3]), 2.0);
+    }
+}
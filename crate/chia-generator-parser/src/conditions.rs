@@ -0,0 +1,482 @@
+use crate::types::{AggSigKind, ConditionInfo};
+use chia_bls::PublicKey;
+use chia_consensus::{
+    opcodes::*,
+    validation_error::{atom, first, next, ErrorCode, ValidationErr},
+};
+use chia_protocol::{Bytes, Bytes32};
+use clvmr::{
+    chia_dialect::ChiaDialect, cost::Cost, reduction::Reduction, run_program::run_program,
+    serde::node_to_bytes, Allocator, NodePtr,
+};
+
+/// Run a coin's puzzle reveal with its solution, returning the cost and the condition list
+///
+/// Failures are consensus `ValidationErr`s, so a block's spends can fail validation with
+/// the same error `run_block_generator2` would report.
+pub fn run_puzzle(
+    allocator: &mut Allocator,
+    puzzle: NodePtr,
+    solution: NodePtr,
+    max_cost: Cost,
+    flags: u32,
+) -> std::result::Result<(Cost, NodePtr), ValidationErr> {
+    let dialect = ChiaDialect::new(flags);
+    let Reduction(cost, output) = run_program(allocator, &dialect, puzzle, solution, max_cost)?;
+    Ok((cost, output))
+}
+
+/// Parse the condition list output by a coin spend into typed entries
+///
+/// CREATE_COIN is reported through `created_coins` instead. Opcodes consensus doesn't
+/// assign a condition to, which softforks may give meaning later, are skipped, as are
+/// conditions with malformed arguments.
+pub fn parse_conditions(allocator: &Allocator, conditions: NodePtr) -> Vec<ConditionInfo> {
+    let mut parsed = Vec::new();
+    let mut iter = conditions;
+
+    while let Ok(Some((condition, next_iter))) = next(allocator, iter) {
+        iter = next_iter;
+
+        if let Some(info) = parse_condition(allocator, condition) {
+            parsed.push(info);
+        }
+    }
+
+    parsed
+}
+
+/// A CREATE_COIN condition together with its memos
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CreateCoinCondition {
+    pub puzzle_hash: Bytes32,
+    pub amount: u64,
+    pub memos: Vec<Vec<u8>>,
+}
+
+/// Collect the CREATE_COIN conditions in a condition list
+///
+/// `SpendConditions` keeps only the hint, so memos are read from the puzzle output.
+pub fn parse_create_coins(allocator: &Allocator, conditions: NodePtr) -> Vec<CreateCoinCondition> {
+    let mut create_coins = Vec::new();
+    let mut iter = conditions;
+
+    while let Ok(Some((condition, next_iter))) = next(allocator, iter) {
+        iter = next_iter;
+
+        if let Some(create_coin) = parse_create_coin(allocator, condition) {
+            create_coins.push(create_coin);
+        }
+    }
+
+    create_coins
+}
+
+fn parse_create_coin(allocator: &Allocator, condition: NodePtr) -> Option<CreateCoinCondition> {
+    let opcode_node = first(allocator, condition).ok()?;
+    if atom_bytes(allocator, opcode_node)? != [CREATE_COIN as u8] {
+        return None;
+    }
+
+    let args = condition_args(allocator, condition);
+    let puzzle_hash = Bytes32::try_from(atom_bytes(allocator, *args.first()?)?).ok()?;
+    let amount = atom_u64(allocator, *args.get(1)?)?;
+
+    // Memos are optional; anything other than a list of atoms is ignored
+    let mut memos = Vec::new();
+    if let Some(&memo_list) = args.get(2) {
+        let mut iter = memo_list;
+        while let Ok(Some((memo, next_iter))) = next(allocator, iter) {
+            iter = next_iter;
+            if let Some(bytes) = atom_bytes(allocator, memo) {
+                memos.push(bytes);
+            }
+        }
+    }
+
+    Some(CreateCoinCondition {
+        puzzle_hash,
+        amount,
+        memos,
+    })
+}
+
+fn parse_condition(allocator: &Allocator, condition: NodePtr) -> Option<ConditionInfo> {
+    let opcode_node = first(allocator, condition).ok()?;
+    let opcode: ConditionOpcode = match atom_bytes(allocator, opcode_node)?.as_slice() {
+        [op] => (*op).into(),
+        _ => return None,
+    };
+    let args = condition_args(allocator, condition);
+    let arg = |index: usize| args.get(index).copied();
+
+    let info = match opcode {
+        AGG_SIG_PARENT
+        | AGG_SIG_PUZZLE
+        | AGG_SIG_AMOUNT
+        | AGG_SIG_PUZZLE_AMOUNT
+        | AGG_SIG_PARENT_AMOUNT
+        | AGG_SIG_PARENT_PUZZLE
+        | AGG_SIG_UNSAFE
+        | AGG_SIG_ME => {
+            let public_key = <[u8; 48]>::try_from(atom_bytes(allocator, arg(0)?)?).ok()?;
+            ConditionInfo::AggSig {
+                kind: agg_sig_kind(opcode)?,
+                public_key: PublicKey::from_bytes(&public_key).ok()?,
+                message: atom_bytes(allocator, arg(1)?)?.into(),
+            }
+        }
+        RESERVE_FEE => ConditionInfo::ReserveFee {
+            amount: atom_u64(allocator, arg(0)?)?,
+        },
+        CREATE_COIN_ANNOUNCEMENT => ConditionInfo::CreateCoinAnnouncement {
+            message: atom_bytes(allocator, arg(0)?)?.into(),
+        },
+        ASSERT_COIN_ANNOUNCEMENT => ConditionInfo::AssertCoinAnnouncement {
+            announcement_id: atom_hash(allocator, arg(0)?)?,
+        },
+        CREATE_PUZZLE_ANNOUNCEMENT => ConditionInfo::CreatePuzzleAnnouncement {
+            message: atom_bytes(allocator, arg(0)?)?.into(),
+        },
+        ASSERT_PUZZLE_ANNOUNCEMENT => ConditionInfo::AssertPuzzleAnnouncement {
+            announcement_id: atom_hash(allocator, arg(0)?)?,
+        },
+        ASSERT_CONCURRENT_SPEND => ConditionInfo::AssertConcurrentSpend {
+            coin_id: atom_hash(allocator, arg(0)?)?,
+        },
+        ASSERT_CONCURRENT_PUZZLE => ConditionInfo::AssertConcurrentPuzzle {
+            puzzle_hash: atom_hash(allocator, arg(0)?)?,
+        },
+        SEND_MESSAGE | RECEIVE_MESSAGE => {
+            let mode = atom_u64(allocator, arg(0)?)?;
+            let message = atom_bytes(allocator, arg(1)?)?.into();
+            let counterparty = args[2..]
+                .iter()
+                .filter_map(|&node| atom_bytes(allocator, node).map(Bytes::from))
+                .collect();
+            let mode = u8::try_from(mode).ok()?;
+            if opcode == SEND_MESSAGE {
+                ConditionInfo::SendMessage {
+                    mode,
+                    message,
+                    counterparty,
+                }
+            } else {
+                ConditionInfo::ReceiveMessage {
+                    mode,
+                    message,
+                    counterparty,
+                }
+            }
+        }
+        ASSERT_SECONDS_RELATIVE => ConditionInfo::AssertSecondsRelative {
+            seconds: atom_timelock_u64(allocator, arg(0)?)?,
+        },
+        ASSERT_SECONDS_ABSOLUTE => ConditionInfo::AssertSecondsAbsolute {
+            seconds: atom_timelock_u64(allocator, arg(0)?)?,
+        },
+        ASSERT_HEIGHT_RELATIVE => ConditionInfo::AssertHeightRelative {
+            height: atom_timelock_u32(allocator, arg(0)?)?,
+        },
+        ASSERT_HEIGHT_ABSOLUTE => ConditionInfo::AssertHeightAbsolute {
+            height: atom_timelock_u32(allocator, arg(0)?)?,
+        },
+        ASSERT_BEFORE_SECONDS_RELATIVE => ConditionInfo::AssertBeforeSecondsRelative {
+            seconds: atom_u64(allocator, arg(0)?)?,
+        },
+        ASSERT_BEFORE_SECONDS_ABSOLUTE => ConditionInfo::AssertBeforeSecondsAbsolute {
+            seconds: atom_u64(allocator, arg(0)?)?,
+        },
+        ASSERT_BEFORE_HEIGHT_RELATIVE => ConditionInfo::AssertBeforeHeightRelative {
+            height: atom_u32(allocator, arg(0)?)?,
+        },
+        ASSERT_BEFORE_HEIGHT_ABSOLUTE => ConditionInfo::AssertBeforeHeightAbsolute {
+            height: atom_u32(allocator, arg(0)?)?,
+        },
+        ASSERT_MY_COIN_ID => ConditionInfo::AssertMyCoinId {
+            coin_id: atom_hash(allocator, arg(0)?)?,
+        },
+        ASSERT_MY_PARENT_ID => ConditionInfo::AssertMyParentId {
+            parent_id: atom_hash(allocator, arg(0)?)?,
+        },
+        ASSERT_MY_PUZZLEHASH => ConditionInfo::AssertMyPuzzleHash {
+            puzzle_hash: atom_hash(allocator, arg(0)?)?,
+        },
+        ASSERT_MY_AMOUNT => ConditionInfo::AssertMyAmount {
+            amount: atom_u64(allocator, arg(0)?)?,
+        },
+        ASSERT_MY_BIRTH_SECONDS => ConditionInfo::AssertMyBirthSeconds {
+            seconds: atom_u64(allocator, arg(0)?)?,
+        },
+        ASSERT_MY_BIRTH_HEIGHT => ConditionInfo::AssertMyBirthHeight {
+            height: atom_u32(allocator, arg(0)?)?,
+        },
+        ASSERT_EPHEMERAL => ConditionInfo::AssertEphemeral,
+        REMARK => {
+            let (_, args) = allocator.next(condition)?;
+            ConditionInfo::Remark {
+                args: node_to_bytes(allocator, args).ok()?.into(),
+            }
+        }
+        _ => return None,
+    };
+
+    Some(info)
+}
+
+fn agg_sig_kind(opcode: ConditionOpcode) -> Option<AggSigKind> {
+    match opcode {
+        AGG_SIG_PARENT => Some(AggSigKind::Parent),
+        AGG_SIG_PUZZLE => Some(AggSigKind::Puzzle),
+        AGG_SIG_AMOUNT => Some(AggSigKind::Amount),
+        AGG_SIG_PUZZLE_AMOUNT => Some(AggSigKind::PuzzleAmount),
+        AGG_SIG_PARENT_AMOUNT => Some(AggSigKind::ParentAmount),
+        AGG_SIG_PARENT_PUZZLE => Some(AggSigKind::ParentPuzzle),
+        AGG_SIG_UNSAFE => Some(AggSigKind::Unsafe),
+        AGG_SIG_ME => Some(AggSigKind::Me),
+        _ => None,
+    }
+}
+
+/// Collect the arguments following the opcode of a condition
+fn condition_args(allocator: &Allocator, condition: NodePtr) -> Vec<NodePtr> {
+    let mut args = Vec::new();
+    let Ok(Some((_, mut iter))) = next(allocator, condition) else {
+        return args;
+    };
+
+    while let Ok(Some((arg, next_iter))) = next(allocator, iter) {
+        args.push(arg);
+        iter = next_iter;
+    }
+
+    args
+}
+
+fn atom_bytes(allocator: &Allocator, node: NodePtr) -> Option<Vec<u8>> {
+    atom(allocator, node, ErrorCode::InvalidCondition)
+        .ok()
+        .map(|a| a.as_ref().to_vec())
+}
+
+fn atom_hash(allocator: &Allocator, node: NodePtr) -> Option<Bytes32> {
+    Bytes32::try_from(atom_bytes(allocator, node)?).ok()
+}
+
+/// Decode a non-negative CLVM integer that fits in a u64
+fn atom_u64(allocator: &Allocator, node: NodePtr) -> Option<u64> {
+    let bytes = atom_bytes(allocator, node)?;
+    if bytes.first().is_some_and(|b| b & 0x80 != 0) {
+        return None;
+    }
+
+    let significant = match bytes.iter().position(|&b| b != 0) {
+        Some(start) => &bytes[start..],
+        None => &[],
+    };
+    if significant.len() > 8 {
+        return None;
+    }
+
+    Some(
+        significant
+            .iter()
+            .fold(0u64, |acc, &b| (acc << 8) | u64::from(b)),
+    )
+}
+
+fn atom_u32(allocator: &Allocator, node: NodePtr) -> Option<u32> {
+    atom_u64(allocator, node).and_then(|v| u32::try_from(v).ok())
+}
+
+/// Decode a timelock that asserts a minimum, where a negative value is always satisfied
+///
+/// Consensus skips these conditions rather than failing the spend, so they're reported
+/// as `0`.
+fn atom_timelock_u64(allocator: &Allocator, node: NodePtr) -> Option<u64> {
+    let bytes = atom_bytes(allocator, node)?;
+    if bytes.first().is_some_and(|b| b & 0x80 != 0) {
+        return Some(0);
+    }
+    atom_u64(allocator, node)
+}
+
+fn atom_timelock_u32(allocator: &Allocator, node: NodePtr) -> Option<u32> {
+    atom_timelock_u64(allocator, node).and_then(|v| u32::try_from(v).ok())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use clvmr::serde::node_from_bytes;
+
+    #[test]
+    fn test_parse_create_coin_memos() {
+        let mut allocator = Allocator::new();
+        let hint = "11".repeat(32);
+        // ((51 0x22..22 1000 (0x11..11 0xbeef)))
+        let serialized = [
+            "ffff33ffa0",
+            &"22".repeat(32),
+            "ff8203e8ffffa0",
+            &hint,
+            "ff82beef808080",
+        ]
+        .concat();
+        let conditions =
+            node_from_bytes(&mut allocator, &hex::decode(serialized).unwrap()).unwrap();
+
+        let create_coins = parse_create_coins(&allocator, conditions);
+
+        assert_eq!(create_coins.len(), 1);
+        assert_eq!(create_coins[0].puzzle_hash, Bytes32::new([0x22; 32]));
+        assert_eq!(create_coins[0].amount, 1000);
+        assert_eq!(
+            create_coins[0].memos,
+            vec![hex::decode(&hint).unwrap(), vec![0xbe, 0xef]]
+        );
+    }
+
+    #[test]
+    fn test_parse_timelock_and_announcement_conditions() {
+        let mut allocator = Allocator::new();
+        // ((83 1000) (80 60) (60 0xcafe) (52 5) (51 0x00..00 1) (999))
+        let serialized = [
+            "ff",
+            "ff53ff8203e880",
+            "ff",
+            "ff50ff3c80",
+            "ff",
+            "ff3cff82cafe80",
+            "ff",
+            "ff34ff0580",
+            "ff",
+            "ff33ffa0",
+            &"00".repeat(32),
+            "ff0180",
+            "ff",
+            "ff8203e780",
+            "80",
+        ]
+        .concat();
+        let conditions =
+            node_from_bytes(&mut allocator, &hex::decode(serialized).unwrap()).unwrap();
+
+        let parsed = parse_conditions(&allocator, conditions);
+
+        let create_coins = parse_create_coins(&allocator, conditions);
+        assert_eq!(
+            create_coins,
+            vec![CreateCoinCondition {
+                puzzle_hash: Bytes32::default(),
+                amount: 1,
+                memos: Vec::new(),
+            }]
+        );
+
+        assert_eq!(
+            parsed,
+            vec![
+                ConditionInfo::AssertHeightAbsolute { height: 1000 },
+                ConditionInfo::AssertSecondsRelative { seconds: 60 },
+                ConditionInfo::CreateCoinAnnouncement {
+                    message: Bytes::new(vec![0xca, 0xfe])
+                },
+                ConditionInfo::ReserveFee { amount: 5 },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_parse_assert_my_and_remark_conditions() {
+        let mut allocator = Allocator::new();
+        // ((70 0x11..11) (71 0x22..22) (72 0x33..33) (73 1000) (74 60) (75 5) (76)
+        //  (1 "hi" (1 2)) (99 1))
+        let serialized = [
+            "ff",
+            "ff46ffa0",
+            &"11".repeat(32),
+            "80",
+            "ff",
+            "ff47ffa0",
+            &"22".repeat(32),
+            "80",
+            "ff",
+            "ff48ffa0",
+            &"33".repeat(32),
+            "80",
+            "ff",
+            "ff49ff8203e880",
+            "ff",
+            "ff4aff3c80",
+            "ff",
+            "ff4bff0580",
+            "ff",
+            "ff4c80",
+            "ff",
+            "ff01ff826869ffff01ff028080",
+            "ff",
+            "ff63ff0180",
+            "80",
+        ]
+        .concat();
+        let conditions =
+            node_from_bytes(&mut allocator, &hex::decode(serialized).unwrap()).unwrap();
+
+        // Opcode 99 isn't assigned, so it's skipped
+        assert_eq!(
+            parse_conditions(&allocator, conditions),
+            vec![
+                ConditionInfo::AssertMyCoinId {
+                    coin_id: Bytes32::new([0x11; 32])
+                },
+                ConditionInfo::AssertMyParentId {
+                    parent_id: Bytes32::new([0x22; 32])
+                },
+                ConditionInfo::AssertMyPuzzleHash {
+                    puzzle_hash: Bytes32::new([0x33; 32])
+                },
+                ConditionInfo::AssertMyAmount { amount: 1000 },
+                ConditionInfo::AssertMyBirthSeconds { seconds: 60 },
+                ConditionInfo::AssertMyBirthHeight { height: 5 },
+                ConditionInfo::AssertEphemeral,
+                ConditionInfo::Remark {
+                    args: Bytes::new(hex::decode("ff826869ffff01ff028080").unwrap())
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_negative_timelocks_are_zero() {
+        let mut allocator = Allocator::new();
+        // ((80 -1) (82 -60) (81 -5) (83 -1000) (85 -1))
+        let serialized = [
+            "ff",
+            "ff50ff81ff80",
+            "ff",
+            "ff52ff81c480",
+            "ff",
+            "ff51ff81fb80",
+            "ff",
+            "ff53ff82fc1880",
+            "ff",
+            "ff55ff81ff80",
+            "80",
+        ]
+        .concat();
+        let conditions =
+            node_from_bytes(&mut allocator, &hex::decode(serialized).unwrap()).unwrap();
+
+        // A negative ASSERT_BEFORE_* can never be satisfied, so it isn't reported
+        assert_eq!(
+            parse_conditions(&allocator, conditions),
+            vec![
+                ConditionInfo::AssertSecondsRelative { seconds: 0 },
+                ConditionInfo::AssertHeightRelative { height: 0 },
+                ConditionInfo::AssertSecondsAbsolute { seconds: 0 },
+                ConditionInfo::AssertHeightAbsolute { height: 0 },
+            ]
+        );
+    }
+}
@@ -0,0 +1,333 @@
+use crate::{
+    conditions::{parse_create_coins, run_puzzle},
+    puzzles::{dl_root_hash, serialize, DL_METADATA_UPDATER},
+    types::{CoinInfo, CoinSpendInfo, DataStoreMirror, DataStoreUpdate, PuzzleKind},
+};
+use chia_consensus::validation_error::{atom, first, next, rest, ErrorCode};
+use chia_protocol::{Bytes32, Program};
+use chia_puzzle_types::{
+    nft::{NftStateLayerArgs, NftStateLayerSolution},
+    singleton::{LauncherSolution, SingletonArgs, SingletonSolution},
+};
+use chia_puzzles::{NFT_STATE_LAYER_HASH, P2_PARENT_HASH};
+use clvm_traits::FromClvm;
+use clvm_utils::{curry_tree_hash, tree_hash_atom, CurriedProgram, ToTreeHash, TreeHash};
+use clvmr::{serde::node_from_bytes, Allocator, NodePtr};
+
+/// Opcode of the condition that asks the NFT state layer to run its metadata updater
+const UPDATE_METADATA_OPCODE: [u8; 1] = [0xe8]; // -24
+
+/// Key-value list of a DataLayer launcher solution: the initial root and owner puzzle hash
+#[derive(FromClvm)]
+#[clvm(list)]
+struct DataStoreLaunchInfo {
+    root_hash: Bytes32,
+    inner_puzzle_hash: Bytes32,
+}
+
+/// Find the DataLayer stores launched or updated by a block's coin spends
+pub fn find_data_store_updates(
+    coin_spends: &[CoinSpendInfo],
+    max_cost: u64,
+    flags: u32,
+) -> Vec<DataStoreUpdate> {
+    coin_spends
+        .iter()
+        .filter_map(|spend| match &spend.puzzle_kind {
+            PuzzleKind::SingletonLauncher => data_store_launch(spend),
+            PuzzleKind::Singleton {
+                launcher_id, inner, ..
+            } => match inner.as_ref() {
+                PuzzleKind::DataLayer {
+                    root_hash,
+                    metadata,
+                    ..
+                } => data_store_spend(spend, *launcher_id, *root_hash, metadata, max_cost, flags),
+                _ => None,
+            },
+            _ => None,
+        })
+        .collect()
+}
+
+/// Find the DataLayer mirror coins created in a block
+///
+/// Mirrors are created with the store's launcher ID as the first memo, followed by the URLs.
+pub fn find_data_store_mirrors(coin_creations: &[CoinInfo]) -> Vec<DataStoreMirror> {
+    let mirror_puzzle_hash = Bytes32::from(mirror_puzzle_hash());
+
+    coin_creations
+        .iter()
+        .filter(|coin| coin.puzzle_hash == mirror_puzzle_hash)
+        .filter_map(|coin| {
+            let (launcher_id, urls) = coin.memos.split_first()?;
+
+            Some(DataStoreMirror {
+                coin_id: coin.coin_id,
+                launcher_id: Bytes32::try_from(launcher_id).ok()?,
+                amount: coin.amount,
+                urls: urls
+                    .iter()
+                    .filter_map(|url| String::from_utf8(url.to_vec()).ok())
+                    .collect(),
+            })
+        })
+        .collect()
+}
+
+/// Puzzle hash of DataLayer mirror coins: p2_parent curried with `1`
+fn mirror_puzzle_hash() -> TreeHash {
+    curry_tree_hash(P2_PARENT_HASH.into(), &[tree_hash_atom(&[1])])
+}
+
+/// Full puzzle hash of a DataLayer store, without the singleton top layer
+fn data_store_puzzle_hash(metadata: TreeHash, inner_puzzle_hash: TreeHash) -> TreeHash {
+    CurriedProgram {
+        program: TreeHash::new(NFT_STATE_LAYER_HASH),
+        args: NftStateLayerArgs {
+            mod_hash: NFT_STATE_LAYER_HASH.into(),
+            metadata,
+            metadata_updater_puzzle_hash: tree_hash_atom(&DL_METADATA_UPDATER).into(),
+            inner_puzzle: inner_puzzle_hash,
+        },
+    }
+    .tree_hash()
+}
+
+/// A launcher spend creates a store when its solution commits to a DataLayer singleton
+fn data_store_launch(spend: &CoinSpendInfo) -> Option<DataStoreUpdate> {
+    let mut allocator = Allocator::new();
+    let solution = decode_program(&mut allocator, &spend.solution)?;
+    let solution = LauncherSolution::<DataStoreLaunchInfo>::from_clvm(&allocator, solution).ok()?;
+    let launch = solution.key_value_list;
+
+    let launcher_id = spend.coin.coin_id;
+    let store_puzzle_hash = data_store_puzzle_hash(
+        tree_hash_atom(&launch.root_hash),
+        launch.inner_puzzle_hash.into(),
+    );
+    if SingletonArgs::curry_tree_hash(launcher_id, store_puzzle_hash)
+        != solution.singleton_puzzle_hash.into()
+    {
+        return None;
+    }
+
+    let metadata = allocator.new_atom(&launch.root_hash).ok()?;
+
+    Some(DataStoreUpdate {
+        launcher_id,
+        coin_id: spend.coin.coin_id,
+        new_root_hash: Some(launch.root_hash),
+        previous_root_hash: None,
+        metadata: serialize(&allocator, metadata),
+        owner_puzzle_hash: Some(launch.inner_puzzle_hash),
+    })
+}
+
+/// Run a store's owner puzzle to find the new metadata and owner it commits to
+fn data_store_spend(
+    spend: &CoinSpendInfo,
+    launcher_id: Bytes32,
+    previous_root_hash: Option<Bytes32>,
+    previous_metadata: &Program,
+    max_cost: u64,
+    flags: u32,
+) -> Option<DataStoreUpdate> {
+    let mut allocator = Allocator::new();
+    let puzzle = decode_program(&mut allocator, &spend.puzzle_reveal)?;
+    let solution = decode_program(&mut allocator, &spend.solution)?;
+
+    let singleton =
+        CurriedProgram::<NodePtr, SingletonArgs<NodePtr>>::from_clvm(&allocator, puzzle).ok()?;
+    let state = CurriedProgram::<NodePtr, NftStateLayerArgs<NodePtr, NodePtr>>::from_clvm(
+        &allocator,
+        singleton.args.inner_puzzle,
+    )
+    .ok()?;
+    let solution =
+        SingletonSolution::<NftStateLayerSolution<NodePtr>>::from_clvm(&allocator, solution)
+            .ok()?;
+
+    let (_, output) = run_puzzle(
+        &mut allocator,
+        state.args.inner_puzzle,
+        solution.inner_solution.inner_solution,
+        max_cost,
+        flags,
+    )
+    .ok()?;
+
+    // The singleton is recreated by the odd CREATE_COIN; without one the store is melted
+    let owner_puzzle_hash = parse_create_coins(&allocator, output)
+        .into_iter()
+        .find(|create_coin| create_coin.amount % 2 == 1)
+        .map(|create_coin| create_coin.puzzle_hash);
+
+    let (new_root_hash, metadata) = match new_metadata(&allocator, output) {
+        Some(metadata) => (
+            dl_root_hash(&allocator, metadata),
+            serialize(&allocator, metadata),
+        ),
+        None => (previous_root_hash, previous_metadata.clone()),
+    };
+
+    Some(DataStoreUpdate {
+        launcher_id,
+        coin_id: spend.coin.coin_id,
+        new_root_hash,
+        previous_root_hash,
+        metadata,
+        owner_puzzle_hash,
+    })
+}
+
+/// New metadata from a `(-24 updater updater_solution)` condition
+///
+/// The DataLayer updater returns its solution unchanged, so the new metadata is the
+/// first item of the updater solution.
+fn new_metadata(allocator: &Allocator, conditions: NodePtr) -> Option<NodePtr> {
+    let mut iter = conditions;
+
+    while let Ok(Some((condition, next_iter))) = next(allocator, iter) {
+        iter = next_iter;
+
+        let Ok(opcode) = first(allocator, condition) else {
+            continue;
+        };
+        let is_update = atom(allocator, opcode, ErrorCode::InvalidConditionOpcode)
+            .is_ok_and(|opcode| opcode.as_ref() == UPDATE_METADATA_OPCODE);
+        if !is_update {
+            continue;
+        }
+
+        let args = rest(allocator, condition).ok()?;
+        let updater_solution = first(allocator, rest(allocator, args).ok()?).ok()?;
+        return first(allocator, updater_solution).ok();
+    }
+
+    None
+}
+
+fn decode_program(allocator: &mut Allocator, program: &Program) -> Option<NodePtr> {
+    node_from_bytes(allocator, program.as_ref()).ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::puzzles::identify_puzzle;
+    use chia_puzzle_types::{EveProof, Proof};
+    use chia_puzzles::{NFT_STATE_LAYER, SINGLETON_TOP_LAYER_V1_1};
+    use clvm_traits::{clvm_list, ToClvm};
+    use clvm_utils::tree_hash;
+    use clvmr::serde::node_to_bytes;
+
+    #[test]
+    fn test_find_data_store_updates() {
+        let mut allocator = Allocator::new();
+        let launcher_id = Bytes32::new([3; 32]);
+        let previous_root = Bytes32::new([4; 32]);
+        let new_root = Bytes32::new([5; 32]);
+        let new_owner = Bytes32::new([6; 32]);
+
+        // The `1` owner puzzle returns its solution as the condition list
+        let owner = allocator.one();
+        let updater = allocator.new_atom(&DL_METADATA_UPDATER).unwrap();
+        let state_mod = node_from_bytes(&mut allocator, &NFT_STATE_LAYER).unwrap();
+        let state = CurriedProgram {
+            program: state_mod,
+            args: NftStateLayerArgs {
+                mod_hash: NFT_STATE_LAYER_HASH.into(),
+                metadata: previous_root,
+                metadata_updater_puzzle_hash: tree_hash_atom(&DL_METADATA_UPDATER).into(),
+                inner_puzzle: owner,
+            },
+        }
+        .to_clvm(&mut allocator)
+        .unwrap();
+        let singleton_mod = node_from_bytes(&mut allocator, &SINGLETON_TOP_LAYER_V1_1).unwrap();
+        let puzzle = CurriedProgram {
+            program: singleton_mod,
+            args: SingletonArgs::new(launcher_id, state),
+        }
+        .to_clvm(&mut allocator)
+        .unwrap();
+
+        let conditions = clvm_list!(
+            clvm_list!(51, new_owner, 1),
+            clvm_list!(-24, updater, clvm_list!(new_root, Bytes32::default()))
+        );
+        let solution = SingletonSolution {
+            lineage_proof: Proof::Eve(EveProof {
+                parent_parent_coin_info: Bytes32::default(),
+                parent_amount: 1,
+            }),
+            amount: 1,
+            inner_solution: NftStateLayerSolution {
+                inner_solution: conditions,
+            },
+        }
+        .to_clvm(&mut allocator)
+        .unwrap();
+
+        let coin = CoinInfo::new(
+            Bytes32::new([1; 32]),
+            tree_hash(&allocator, puzzle).into(),
+            1,
+        );
+        let spend = CoinSpendInfo {
+            puzzle_kind: identify_puzzle(&allocator, puzzle),
+            ..CoinSpendInfo::new(
+                coin.clone(),
+                node_to_bytes(&allocator, puzzle).unwrap().into(),
+                node_to_bytes(&allocator, solution).unwrap().into(),
+                true,
+                String::new(),
+                0,
+                Vec::new(),
+            )
+        };
+
+        let updates = find_data_store_updates(&[spend], u64::MAX, 0);
+
+        assert_eq!(
+            updates,
+            vec![DataStoreUpdate {
+                launcher_id,
+                coin_id: coin.coin_id,
+                new_root_hash: Some(new_root),
+                previous_root_hash: Some(previous_root),
+                metadata: [&[0xa0], new_root.as_ref()].concat().into(),
+                owner_puzzle_hash: Some(new_owner),
+            }]
+        );
+    }
+
+    #[test]
+    fn test_find_data_store_mirrors() {
+        let launcher_id = Bytes32::new([3; 32]);
+        let parent = Bytes32::new([1; 32]);
+        let mirror = CoinInfo::new(parent, mirror_puzzle_hash().into(), 1).with_memos(&[
+            launcher_id.to_vec(),
+            b"https://example.com/dl".to_vec(),
+            b"http://127.0.0.1:8575".to_vec(),
+        ]);
+        let other = CoinInfo::new(parent, Bytes32::new([2; 32]), 1)
+            .with_memos(&[launcher_id.to_vec(), b"https://example.com/dl".to_vec()]);
+
+        let mirrors = find_data_store_mirrors(&[mirror.clone(), other]);
+
+        assert_eq!(
+            mirrors,
+            vec![DataStoreMirror {
+                coin_id: mirror.coin_id,
+                launcher_id,
+                amount: 1,
+                urls: vec![
+                    "https://example.com/dl".to_string(),
+                    "http://127.0.0.1:8575".to_string(),
+                ],
+            }]
+        );
+    }
+}
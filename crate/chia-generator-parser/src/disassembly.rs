@@ -0,0 +1,234 @@
+use crate::error::{GeneratorParserError, Result};
+use clvmr::{serde::node_from_bytes_backrefs, Allocator, NodePtr, SExp};
+
+/// Default cap on the length of a disassembly, in bytes
+pub const DEFAULT_DISASSEMBLY_LIMIT: usize = 16 * 1024;
+
+/// Appended to a disassembly cut short by its length limit
+const TRUNCATION_MARKER: &str = "...";
+
+/// Operator names by opcode, as printed by `opd`; "." marks unassigned opcodes
+///
+/// Opcodes from 0x30 on aren't named: they share values with condition opcodes, which
+/// are far more common in solutions, e.g. CREATE_COIN is 51.
+const KEYWORDS: &str = ". q a i c f r l x = >s sha256 substr strlen concat . + - * / divmod > ash \
+    lsh logand logior logxor lognot . point_add pubkey_for_exp . not any all . softfork";
+
+/// Disassemble serialized CLVM into chialisp-style text, at most `max_len` bytes long
+pub fn disassemble_bytes(program: &[u8], max_len: usize) -> Result<String> {
+    let mut allocator = Allocator::new();
+    let node = node_from_bytes_backrefs(&mut allocator, program)
+        .map_err(|e| GeneratorParserError::ClvmParsingError(e.to_string()))?;
+    Ok(disassemble(&allocator, node, max_len))
+}
+
+/// Disassemble a CLVM tree into chialisp-style text, the way `opd` prints it
+///
+/// The first atom of each list is printed as an operator name when it is one, atoms of
+/// printable text longer than two bytes as quoted strings, canonical atoms of up to two
+/// bytes as integers and everything else as hex. Output longer than `max_len` bytes is
+/// cut short and ends in "...", of which a `max_len` below 3 only keeps what fits.
+pub fn disassemble(allocator: &Allocator, node: NodePtr, max_len: usize) -> String {
+    enum Step {
+        Node(NodePtr, bool),
+        Rest(NodePtr),
+    }
+
+    let mut writer = LimitedWriter {
+        text: String::new(),
+        max_len,
+    };
+    let mut stack = vec![Step::Node(node, false)];
+    while let Some(step) = stack.pop() {
+        if writer.is_full() {
+            break;
+        }
+        match step {
+            Step::Node(node, operator) => match allocator.sexp(node) {
+                SExp::Pair(first, rest) => {
+                    writer.push("(");
+                    stack.push(Step::Rest(rest));
+                    stack.push(Step::Node(first, true));
+                }
+                SExp::Atom => write_atom(&mut writer, allocator.atom(node).as_ref(), operator),
+            },
+            Step::Rest(node) => match allocator.sexp(node) {
+                SExp::Pair(first, rest) => {
+                    writer.push(" ");
+                    stack.push(Step::Rest(rest));
+                    stack.push(Step::Node(first, false));
+                }
+                SExp::Atom if allocator.atom_len(node) == 0 => writer.push(")"),
+                SExp::Atom => {
+                    writer.push(" . ");
+                    write_atom(&mut writer, allocator.atom(node).as_ref(), false);
+                    writer.push(")");
+                }
+            },
+        }
+    }
+    writer.finish()
+}
+
+fn write_atom(writer: &mut LimitedWriter, atom: &[u8], operator: bool) {
+    if let Some(keyword) = operator.then(|| keyword(atom)).flatten() {
+        writer.push(keyword);
+    } else if atom.is_empty() {
+        writer.push("()");
+    } else if atom.len() > 2 && atom.iter().all(|c| (0x20..0x7f).contains(c)) {
+        let quote = if atom.contains(&b'"') && !atom.contains(&b'\'') {
+            "'"
+        } else {
+            "\""
+        };
+        writer.push(quote);
+        writer.push_bytes(atom);
+        writer.push(quote);
+    } else if atom.len() <= 2 && is_canonical_int(atom) {
+        let value = atom
+            .iter()
+            .fold(i64::from(atom[0] as i8) >> 8, |value, byte| {
+                value << 8 | i64::from(*byte)
+            });
+        writer.push(&value.to_string());
+    } else {
+        writer.push("0x");
+        writer.push_hex(atom);
+    }
+}
+
+fn keyword(atom: &[u8]) -> Option<&'static str> {
+    match atom {
+        [opcode] => KEYWORDS
+            .split(' ')
+            .nth(usize::from(*opcode))
+            .filter(|keyword| *keyword != "."),
+        _ => None,
+    }
+}
+
+/// Whether an atom is the shortest encoding of its signed integer value
+fn is_canonical_int(atom: &[u8]) -> bool {
+    match atom {
+        [] => true,
+        [0] => false,
+        [0, next, ..] => *next >= 0x80,
+        [0xff, next, ..] => *next < 0x80,
+        _ => true,
+    }
+}
+
+/// String builder that stops growing at `max_len` bytes
+struct LimitedWriter {
+    text: String,
+    max_len: usize,
+}
+
+impl LimitedWriter {
+    fn is_full(&self) -> bool {
+        self.text.len() > self.max_len
+    }
+
+    fn push(&mut self, text: &str) {
+        self.push_bytes(text.as_bytes());
+    }
+
+    /// Append printable ASCII, up to one byte past the limit
+    fn push_bytes(&mut self, text: &[u8]) {
+        let room = self
+            .max_len
+            .saturating_add(1)
+            .saturating_sub(self.text.len());
+        self.text
+            .extend(text.iter().take(room).map(|byte| char::from(*byte)));
+    }
+
+    /// Append bytes as hex without encoding more of them than fits
+    fn push_hex(&mut self, bytes: &[u8]) {
+        let room = self
+            .max_len
+            .saturating_add(1)
+            .saturating_sub(self.text.len());
+        let hex = hex::encode(&bytes[..bytes.len().min(room / 2 + 1)]);
+        self.push(&hex);
+    }
+
+    fn finish(mut self) -> String {
+        if self.is_full() {
+            // Limits shorter than the marker get as much of the marker as fits
+            let marker = &TRUNCATION_MARKER[..self.max_len.min(TRUNCATION_MARKER.len())];
+            self.text.truncate(self.max_len - marker.len());
+            self.text.push_str(marker);
+        }
+        self.text
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chia_protocol::{Bytes, Bytes32};
+    use clvm_traits::{clvm_list, clvm_quote, ToClvm};
+
+    fn disassemble_value(value: impl ToClvm<Allocator>, max_len: usize) -> String {
+        let mut allocator = Allocator::new();
+        let node = value.to_clvm(&mut allocator).unwrap();
+        disassemble(&allocator, node, max_len)
+    }
+
+    #[test]
+    fn test_disassemble() {
+        let cases: [(&str, &str); 8] = [
+            // (a (q 2 (q . 1) 1) 1), the standard shape of a puzzle running its curried body
+            (
+                "ff02ffff01ff02ffff0101ff0180ff0180",
+                "(a (q 2 (q . 1) 1) 1)",
+            ),
+            ("80", "()"),
+            ("01", "1"),
+            ("8200ff", "255"),
+            ("81ff", "-1"),
+            ("00", "0x00"),
+            ("8568656c6c6f", "\"hello\""),
+            ("ff24ff33ff0a80", "(softfork 51 10)"),
+        ];
+        for (program, expected) in cases {
+            let program = hex::decode(program).unwrap();
+            assert_eq!(
+                disassemble_bytes(&program, DEFAULT_DISASSEMBLY_LIMIT).unwrap(),
+                expected
+            );
+        }
+
+        // Operator names are only used in operator position
+        assert_eq!(
+            disassemble_value(clvm_list!(2, 2, (4, 5)), 100),
+            "(a 2 (c . 5))"
+        );
+        assert_eq!(
+            disassemble_value(clvm_quote!(Bytes32::new([0xab; 32])), 100),
+            format!("(q . 0x{})", "ab".repeat(32))
+        );
+        assert!(disassemble_bytes(&[0xff], 100).is_err());
+    }
+
+    #[test]
+    fn test_disassemble_limit() {
+        let list: Vec<u32> = (1000..2000).collect();
+        let full = disassemble_value(&list, usize::MAX);
+        let limited = disassemble_value(&list, 64);
+        assert_eq!(limited.len(), 64);
+        assert!(limited.ends_with(TRUNCATION_MARKER));
+        assert!(full.starts_with(&limited[..61]));
+
+        // A huge atom is not hex encoded past the limit
+        let limited = disassemble_value(Bytes::new(vec![0xab; 1 << 20]), 32);
+        assert_eq!(limited, format!("0x{}a...", "ab".repeat(13)));
+
+        // The output never exceeds a limit shorter than the marker
+        for max_len in 0..=TRUNCATION_MARKER.len() {
+            let limited = disassemble_value(&list, max_len);
+            assert_eq!(limited, TRUNCATION_MARKER[..max_len]);
+        }
+    }
+}
@@ -0,0 +1,315 @@
+use crate::{
+    error::{GeneratorParserError, Result},
+    types::ParsedBlock,
+};
+use serde::{Deserialize, Serialize};
+
+/// Version of the `ParsedBlock` encodings
+///
+/// Both encodings wrap the block as `{"format_version": 1, "block": {...}}`. The version
+/// is bumped whenever a change to `ParsedBlock` stops older readers from decoding newer
+/// blocks, and blocks of any other version are rejected.
+pub const PARSED_BLOCK_FORMAT_VERSION: u32 = 1;
+
+#[derive(Serialize)]
+struct Envelope<'a> {
+    format_version: u32,
+    block: &'a ParsedBlock,
+}
+
+#[derive(Deserialize)]
+struct OwnedEnvelope {
+    block: ParsedBlock,
+}
+
+/// The envelope read without its block, so the version is checked before the block is
+/// decoded
+#[derive(Deserialize)]
+struct VersionEnvelope {
+    format_version: u32,
+}
+
+impl ParsedBlock {
+    /// Encode as versioned JSON, with hashes, keys and programs as hex strings
+    pub fn to_json(&self) -> Result<String> {
+        serde_json::to_string(&self.envelope()).map_err(serialization_error)
+    }
+
+    /// Encode as versioned JSON indented for reading and diffing
+    pub fn to_json_pretty(&self) -> Result<String> {
+        serde_json::to_string_pretty(&self.envelope()).map_err(serialization_error)
+    }
+
+    /// Decode versioned JSON written by `to_json` or `to_json_pretty`
+    pub fn from_json(json: &str) -> Result<Self> {
+        let version: VersionEnvelope = serde_json::from_str(json).map_err(serialization_error)?;
+        check_version(version.format_version)?;
+
+        let envelope: OwnedEnvelope = serde_json::from_str(json).map_err(serialization_error)?;
+        Ok(envelope.block)
+    }
+
+    /// Encode as versioned CBOR, the same structure as the JSON encoding with hashes,
+    /// keys and programs as raw bytes
+    pub fn to_binary(&self) -> Result<Vec<u8>> {
+        let mut bytes = Vec::new();
+        ciborium::into_writer(&self.envelope(), &mut bytes).map_err(serialization_error)?;
+        Ok(bytes)
+    }
+
+    /// Decode versioned CBOR written by `to_binary`
+    pub fn from_binary(bytes: &[u8]) -> Result<Self> {
+        let version: VersionEnvelope = ciborium::from_reader(bytes).map_err(serialization_error)?;
+        check_version(version.format_version)?;
+
+        let envelope: OwnedEnvelope = ciborium::from_reader(bytes).map_err(serialization_error)?;
+        Ok(envelope.block)
+    }
+
+    fn envelope(&self) -> Envelope<'_> {
+        Envelope {
+            format_version: PARSED_BLOCK_FORMAT_VERSION,
+            block: self,
+        }
+    }
+}
+
+/// `u64` and `u128` fields as decimal strings in human-readable formats, where JSON
+/// readers would lose amounts and weights past 2^53, and as integers in CBOR
+///
+/// Decoding accepts either form.
+pub(crate) mod decimal {
+    use serde::{de, Deserializer, Serialize, Serializer};
+    use std::{fmt, marker::PhantomData, str::FromStr};
+
+    pub fn serialize<T, S>(value: &T, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        T: fmt::Display + Serialize,
+        S: Serializer,
+    {
+        if serializer.is_human_readable() {
+            serializer.collect_str(value)
+        } else {
+            value.serialize(serializer)
+        }
+    }
+
+    pub fn deserialize<'de, T, D>(deserializer: D) -> Result<T, D::Error>
+    where
+        T: FromStr + TryFrom<u64> + TryFrom<u128>,
+        D: Deserializer<'de>,
+    {
+        deserializer.deserialize_any(DecimalVisitor(PhantomData))
+    }
+
+    struct DecimalVisitor<T>(PhantomData<T>);
+
+    impl<T> de::Visitor<'_> for DecimalVisitor<T>
+    where
+        T: FromStr + TryFrom<u64> + TryFrom<u128>,
+    {
+        type Value = T;
+
+        fn expecting(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+            formatter.write_str("an unsigned integer or a decimal string")
+        }
+
+        fn visit_str<E: de::Error>(self, value: &str) -> Result<T, E> {
+            value
+                .parse()
+                .map_err(|_| E::invalid_value(de::Unexpected::Str(value), &self))
+        }
+
+        fn visit_u64<E: de::Error>(self, value: u64) -> Result<T, E> {
+            T::try_from(value).map_err(|_| E::invalid_value(de::Unexpected::Unsigned(value), &self))
+        }
+
+        fn visit_u128<E: de::Error>(self, value: u128) -> Result<T, E> {
+            T::try_from(value).map_err(|_| E::invalid_value(de::Unexpected::Other("u128"), &self))
+        }
+    }
+}
+
+fn check_version(format_version: u32) -> Result<()> {
+    if format_version != PARSED_BLOCK_FORMAT_VERSION {
+        return Err(GeneratorParserError::UnsupportedFormatVersion {
+            found: format_version,
+            expected: PARSED_BLOCK_FORMAT_VERSION,
+        });
+    }
+    Ok(())
+}
+
+fn serialization_error(e: impl std::fmt::Display) -> GeneratorParserError {
+    GeneratorParserError::SerializationError(e.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::{
+        AggSigKind, CoinInfo, CoinSpendInfo, ConditionInfo, DataStoreUpdate, FarmingInfo,
+        ParseDiagnostics, ParseFailure, ParseStage, PoolTarget, PuzzleKind, SignatureValidation,
+    };
+    use chia_bls::PublicKey;
+    use chia_protocol::{Bytes, Bytes32, Program};
+    use std::collections::BTreeMap;
+
+    /// A block using every kind of field: nested and tagged enums, keys, programs and a
+    /// weight past `u64::MAX`
+    fn parsed_block() -> ParsedBlock {
+        let created = CoinInfo::new(Bytes32::new([2; 32]), Bytes32::new([3; 32]), 7)
+            .with_memos(&[vec![4; 32], b"memo".to_vec()]);
+        let spent = CoinInfo::new(Bytes32::new([1; 32]), Bytes32::new([5; 32]), 10);
+        let spend = CoinSpendInfo {
+            conditions: vec![
+                ConditionInfo::AggSig {
+                    kind: AggSigKind::Me,
+                    public_key: PublicKey::default(),
+                    message: Bytes::new(b"message".to_vec()),
+                },
+                ConditionInfo::ReserveFee { amount: 3 },
+            ],
+            execution_cost: 1_000,
+            condition_cost: 2_000,
+            puzzle_kind: PuzzleKind::Cat {
+                asset_id: Bytes32::new([6; 32]),
+                inner_puzzle_hash: Bytes32::new([7; 32]),
+                inner: Box::new(PuzzleKind::StandardTransaction {
+                    synthetic_key: PublicKey::default(),
+                }),
+            },
+            puzzle_disassembly: Some("1".to_string()),
+            ..CoinSpendInfo::new(
+                spent.clone(),
+                Program::new(vec![1].into()),
+                Program::new(vec![0x80].into()),
+                true,
+                "From transaction generator".to_string(),
+                0,
+                vec![created.clone()],
+            )
+        };
+
+        ParsedBlock {
+            height: 5_000_000,
+            weight: u128::from(u64::MAX) * 3,
+            header_hash: Bytes32::new([8; 32]),
+            timestamp: Some(1_700_000_000),
+            farming: FarmingInfo {
+                plot_public_key: PublicKey::default(),
+                pool_public_key: None,
+                pool_contract_puzzle_hash: Some(Bytes32::new([12; 32])),
+                farmer_reward_puzzle_hash: Bytes32::new([13; 32]),
+                pool_target: PoolTarget::new(Bytes32::new([14; 32]), 0),
+                signage_point_index: 62,
+                plot_size: 32,
+                plot_version: 1,
+                overflow: true,
+            },
+            coin_additions: vec![created.clone()],
+            coin_removals: vec![spent],
+            coin_spends: vec![spend],
+            coin_creations: vec![created.clone()],
+            hint_index: BTreeMap::from([(Bytes32::new([4; 32]), vec![created.coin_id])]),
+            cost: 3_000,
+            execution_cost: 1_000,
+            condition_cost: 2_000,
+            fees: 3,
+            reserve_fee: 3,
+            signature_validation: SignatureValidation::Valid,
+            diagnostics: ParseDiagnostics {
+                failures: vec![ParseFailure {
+                    stage: ParseStage::RunPuzzle,
+                    error_code: Some(11),
+                    message: "GeneratorRuntimeError".to_string(),
+                    spend_index: Some(0),
+                }],
+            },
+            data_store_updates: vec![DataStoreUpdate {
+                launcher_id: Bytes32::new([9; 32]),
+                coin_id: Bytes32::new([10; 32]),
+                new_root_hash: Some(Bytes32::new([11; 32])),
+                previous_root_hash: None,
+                metadata: Program::new(vec![0x80].into()),
+                owner_puzzle_hash: None,
+            }],
+            data_store_mirrors: Vec::new(),
+            has_transactions_generator: true,
+            generator_size: Some(1234),
+        }
+    }
+
+    /// `ParsedBlock` isn't `PartialEq`, so blocks are compared through their debug output
+    fn assert_same_block(a: &ParsedBlock, b: &ParsedBlock) {
+        assert_eq!(format!("{a:?}"), format!("{b:?}"));
+    }
+
+    #[test]
+    fn test_json_round_trip() {
+        let block = parsed_block();
+        let json = block.to_json().unwrap();
+        assert!(json.starts_with(r#"{"format_version":1,"block":{"#));
+        assert!(json.contains(&format!(r#""header_hash":"0x{}""#, "08".repeat(32))));
+        // Amounts and weights survive JSON readers that only have doubles
+        assert!(json.contains(r#""weight":"55340232221128654845""#));
+        assert!(json.contains(r#""amount":"7""#));
+        assert!(json.contains(r#"{"type":"reserve_fee","amount":"3"}"#));
+
+        assert_same_block(&ParsedBlock::from_json(&json).unwrap(), &block);
+    }
+
+    #[test]
+    fn test_json_numbers_decode() {
+        // Integers written as JSON numbers are still read
+        let json = parsed_block()
+            .to_json()
+            .unwrap()
+            .replace(r#""amount":"7""#, r#""amount":7"#);
+        let block = ParsedBlock::from_json(&json).unwrap();
+        assert_eq!(block.coin_creations[0].amount, 7);
+
+        let json = json.replace(r#""amount":7"#, r#""amount":"-7""#);
+        assert!(matches!(
+            ParsedBlock::from_json(&json),
+            Err(GeneratorParserError::SerializationError(_))
+        ));
+    }
+
+    #[test]
+    fn test_binary_round_trip() {
+        let block = parsed_block();
+        let bytes = block.to_binary().unwrap();
+        assert!(bytes.len() < block.to_json().unwrap().len());
+
+        assert_same_block(&ParsedBlock::from_binary(&bytes).unwrap(), &block);
+    }
+
+    #[test]
+    fn test_unsupported_version() {
+        let json = parsed_block().to_json().unwrap().replacen(
+            r#""format_version":1"#,
+            r#""format_version":2"#,
+            1,
+        );
+        assert!(matches!(
+            ParsedBlock::from_json(&json),
+            Err(GeneratorParserError::UnsupportedFormatVersion {
+                found: 2,
+                expected: 1
+            })
+        ));
+
+        // The version is checked before the block, so newer blocks fail on their version
+        let json = r#"{"format_version":2,"block":{"height":"new layout"}}"#;
+        assert!(matches!(
+            ParsedBlock::from_json(json),
+            Err(GeneratorParserError::UnsupportedFormatVersion { .. })
+        ));
+
+        assert!(matches!(
+            ParsedBlock::from_binary(&[0xff]),
+            Err(GeneratorParserError::SerializationError(_))
+        ));
+    }
+}
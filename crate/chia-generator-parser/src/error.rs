@@ -1,3 +1,4 @@
+use crate::types::ParseFailure;
 use thiserror::Error;
 
 #[derive(Error, Debug)]
@@ -17,9 +18,21 @@ pub enum GeneratorParserError {
     #[error("CLVM execution error: {0}")]
     ClvmExecutionError(String),
 
+    #[error("Generator for referenced block {0} is not available")]
+    GeneratorRefNotFound(u32),
+
+    #[error("Generator lookup error: {0}")]
+    GeneratorLookupError(String),
+
     #[error("Serialization error: {0}")]
     SerializationError(String),
 
+    #[error("Unsupported ParsedBlock format version {found}, expected {expected}")]
+    UnsupportedFormatVersion { found: u32, expected: u32 },
+
+    #[error("Generator failed at {}: {}", .0.stage.name(), .0.message)]
+    GeneratorFailed(ParseFailure),
+
     #[error("Hex decoding error: {0}")]
     HexDecodingError(#[from] hex::FromHexError),
 }
@@ -0,0 +1,77 @@
+use crate::types::CoinInfo;
+use chia_protocol::Bytes32;
+use std::collections::HashSet;
+
+/// Coins whose spends a `BlockParser` parses in full
+///
+/// A spend matches when the coin it spends, or any coin it creates, has a listed coin
+/// ID, puzzle hash or hint. Coin IDs are still computed for every spend, so removals and
+/// additions stay complete, but only matching spends are serialized into `coin_spends`
+/// and only the coins they create keep their memos. An empty filter matches nothing.
+#[derive(Debug, Clone, Default)]
+pub struct CoinFilter {
+    pub puzzle_hashes: HashSet<Bytes32>,
+    pub coin_ids: HashSet<Bytes32>,
+    pub hints: HashSet<Bytes32>,
+}
+
+impl CoinFilter {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Match coins with any of these puzzle hashes
+    pub fn with_puzzle_hashes(mut self, puzzle_hashes: impl IntoIterator<Item = Bytes32>) -> Self {
+        self.puzzle_hashes.extend(puzzle_hashes);
+        self
+    }
+
+    /// Match coins with any of these IDs
+    pub fn with_coin_ids(mut self, coin_ids: impl IntoIterator<Item = Bytes32>) -> Self {
+        self.coin_ids.extend(coin_ids);
+        self
+    }
+
+    /// Match coins created with any of these hints
+    pub fn with_hints(mut self, hints: impl IntoIterator<Item = Bytes32>) -> Self {
+        self.hints.extend(hints);
+        self
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.puzzle_hashes.is_empty() && self.coin_ids.is_empty() && self.hints.is_empty()
+    }
+
+    /// Whether a coin's ID, puzzle hash or hint is in the filter
+    pub fn matches_coin(&self, coin: &CoinInfo) -> bool {
+        self.coin_ids.contains(&coin.coin_id)
+            || self.puzzle_hashes.contains(&coin.puzzle_hash)
+            || coin.hint.is_some_and(|hint| self.hints.contains(&hint))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_matches_coin() {
+        let coin = CoinInfo::new(Bytes32::new([1; 32]), Bytes32::new([2; 32]), 1)
+            .with_memos(&[vec![3; 32]]);
+
+        assert!(!CoinFilter::new().matches_coin(&coin));
+        assert!(CoinFilter::new()
+            .with_puzzle_hashes([Bytes32::new([2; 32])])
+            .matches_coin(&coin));
+        assert!(CoinFilter::new()
+            .with_coin_ids([coin.coin_id])
+            .matches_coin(&coin));
+        assert!(CoinFilter::new()
+            .with_hints([Bytes32::new([3; 32])])
+            .matches_coin(&coin));
+        // The parent isn't the coin
+        assert!(!CoinFilter::new()
+            .with_coin_ids([Bytes32::new([1; 32])])
+            .matches_coin(&coin));
+    }
+}
@@ -0,0 +1,166 @@
+use crate::error::{GeneratorParserError, Result};
+use chia_protocol::FullBlock;
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::sync::{Arc, RwLock};
+
+/// Source of generator bytecode for blocks listed in `transactions_generator_ref_list`
+///
+/// Compressed blocks reference the generators of earlier blocks by height. The parser
+/// calls the lookup once per referenced height, in list order, before running the block.
+pub trait GeneratorLookup: Send + Sync {
+    /// Return the serialized `transactions_generator` of the block at `height`
+    fn get_generator(&self, height: u32) -> Result<Vec<u8>>;
+}
+
+/// In-memory generator store keyed by block height
+///
+/// When a capacity limit is set, the lowest heights are evicted first.
+#[derive(Debug, Default)]
+pub struct InMemoryGeneratorCache {
+    generators: RwLock<BTreeMap<u32, Vec<u8>>>,
+    max_entries: Option<usize>,
+}
+
+impl InMemoryGeneratorCache {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Create a cache that holds at most `max_entries` generators
+    pub fn with_max_entries(max_entries: usize) -> Self {
+        Self {
+            generators: RwLock::new(BTreeMap::new()),
+            max_entries: Some(max_entries),
+        }
+    }
+
+    /// Store the generator for a block height
+    pub fn insert(&self, height: u32, generator: Vec<u8>) {
+        let Ok(mut generators) = self.generators.write() else {
+            return;
+        };
+
+        generators.insert(height, generator);
+
+        if let Some(max_entries) = self.max_entries {
+            while generators.len() > max_entries {
+                generators.pop_first();
+            }
+        }
+    }
+
+    /// Store the generator of a FullBlock, if it has one
+    pub fn insert_block(&self, block: &FullBlock) {
+        if let Some(generator) = &block.transactions_generator {
+            self.insert(block.reward_chain_block.height, generator.to_vec());
+        }
+    }
+
+    /// Check whether a generator is cached for a block height
+    pub fn contains(&self, height: u32) -> bool {
+        self.generators
+            .read()
+            .map(|generators| generators.contains_key(&height))
+            .unwrap_or(false)
+    }
+
+    pub fn len(&self) -> usize {
+        self.generators
+            .read()
+            .map(|generators| generators.len())
+            .unwrap_or(0)
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
+
+impl GeneratorLookup for InMemoryGeneratorCache {
+    fn get_generator(&self, height: u32) -> Result<Vec<u8>> {
+        let generators = self.generators.read().map_err(|e| {
+            GeneratorParserError::GeneratorLookupError(format!("Generator cache poisoned: {e}"))
+        })?;
+
+        generators
+            .get(&height)
+            .cloned()
+            .ok_or(GeneratorParserError::GeneratorRefNotFound(height))
+    }
+}
+
+/// Generators of blocks parsed together in a batch, checked before the parser's own lookup
+///
+/// Only generators that another block of the batch references are kept.
+pub(crate) struct BatchGeneratorLookup {
+    generators: HashMap<u32, Vec<u8>>,
+    fallback: Option<Arc<dyn GeneratorLookup>>,
+}
+
+impl BatchGeneratorLookup {
+    /// `None` when no block of the batch references another one
+    pub(crate) fn new(
+        blocks: &[&FullBlock],
+        fallback: Option<Arc<dyn GeneratorLookup>>,
+    ) -> Option<Self> {
+        let referenced: HashSet<u32> = blocks
+            .iter()
+            .flat_map(|block| block.transactions_generator_ref_list.iter().copied())
+            .collect();
+
+        let generators: HashMap<u32, Vec<u8>> = blocks
+            .iter()
+            .filter(|block| referenced.contains(&block.reward_chain_block.height))
+            .filter_map(|block| {
+                let generator = block.transactions_generator.as_ref()?;
+                Some((block.reward_chain_block.height, generator.to_vec()))
+            })
+            .collect();
+
+        (!generators.is_empty()).then_some(Self {
+            generators,
+            fallback,
+        })
+    }
+}
+
+impl GeneratorLookup for BatchGeneratorLookup {
+    fn get_generator(&self, height: u32) -> Result<Vec<u8>> {
+        match (self.generators.get(&height), &self.fallback) {
+            (Some(generator), _) => Ok(generator.clone()),
+            (None, Some(fallback)) => fallback.get_generator(height),
+            (None, None) => Err(GeneratorParserError::GeneratorRefNotFound(height)),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_cache_lookup() {
+        let cache = InMemoryGeneratorCache::new();
+        cache.insert(10, vec![0xff, 0x80, 0x80]);
+
+        assert!(cache.contains(10));
+        assert_eq!(cache.get_generator(10).unwrap(), vec![0xff, 0x80, 0x80]);
+        assert!(matches!(
+            cache.get_generator(11),
+            Err(GeneratorParserError::GeneratorRefNotFound(11))
+        ));
+    }
+
+    #[test]
+    fn test_cache_evicts_lowest_heights() {
+        let cache = InMemoryGeneratorCache::with_max_entries(2);
+        cache.insert(5, vec![1]);
+        cache.insert(7, vec![2]);
+        cache.insert(6, vec![3]);
+
+        assert_eq!(cache.len(), 2);
+        assert!(!cache.contains(5));
+        assert!(cache.contains(6));
+        assert!(cache.contains(7));
+    }
+}
@@ -1,7 +1,38 @@
+pub mod analysis;
+pub mod conditions;
+pub mod datalayer;
+pub mod disassembly;
+#[cfg(feature = "serde")]
+pub mod encoding;
 pub mod error;
+pub mod filter;
+pub mod generator_lookup;
+pub mod network;
 pub mod parser;
+pub mod puzzles;
 pub mod types;
+pub mod verification;
 
+#[cfg(test)]
+#[path = "../tests/common/mod.rs"]
+mod fixtures;
+
+pub use analysis::LARGEST_PUZZLE_REVEALS;
+pub use conditions::{parse_conditions, parse_create_coins, run_puzzle, CreateCoinCondition};
+pub use datalayer::{find_data_store_mirrors, find_data_store_updates};
+pub use disassembly::{disassemble, disassemble_bytes, DEFAULT_DISASSEMBLY_LIMIT};
+#[cfg(feature = "serde")]
+pub use encoding::PARSED_BLOCK_FORMAT_VERSION;
 pub use error::*;
+pub use filter::CoinFilter;
+pub use generator_lookup::*;
+pub use network::*;
 pub use parser::*;
+pub use puzzles::{identify_puzzle, puzzle_mod_hash};
 pub use types::*;
+pub use verification::{
+    additions_root, generator_matches_root, generator_refs_root, generator_root, removals_root,
+    transactions_filter,
+};
+
+pub use chia_bls::BlsCache;
@@ -0,0 +1,122 @@
+use chia_consensus::{
+    consensus_constants::{ConsensusConstants, TEST_CONSTANTS},
+    spendbundle_validation::get_flags_for_height_and_constants,
+};
+use chia_protocol::Bytes32;
+use sha2::{Digest, Sha256};
+
+const TESTNET11_GENESIS_CHALLENGE: [u8; 32] = [
+    0x37, 0xa9, 0x0e, 0xb5, 0x18, 0x5a, 0x9c, 0x44, 0x39, 0xa9, 0x1d, 0xdc, 0x98, 0xbb, 0xad, 0xce,
+    0x7b, 0x4f, 0xeb, 0xa0, 0x60, 0xd5, 0x01, 0x16, 0xa0, 0x67, 0xde, 0x66, 0xbf, 0x23, 0x66, 0x15,
+];
+const TESTNET11_PRE_FARM_POOL_PUZZLE_HASH: [u8; 32] = [
+    0xd2, 0x3d, 0xa1, 0x46, 0x95, 0xa1, 0x88, 0xae, 0x57, 0x08, 0xdd, 0x15, 0x22, 0x63, 0xc4, 0xdb,
+    0x88, 0x3e, 0xb2, 0x7e, 0xde, 0xb9, 0x36, 0x17, 0x8d, 0x4d, 0x98, 0x8b, 0x8f, 0x3c, 0xe5, 0xfc,
+];
+const TESTNET11_PRE_FARM_FARMER_PUZZLE_HASH: [u8; 32] = [
+    0x3d, 0x87, 0x65, 0xd3, 0xa5, 0x97, 0xec, 0x1d, 0x99, 0x66, 0x3f, 0x6c, 0x98, 0x16, 0xd9, 0x15,
+    0xb9, 0xf6, 0x86, 0x13, 0xac, 0x94, 0x00, 0x98, 0x84, 0xc4, 0xad, 0xda, 0xef, 0xcc, 0xe6, 0xaf,
+];
+
+/// Chia network whose consensus rules are used to run block generators
+#[derive(Debug, Clone, Default)]
+pub enum Network {
+    #[default]
+    Mainnet,
+    Testnet11,
+    Custom(Box<ConsensusConstants>),
+}
+
+impl Network {
+    /// Consensus constants for this network
+    pub fn consensus_constants(&self) -> ConsensusConstants {
+        match self {
+            // TEST_CONSTANTS carries the mainnet genesis challenge and fork heights
+            Network::Mainnet => TEST_CONSTANTS,
+            Network::Testnet11 => testnet11_constants(),
+            Network::Custom(constants) => (**constants).clone(),
+        }
+    }
+
+    /// Genesis challenge that identifies this network
+    pub fn genesis_challenge(&self) -> Bytes32 {
+        match self {
+            Network::Mainnet => TEST_CONSTANTS.genesis_challenge,
+            Network::Testnet11 => Bytes32::new(TESTNET11_GENESIS_CHALLENGE),
+            Network::Custom(constants) => constants.genesis_challenge,
+        }
+    }
+}
+
+/// CLVM and condition flags for running a block generator at `height`
+pub fn flags_for_height(height: u32, constants: &ConsensusConstants) -> u32 {
+    get_flags_for_height_and_constants(height, constants)
+}
+
+fn testnet11_constants() -> ConsensusConstants {
+    let genesis_challenge = Bytes32::new(TESTNET11_GENESIS_CHALLENGE);
+
+    ConsensusConstants {
+        genesis_challenge,
+        agg_sig_me_additional_data: genesis_challenge,
+        agg_sig_parent_additional_data: agg_sig_additional_data(genesis_challenge, 43),
+        agg_sig_puzzle_additional_data: agg_sig_additional_data(genesis_challenge, 44),
+        agg_sig_amount_additional_data: agg_sig_additional_data(genesis_challenge, 45),
+        agg_sig_puzzle_amount_additional_data: agg_sig_additional_data(genesis_challenge, 46),
+        agg_sig_parent_amount_additional_data: agg_sig_additional_data(genesis_challenge, 47),
+        agg_sig_parent_puzzle_additional_data: agg_sig_additional_data(genesis_challenge, 48),
+        genesis_pre_farm_pool_puzzle_hash: Bytes32::new(TESTNET11_PRE_FARM_POOL_PUZZLE_HASH),
+        genesis_pre_farm_farmer_puzzle_hash: Bytes32::new(TESTNET11_PRE_FARM_FARMER_PUZZLE_HASH),
+        hard_fork_height: 0,
+        plot_filter_128_height: 6_029_568,
+        plot_filter_64_height: 11_075_328,
+        plot_filter_32_height: 16_121_088,
+        ..TEST_CONSTANTS
+    }
+}
+
+/// Additional data for the AGG_SIG_* variant with condition opcode `opcode`
+fn agg_sig_additional_data(agg_sig_me_data: Bytes32, opcode: u8) -> Bytes32 {
+    let mut hasher = Sha256::new();
+    hasher.update(agg_sig_me_data);
+    hasher.update([opcode]);
+    Bytes32::new(hasher.finalize().into())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_networks_have_distinct_genesis_challenges() {
+        assert_eq!(
+            hex::encode(Network::Mainnet.genesis_challenge()),
+            "ccd5bb71183532bff220ba46c268991a3ff07eb358e8255a65c30a2dce0e5fbb"
+        );
+        assert_eq!(
+            hex::encode(Network::Testnet11.genesis_challenge()),
+            "37a90eb5185a9c4439a91ddc98bbadce7b4feba060d50116a067de66bf236615"
+        );
+    }
+
+    #[test]
+    fn test_testnet11_constants_differ_from_mainnet() {
+        let mainnet = Network::Mainnet.consensus_constants();
+        let testnet = Network::Testnet11.consensus_constants();
+
+        // Testnet11 launched with the hard fork active and its own plot filter schedule
+        assert_eq!(testnet.hard_fork_height, 0);
+        assert_ne!(mainnet.hard_fork_height, testnet.hard_fork_height);
+        assert_eq!(testnet.plot_filter_128_height, 6_029_568);
+        assert_ne!(
+            mainnet.plot_filter_128_height,
+            testnet.plot_filter_128_height
+        );
+        assert_ne!(mainnet.plot_filter_64_height, testnet.plot_filter_64_height);
+        assert_ne!(mainnet.plot_filter_32_height, testnet.plot_filter_32_height);
+        assert_ne!(
+            mainnet.agg_sig_me_additional_data,
+            testnet.agg_sig_me_additional_data
+        );
+    }
+}
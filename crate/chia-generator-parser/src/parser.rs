@@ -1,37 +1,144 @@
 use crate::{
+    analysis::{
+        back_reference_stats, entropy, largest_puzzle_reveals, repeated_puzzle_mods,
+        LARGEST_PUZZLE_REVEALS,
+    },
+    conditions::{parse_conditions, parse_create_coins, run_puzzle, CreateCoinCondition},
+    datalayer::{find_data_store_mirrors, find_data_store_updates},
+    disassembly::disassemble,
     error::{GeneratorParserError, Result},
-    types::{BlockHeightInfo, CoinInfo, CoinSpendInfo, GeneratorBlockInfo, ParsedBlock},
+    filter::CoinFilter,
+    generator_lookup::{BatchGeneratorLookup, GeneratorLookup},
+    network::{flags_for_height, Network},
+    puzzles::identify_puzzle,
+    types::{
+        BlockHeightInfo, BlockVerification, CoinInfo, CoinSpendInfo, CommitmentCheck, FarmingInfo,
+        GeneratorAnalysis, GeneratorBlockInfo, ParseDiagnostics, ParseFailure, ParseStage,
+        ParsedBlock, ParsedGenerator, ParsedHeaderBlock, ParsedSpendBundle, SignatureValidation,
+    },
+    verification::{
+        additions_root, filter_hash, generator_refs_root, generator_root, removals_root,
+        transactions_filter,
+    },
 };
-use chia_bls::Signature;
+use chia_bls::{BlsCache, Signature};
 use chia_consensus::{
     allocator::make_allocator,
-    conditions::SpendBundleConditions,
-    consensus_constants::{ConsensusConstants, TEST_CONSTANTS},
+    conditions::{
+        process_single_spend, validate_conditions, validate_signature, EmptyVisitor, ParseState,
+        SpendBundleConditions, SpendConditions,
+    },
+    consensus_constants::ConsensusConstants,
     flags::DONT_VALIDATE_SIGNATURE,
-    run_block_generator::{run_block_generator2, setup_generator_args},
-    validation_error::{atom, first, next, rest, ErrorCode},
+    run_block_generator::{extract_n, setup_generator_args, subtract_cost},
+    solution_generator::calculate_generator_length,
+    validation_error::{atom, first, rest, ErrorCode, ValidationErr},
+};
+use chia_protocol::{
+    Bytes32, CoinSpend, Foliage, FullBlock, HeaderBlock, PlotSize, RewardChainBlock, SpendBundle,
+    TransactionsInfo,
 };
-use chia_protocol::FullBlock;
 use chia_traits::streamable::Streamable;
-use clvm_utils::tree_hash;
+use clvm_utils::{tree_hash, tree_hash_cached, TreeHash};
 use clvmr::{
     chia_dialect::ChiaDialect,
+    cost::Cost,
     op_utils::u64_from_bytes,
+    reduction::{EvalErr, Reduction},
     run_program::run_program,
-    serde::{node_from_bytes_backrefs, node_to_bytes},
+    serde::{node_from_bytes, node_from_bytes_backrefs_record, node_to_bytes},
     Allocator, NodePtr,
 };
+use rayon::prelude::*;
 use sha2::{Digest, Sha256};
-use tracing::info;
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::sync::Arc;
+use tracing::{info, warn};
+
+/// Size of the quote wrapping a spend bundle's spends in a generator, which the bundle
+/// doesn't pay for
+const QUOTE_BYTES: usize = 2;
 
 /// Block parser that extracts generator information from FullBlock structures
+#[derive(Clone)]
 pub struct BlockParser {
-    // We don't need ConsensusConstants for now
+    /// Network whose consensus rules generators are run under
+    network: Network,
+
+    /// Consensus constants resolved from `network`
+    constants: ConsensusConstants,
+
+    /// Resolves `transactions_generator_ref_list` for compressed blocks
+    generator_lookup: Option<Arc<dyn GeneratorLookup>>,
+
+    /// Pairing cache for aggregate signature validation; validation is skipped when unset
+    bls_cache: Option<Arc<BlsCache>>,
+
+    /// Fail with `GeneratorParserError::GeneratorFailed` instead of reporting diagnostics
+    strict: bool,
+
+    /// Length limit of each spend's puzzle and solution disassembly; spends aren't
+    /// disassembled when unset
+    disassembly_limit: Option<usize>,
+
+    /// Coins whose spends are parsed in full; every spend is when unset
+    coin_filter: Option<Arc<CoinFilter>>,
 }
 
 impl BlockParser {
-    pub fn new() -> Self {
-        Self {}
+    pub fn new(network: Network) -> Self {
+        let constants = network.consensus_constants();
+        Self {
+            network,
+            constants,
+            generator_lookup: None,
+            bls_cache: None,
+            strict: false,
+            disassembly_limit: None,
+            coin_filter: None,
+        }
+    }
+
+    /// Network this parser runs generators for
+    pub fn network(&self) -> &Network {
+        &self.network
+    }
+
+    /// Use `lookup` to fetch the generators of blocks referenced by a compressed block
+    pub fn with_generator_lookup(mut self, lookup: Arc<dyn GeneratorLookup>) -> Self {
+        self.generator_lookup = Some(lookup);
+        self
+    }
+
+    /// Validate each block's aggregate signature, caching pairings in `bls_cache`
+    ///
+    /// The result is reported in `ParsedBlock::signature_validation`.
+    pub fn with_signature_validation(mut self, bls_cache: Arc<BlsCache>) -> Self {
+        self.bls_cache = Some(bls_cache);
+        self
+    }
+
+    /// In strict mode, a generator that fails to parse or run is returned as an error
+    /// instead of a block with empty coin data and `ParsedBlock::diagnostics`
+    pub fn with_strict_mode(mut self, strict: bool) -> Self {
+        self.strict = strict;
+        self
+    }
+
+    /// Disassemble each spend's puzzle reveal and solution into chialisp-style text, each
+    /// cut short at `max_len` bytes
+    pub fn with_disassembly(mut self, max_len: usize) -> Self {
+        self.disassembly_limit = Some(max_len);
+        self
+    }
+
+    /// Only parse the spends that match `filter` in full
+    ///
+    /// Removals and additions still list every coin, but `coin_spends` only holds the
+    /// matching spends, so DataLayer updates are only found for matching stores.
+    pub fn with_coin_filter(mut self, filter: Arc<CoinFilter>) -> Self {
+        self.coin_filter = Some(filter);
+        self
     }
 
     /// Parse a FullBlock directly instead of bytes
@@ -51,6 +158,7 @@ impl BlockParser {
 
         // Calculate header hash by serializing the foliage
         let header_hash = self.calculate_header_hash(&block.foliage)?;
+        let farming = self.farming_info(&block.reward_chain_block, &block.foliage);
 
         // Check if block has transactions generator
         let has_transactions_generator = block.transactions_generator.is_some();
@@ -70,50 +178,231 @@ impl BlockParser {
             });
 
         // Process reward claims
-        let mut coin_additions = self.extract_reward_claims(block);
+        let mut coin_additions = self.extract_reward_claims(block.transactions_info.as_ref());
 
         // Process generator to extract coins if present
-        let (coin_removals, coin_spends, coin_creations) =
-            if let Some(generator) = &block.transactions_generator {
-                self.process_generator_for_coins(
-                    generator,
-                    &block.transactions_generator_ref_list,
-                    height,
-                )?
-            } else {
-                (Vec::new(), Vec::new(), Vec::new())
-            };
+        let GeneratorCoins {
+            mut coin_removals,
+            mut coin_spends,
+            mut coin_creations,
+            costs,
+            signature_validation,
+            diagnostics,
+        } = if let Some(generator) = &block.transactions_generator {
+            let signature = self
+                .bls_cache
+                .as_ref()
+                .and(block.transactions_info.as_ref())
+                .map(|info| &info.aggregated_signature);
+
+            self.process_generator_for_coins(
+                generator,
+                &block.transactions_generator_ref_list,
+                height,
+                signature,
+            )?
+        } else {
+            GeneratorCoins::default()
+        };
+
+        mark_ephemeral_coins(&mut coin_removals, &mut coin_spends, &mut coin_creations);
+        let hint_index = build_hint_index(&coin_creations);
+
+        let data_store_updates = find_data_store_updates(
+            &coin_spends,
+            self.constants.max_block_cost_clvm,
+            flags_for_height(height, &self.constants),
+        );
+        let data_store_mirrors = find_data_store_mirrors(&coin_creations);
 
         // Add coin creations to additions
         coin_additions.extend(coin_creations.clone());
 
         Ok(ParsedBlock {
             height,
-            weight: weight.to_string(),
+            weight,
             header_hash,
             timestamp,
+            farming,
             coin_additions,
             coin_removals,
             coin_spends,
             coin_creations,
+            hint_index,
+            cost: costs.cost,
+            execution_cost: costs.execution_cost,
+            condition_cost: costs.condition_cost,
+            fees: costs.fees,
+            reserve_fee: costs.reserve_fee,
+            signature_validation,
+            diagnostics,
+            data_store_updates,
+            data_store_mirrors,
             has_transactions_generator,
             generator_size,
         })
     }
 
     /// Calculate header hash from foliage
-    fn calculate_header_hash(&self, foliage: &chia_protocol::Foliage) -> Result<String> {
+    fn calculate_header_hash(&self, foliage: &chia_protocol::Foliage) -> Result<Bytes32> {
         let foliage_bytes = foliage.to_bytes().map_err(|e| {
             GeneratorParserError::InvalidBlockFormat(format!("Failed to serialize foliage: {}", e))
         })?;
         let mut hasher = Sha256::new();
         hasher.update(&foliage_bytes);
-        Ok(hex::encode(hasher.finalize()))
+        Ok(Bytes32::new(hasher.finalize().into()))
+    }
+
+    /// Check the hashes a block's foliage commits to against the values recomputed from
+    /// the block and its parsed coins, so blocks a peer made up or altered can be rejected
+    ///
+    /// `parsed` must come from parsing `block`. The additions root, removals root and
+    /// filter hash are recomputed from its coins, so they're `Unverifiable` when the
+    /// generator didn't parse cleanly. The additions root covers reward claims, while the
+    /// filter only covers coins created by spends.
+    pub fn verify_block(
+        &self,
+        block: &FullBlock,
+        parsed: &ParsedBlock,
+    ) -> Result<BlockVerification> {
+        let header_hash = self.calculate_header_hash(&block.foliage)?;
+        if parsed.header_hash != header_hash {
+            return Err(GeneratorParserError::InvalidBlockFormat(format!(
+                "Parsed block {} is not block {}",
+                parsed.header_hash, header_hash
+            )));
+        }
+
+        let foliage = &block.foliage;
+        let unfinished_reward_block_hash =
+            Bytes32::new(block.reward_chain_block.get_unfinished().hash());
+
+        // A generator without transactions info to commit to it fails both generator checks
+        let transactions_info = block.transactions_info.as_ref();
+        let generator = block.transactions_generator.as_ref();
+        let has_transactions = transactions_info.is_some() || generator.is_some();
+
+        let transaction_block = block.foliage_transaction_block.as_ref();
+        let mut verification = BlockVerification {
+            header_hash,
+            reward_block_hash: CommitmentCheck::compare(
+                Some(foliage.reward_block_hash),
+                Some(unfinished_reward_block_hash),
+            ),
+            unfinished_reward_block_hash: CommitmentCheck::compare(
+                Some(foliage.foliage_block_data.unfinished_reward_block_hash),
+                Some(unfinished_reward_block_hash),
+            ),
+            foliage_transaction_block_hash: CommitmentCheck::compare(
+                foliage.foliage_transaction_block_hash,
+                transaction_block.map(|ftb| Bytes32::new(ftb.hash())),
+            ),
+            transactions_info_hash: CommitmentCheck::compare(
+                transaction_block.map(|ftb| ftb.transactions_info_hash),
+                transactions_info.map(|info| Bytes32::new(info.hash())),
+            ),
+            generator_root: CommitmentCheck::compare(
+                transactions_info.map(|info| info.generator_root),
+                has_transactions.then(|| generator_root(generator.map(|g| g.as_ref()))),
+            ),
+            generator_refs_root: CommitmentCheck::compare(
+                transactions_info.map(|info| info.generator_refs_root),
+                has_transactions
+                    .then(|| generator_refs_root(&block.transactions_generator_ref_list)),
+            ),
+            additions_root: CommitmentCheck::NotChecked,
+            removals_root: CommitmentCheck::NotChecked,
+            filter_hash: CommitmentCheck::NotChecked,
+        };
+
+        if transaction_block.is_some() && !parsed.diagnostics.is_clean() {
+            verification.additions_root = CommitmentCheck::Unverifiable;
+            verification.removals_root = CommitmentCheck::Unverifiable;
+            verification.filter_hash = CommitmentCheck::Unverifiable;
+        } else if let Some(ftb) = transaction_block {
+            let filter = transactions_filter(&parsed.coin_creations, &parsed.coin_removals);
+            verification.additions_root = CommitmentCheck::compare(
+                Some(ftb.additions_root),
+                Some(additions_root(&parsed.coin_additions)),
+            );
+            verification.removals_root = CommitmentCheck::compare(
+                Some(ftb.removals_root),
+                Some(removals_root(&parsed.coin_removals)),
+            );
+            verification.filter_hash =
+                CommitmentCheck::compare(Some(ftb.filter_hash), Some(filter_hash(&filter)));
+        }
+
+        Ok(verification)
+    }
+
+    /// Parse a HeaderBlock, which has everything but the generator
+    pub fn parse_header_block(&self, block: &HeaderBlock) -> Result<ParsedHeaderBlock> {
+        info!(
+            "Parsing HeaderBlock at height {}",
+            block.reward_chain_block.height
+        );
+
+        Ok(ParsedHeaderBlock {
+            height: block.reward_chain_block.height,
+            weight: block.reward_chain_block.weight,
+            header_hash: self.calculate_header_hash(&block.foliage)?,
+            timestamp: block
+                .foliage_transaction_block
+                .as_ref()
+                .map(|ftb| ftb.timestamp as u32),
+            farming: self.farming_info(&block.reward_chain_block, &block.foliage),
+            reward_claims: self.extract_reward_claims(block.transactions_info.as_ref()),
+            transactions_filter: block.transactions_filter.clone(),
+        })
+    }
+
+    /// Parse a HeaderBlock from bytes
+    pub fn parse_header_block_from_bytes(&self, block_bytes: &[u8]) -> Result<ParsedHeaderBlock> {
+        let block = HeaderBlock::from_bytes(block_bytes).map_err(|e| {
+            GeneratorParserError::InvalidBlockFormat(format!(
+                "Failed to deserialize HeaderBlock: {}",
+                e
+            ))
+        })?;
+
+        self.parse_header_block(&block)
+    }
+
+    /// Farmer and pool attribution from a block's proof of space and foliage
+    fn farming_info(
+        &self,
+        reward_chain_block: &RewardChainBlock,
+        foliage: &Foliage,
+    ) -> FarmingInfo {
+        let proof_of_space = &reward_chain_block.proof_of_space;
+        let foliage_block_data = &foliage.foliage_block_data;
+        let (plot_size, plot_version) = match proof_of_space.size() {
+            PlotSize::V1(size) => (size, 1),
+            PlotSize::V2(size) => (size, 2),
+        };
+
+        // Same rule as `is_overflow_block` in chia's pot_iterations
+        let signage_point_index = reward_chain_block.signage_point_index;
+        let overflow = signage_point_index
+            >= self.constants.num_sps_sub_slot - self.constants.num_sp_intervals_extra;
+
+        FarmingInfo {
+            plot_public_key: proof_of_space.plot_public_key,
+            pool_public_key: proof_of_space.pool_public_key,
+            pool_contract_puzzle_hash: proof_of_space.pool_contract_puzzle_hash,
+            farmer_reward_puzzle_hash: foliage_block_data.farmer_reward_puzzle_hash,
+            pool_target: foliage_block_data.pool_target.clone(),
+            signage_point_index,
+            plot_size,
+            plot_version,
+            overflow,
+        }
     }
 
-    /// Extract reward claims from block
-    fn extract_reward_claims(&self, block: &FullBlock) -> Vec<CoinInfo> {
-        match &block.transactions_info {
+    /// Extract reward claims from a block's transactions info
+    fn extract_reward_claims(&self, transactions_info: Option<&TransactionsInfo>) -> Vec<CoinInfo> {
+        match transactions_info {
             Some(tx_info) => tx_info
                 .reward_claims_incorporated
                 .iter()
@@ -124,76 +413,201 @@ impl BlockParser {
     }
 
     /// Process generator using chia-consensus to execute CLVM and extract coins
+    ///
+    /// The generator and each puzzle run once: every puzzle's output is both parsed for
+    /// the spend's details and fed to consensus condition processing, the way
+    /// `run_block_generator2` does it. The aggregate signature is validated against the
+    /// AGG_SIG conditions when given.
     fn process_generator_for_coins(
         &self,
         generator_bytes: &[u8],
-        _block_refs: &[u32],
-        _height: u32,
-    ) -> Result<(Vec<CoinInfo>, Vec<CoinSpendInfo>, Vec<CoinInfo>)> {
+        block_refs: &[u32],
+        height: u32,
+        signature: Option<&Signature>,
+    ) -> Result<GeneratorCoins> {
         info!("Processing generator for coins using CLVM execution");
 
         if generator_bytes.is_empty() {
-            return Ok((Vec::new(), Vec::new(), Vec::new()));
+            return Ok(GeneratorCoins::default());
         }
 
         // Create allocator for CLVM execution
         let mut allocator = make_allocator(clvmr::LIMIT_HEAP);
 
-        // TODO: Fetch actual block references for compressed blocks
-        // For now, use empty references
-        let generator_refs: Vec<&[u8]> = Vec::new();
-
-        // Use test constants (similar to mainnet)
-        let constants = TEST_CONSTANTS;
-        let max_cost = constants.max_block_cost_clvm;
-        let flags = DONT_VALIDATE_SIGNATURE;
-        let signature = Signature::default();
-
-        // Parse generator node
-        let generator_node = match node_from_bytes_backrefs(&mut allocator, generator_bytes) {
-            Ok(node) => node,
+        // Fetch the generators of referenced blocks for compressed blocks
+        let ref_generators = match self.resolve_generator_refs(block_refs) {
+            Ok(generators) => generators,
             Err(e) => {
-                info!("Failed to parse generator: {:?}", e);
-                return Ok((Vec::new(), Vec::new(), Vec::new()));
+                return self.generator_failure(ParseFailure {
+                    stage: ParseStage::ResolveGeneratorRefs,
+                    error_code: None,
+                    message: format!(
+                        "Failed to resolve {} generator refs for block {}: {}",
+                        block_refs.len(),
+                        height,
+                        e
+                    ),
+                    spend_index: None,
+                });
             }
         };
+        let generator_refs: Vec<&[u8]> = ref_generators.iter().map(|g| g.as_slice()).collect();
+
+        // Run under the consensus rules in effect at this height
+        let constants = &self.constants;
+        let max_cost = constants.max_block_cost_clvm;
+        // AGG_SIG pairs are only collected when the signature is going to be checked
+        let mut flags = flags_for_height(height, constants);
+        if signature.is_none() {
+            flags |= DONT_VALIDATE_SIGNATURE;
+        }
+
+        // Parse generator node, recording back references so shared puzzles are hashed once
+        let (generator_node, backrefs) =
+            match node_from_bytes_backrefs_record(&mut allocator, generator_bytes) {
+                Ok(result) => result,
+                Err(e) => {
+                    return self.generator_failure(ParseFailure {
+                        stage: ParseStage::DeserializeGenerator,
+                        error_code: None,
+                        message: format!("Failed to parse generator: {e}"),
+                        spend_index: None,
+                    });
+                }
+            };
 
         // Setup arguments
         let args = match setup_generator_args(&mut allocator, &generator_refs) {
             Ok(args) => args,
             Err(e) => {
-                info!("Failed to setup generator args: {:?}", e);
-                return Ok((Vec::new(), Vec::new(), Vec::new()));
+                return self
+                    .generator_failure(validation_failure(ParseStage::SetupGeneratorArgs, e));
             }
         };
 
         // Run the generator to get the list of coin spends
-        let generator_output =
+        let Reduction(generator_cost, generator_output) =
             match self.run_generator(&mut allocator, generator_node, args, max_cost, flags) {
-                Ok(output) => output,
+                Ok(reduction) => reduction,
                 Err(e) => {
-                    info!("Failed to run generator: {:?}", e);
-                    return Ok((Vec::new(), Vec::new(), Vec::new()));
+                    return self.generator_failure(validation_failure(ParseStage::RunGenerator, e));
                 }
             };
 
-        // Also run block generator2 to get spend conditions (for CREATE_COIN)
-        let spend_bundle_conditions = self.get_spend_bundle_conditions(
+        let byte_cost = generator_bytes.len() as u64 * constants.cost_per_byte;
+        let consensus = ConsensusConditions::new(&allocator, max_cost, byte_cost, generator_cost);
+
+        self.process_spend_list(
             &mut allocator,
-            generator_bytes,
-            &generator_refs,
-            max_cost,
+            generator_output,
+            &backrefs,
+            consensus,
             flags,
-            &signature,
-            &constants,
-        );
+            signature,
+        )
+    }
+
+    /// Parse the coin spends of a generator's output and validate their conditions
+    ///
+    /// When validation fails, created coins and fees come from the spends' own
+    /// CREATE_COIN conditions instead, with the failure in the diagnostics.
+    fn process_spend_list(
+        &self,
+        allocator: &mut Allocator,
+        generator_output: NodePtr,
+        backrefs: &HashSet<NodePtr>,
+        mut consensus: ConsensusConditions,
+        flags: u32,
+        signature: Option<&Signature>,
+    ) -> Result<GeneratorCoins> {
+        let mut diagnostics = ParseDiagnostics::default();
 
         // Extract coin spends from generator output
-        self.extract_coin_spends_from_output(
-            &mut allocator,
+        let spends = self.extract_coin_spends_from_output(
+            allocator,
             generator_output,
-            &spend_bundle_conditions,
-        )
+            backrefs,
+            &mut consensus,
+            flags,
+            &mut diagnostics,
+        );
+
+        let (spend_bundle_conditions, signature_validation) =
+            match consensus.finish(allocator, flags, signature, self.bls_cache.as_deref()) {
+                Ok((conditions, signature_validation)) => (Some(conditions), signature_validation),
+                Err(e) => {
+                    let failure = validation_failure(ParseStage::RunBlockGenerator, e);
+                    warn!("Conditions failed validation: {}", failure.message);
+                    diagnostics.failures.push(failure);
+                    (None, SignatureValidation::NotValidated)
+                }
+            };
+
+        let (coin_removals, coin_spends, coin_creations) =
+            match_spend_conditions(spends, spend_bundle_conditions.as_ref(), &mut diagnostics);
+
+        if self.strict {
+            if let Some(failure) = diagnostics.failures.first() {
+                return Err(GeneratorParserError::GeneratorFailed(failure.clone()));
+            }
+        }
+
+        let costs = match &spend_bundle_conditions {
+            Some(conditions) => BlockCosts::from(conditions),
+            None => BlockCosts {
+                fees: unvalidated_fees(&coin_removals, &coin_creations),
+                ..Default::default()
+            },
+        };
+
+        Ok(GeneratorCoins {
+            coin_removals,
+            coin_spends,
+            coin_creations,
+            costs,
+            signature_validation,
+            diagnostics,
+        })
+    }
+
+    /// A failure that stops generator processing: an error in strict mode, otherwise a
+    /// result without coin data that carries the failure in its diagnostics
+    fn generator_failure(&self, failure: ParseFailure) -> Result<GeneratorCoins> {
+        warn!(
+            "Generator failed at {}: {}",
+            failure.stage.name(),
+            failure.message
+        );
+
+        if self.strict {
+            return Err(GeneratorParserError::GeneratorFailed(failure));
+        }
+
+        Ok(GeneratorCoins {
+            diagnostics: ParseDiagnostics {
+                failures: vec![failure],
+            },
+            ..Default::default()
+        })
+    }
+
+    /// Fetch the generator bytecode for each height in `transactions_generator_ref_list`
+    fn resolve_generator_refs(&self, block_refs: &[u32]) -> Result<Vec<Vec<u8>>> {
+        if block_refs.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        let Some(lookup) = &self.generator_lookup else {
+            return Err(GeneratorParserError::GeneratorLookupError(format!(
+                "block references {} generators but no generator lookup is configured",
+                block_refs.len()
+            )));
+        };
+
+        block_refs
+            .iter()
+            .map(|&ref_height| lookup.get_generator(ref_height))
+            .collect()
     }
 
     /// Run the generator program
@@ -204,180 +618,244 @@ impl BlockParser {
         args: NodePtr,
         max_cost: u64,
         flags: u32,
-    ) -> Result<NodePtr> {
+    ) -> std::result::Result<Reduction, ValidationErr> {
         let dialect = ChiaDialect::new(flags);
-        let reduction = run_program(allocator, &dialect, generator_node, args, max_cost)
-            .map_err(|e| GeneratorParserError::ClvmExecutionError(format!("{:?}", e)))?;
-        Ok(reduction.1) // Get the result NodePtr
-    }
-
-    /// Get spend bundle conditions from generator
-    fn get_spend_bundle_conditions(
-        &self,
-        allocator: &mut Allocator,
-        generator_bytes: &[u8],
-        generator_refs: &Vec<&[u8]>,
-        max_cost: u64,
-        flags: u32,
-        signature: &Signature,
-        constants: &ConsensusConstants,
-    ) -> SpendBundleConditions {
-        match run_block_generator2(
+        Ok(run_program(
             allocator,
-            generator_bytes,
-            generator_refs.clone(),
+            &dialect,
+            generator_node,
+            args,
             max_cost,
-            flags,
-            signature,
-            None, // No BLS cache
-            constants,
-        ) {
-            Ok(conditions) => conditions,
-            Err(e) => {
-                info!(
-                    "Failed to execute generator with run_block_generator2: {:?}",
-                    e
-                );
-                SpendBundleConditions::default()
-            }
-        }
+        )?)
     }
 
-    /// Extract coin spends from generator output
+    /// Extract coin spends from generator output, running each spend's puzzle once
     fn extract_coin_spends_from_output(
         &self,
         allocator: &mut Allocator,
         generator_output: NodePtr,
-        spend_bundle_conditions: &SpendBundleConditions,
-    ) -> Result<(Vec<CoinInfo>, Vec<CoinSpendInfo>, Vec<CoinInfo>)> {
-        let mut coin_spends = Vec::new();
-        let mut coins_created = Vec::new();
-        let mut coins_spent = Vec::new();
+        backrefs: &HashSet<NodePtr>,
+        consensus: &mut ConsensusConditions,
+        flags: u32,
+        diagnostics: &mut ParseDiagnostics,
+    ) -> Vec<ParsedSpend> {
+        let mut spends = Vec::new();
 
         // Parse the generator output to extract coin spends
-        let Ok(spends_list) = first(allocator, generator_output) else {
-            return Ok((coins_spent, coin_spends, coins_created));
+        let spends_list = match first(allocator, generator_output) {
+            Ok(spends_list) => spends_list,
+            Err(e) => {
+                consensus.fail(e);
+                return spends;
+            }
         };
 
+        let mut tree_hashes = HashMap::new();
         let mut iter = spends_list;
-        let mut spend_index = 0;
+        let mut next_position = 0;
 
-        while let Ok(Some((coin_spend, next_iter))) = next(allocator, iter) {
+        while let Some((coin_spend, next_iter)) = allocator.next(iter) {
             iter = next_iter;
+            let position = next_position;
+            next_position += 1;
 
-            if let Some(spend_info) = self.parse_single_coin_spend(
+            let spend_run = self.run_spend(
                 allocator,
                 coin_spend,
-                spend_index,
-                spend_bundle_conditions,
-            ) {
-                coins_spent.push(spend_info.coin.clone());
-
-                // Add created coins
-                for created_coin in &spend_info.created_coins {
-                    coins_created.push(created_coin.clone());
-                }
+                backrefs,
+                &mut tree_hashes,
+                consensus.spend_cost_limit(),
+                flags,
+            );
+            let condition_cost = consensus.add_spend(
+                allocator,
+                coin_spend,
+                spend_run.as_ref(),
+                flags,
+                &self.constants,
+            );
 
-                coin_spends.push(spend_info);
-                spend_index += 1;
-            }
+            let Some(spend) = spend_run.and_then(|spend_run| {
+                self.parse_single_coin_spend(
+                    allocator,
+                    coin_spend,
+                    spend_run,
+                    condition_cost,
+                    position,
+                    diagnostics,
+                )
+            }) else {
+                diagnostics.failures.push(ParseFailure {
+                    stage: ParseStage::ParseCoinSpend,
+                    error_code: None,
+                    message: "Malformed coin spend in generator output".to_string(),
+                    spend_index: Some(position),
+                });
+                continue;
+            };
+
+            spends.push(spend);
         }
 
-        info!(
-            "CLVM execution extracted {} spends, {} coins created",
-            coin_spends.len(),
-            coins_created.len()
-        );
+        if allocator.atom_len(iter) != 0 {
+            consensus.fail(ValidationErr(iter, ErrorCode::GeneratorRuntimeError));
+        }
+
+        spends
+    }
+
+    /// Run a spend's puzzle with its solution, using at most `max_cost`
+    fn run_spend(
+        &self,
+        allocator: &mut Allocator,
+        coin_spend: NodePtr,
+        backrefs: &HashSet<NodePtr>,
+        tree_hashes: &mut HashMap<NodePtr, TreeHash>,
+        max_cost: Cost,
+        flags: u32,
+    ) -> Option<SpendRun> {
+        let rest1 = rest(allocator, coin_spend).ok()?;
+        let puzzle = first(allocator, rest1).ok()?;
+        let rest2 = rest(allocator, rest1).ok()?;
+        let rest3 = rest(allocator, rest2).ok()?;
+        let solution = first(allocator, rest3).ok()?;
+
+        let puzzle_hash = tree_hash_cached(allocator, puzzle, backrefs, tree_hashes);
+        // clvmr takes a limit of 0 as no limit at all
+        let output = if max_cost == 0 {
+            Err(ValidationErr(puzzle, ErrorCode::CostExceeded))
+        } else {
+            run_puzzle(allocator, puzzle, solution, max_cost, flags)
+        };
 
-        Ok((coins_spent, coin_spends, coins_created))
+        Some(SpendRun {
+            puzzle,
+            solution,
+            puzzle_hash,
+            output,
+        })
     }
 
     /// Parse a single coin spend from the generator output
+    ///
+    /// Created coins are filled in by `match_spend_conditions` once the block's
+    /// conditions have been validated. `condition_cost` is what consensus charged for the
+    /// spend's conditions, `None` when it didn't process the spend. `position` is the
+    /// spend's position in the generator output.
     fn parse_single_coin_spend(
         &self,
         allocator: &mut Allocator,
         coin_spend: NodePtr,
-        spend_index: usize,
-        spend_bundle_conditions: &SpendBundleConditions,
-    ) -> Option<CoinSpendInfo> {
-        // Extract parent coin info
-        let parent_bytes = self.extract_parent_coin_info(allocator, coin_spend)?;
-        info!("🔍 DEBUG: parent_bytes length = {}", parent_bytes.len());
-
-        if parent_bytes.len() != 32 {
-            info!(
-                "❌ ERROR: parent_bytes wrong length: {} bytes (expected 32)",
-                parent_bytes.len()
-            );
-            return None;
-        }
+        spend_run: SpendRun,
+        condition_cost: Option<Cost>,
+        position: u32,
+        diagnostics: &mut ParseDiagnostics,
+    ) -> Option<ParsedSpend> {
+        let SpendRun {
+            puzzle,
+            solution,
+            puzzle_hash,
+            output,
+        } = spend_run;
 
-        // parent_bytes is already Vec<u8> with 32 bytes, just hex encode it directly
-        let parent_hex = hex::encode(&parent_bytes);
-        info!(
-            "🔍 DEBUG: parent_coin_info hex = {} (length: {})",
-            parent_hex,
-            parent_hex.len()
-        );
+        // Extract parent coin info
+        let parent_coin_info = self.extract_parent_coin_info(allocator, coin_spend)?;
 
-        // Extract puzzle, amount, and solution
+        // Extract amount
         let rest1 = rest(allocator, coin_spend).ok()?;
-        let puzzle = first(allocator, rest1).ok()?;
-
         let rest2 = rest(allocator, rest1).ok()?;
         let amount_node = first(allocator, rest2).ok()?;
         let amount_atom = atom(allocator, amount_node, ErrorCode::InvalidCoinAmount).ok()?;
         let amount = u64_from_bytes(amount_atom.as_ref());
 
-        let rest3 = rest(allocator, rest2).ok()?;
-        let solution = first(allocator, rest3).ok()?;
-
-        // Calculate puzzle hash
-        let puzzle_hash_vec = tree_hash(allocator, puzzle);
-        info!(
-            "🔍 DEBUG: tree_hash returned {} bytes",
-            puzzle_hash_vec.len()
-        );
-
-        if puzzle_hash_vec.len() != 32 {
-            info!(
-                "❌ ERROR: tree_hash returned wrong length: {} bytes (expected 32)",
-                puzzle_hash_vec.len()
-            );
-            return None;
-        }
+        // Create coin info
+        let coin_info = CoinInfo::new(parent_coin_info, puzzle_hash.into(), amount);
 
-        // tree_hash returns Vec<u8> with 32 bytes, just hex encode it directly
-        let puzzle_hash_hex = hex::encode(&puzzle_hash_vec);
-        info!(
-            "🔍 DEBUG: puzzle_hash hex = {} (length: {})",
-            puzzle_hash_hex,
-            puzzle_hash_hex.len()
-        );
+        // SpendBundleConditions drops announcements, messages and memos, so the puzzle
+        // output is parsed for the full condition list
+        let create_coins = output
+            .as_ref()
+            .map(|(_, output)| parse_create_coins(allocator, *output))
+            .unwrap_or_default();
+        let matches_filter = self.spend_matches_filter(&coin_info, &create_coins);
 
-        // Create coin info
-        let coin_info = CoinInfo {
-            parent_coin_info: parent_hex,
-            puzzle_hash: puzzle_hash_hex,
-            amount,
+        // A spend left out by the coin filter isn't reported, so neither is its index
+        let output = match output {
+            Ok(output) => Some(output),
+            Err(e) => {
+                warn!("Failed to run puzzle for spend {}: {:?}", position, e.1);
+                diagnostics.failures.push(ParseFailure {
+                    spend_index: matches_filter.then_some(position),
+                    ..validation_failure(ParseStage::RunPuzzle, e)
+                });
+                None
+            }
         };
 
+        // Spends outside the coin filter only keep what's needed for the block's coins
+        if !matches_filter {
+            return Some(ParsedSpend {
+                position,
+                coin: coin_info,
+                info: None,
+                create_coins,
+            });
+        }
+
         // Serialize puzzle reveal and solution
         let puzzle_reveal = node_to_bytes(allocator, puzzle).ok()?;
         let solution_bytes = node_to_bytes(allocator, solution).ok()?;
 
-        // Get created coins from conditions
-        let created_coins = self.extract_created_coins(spend_index, spend_bundle_conditions);
+        let execution_cost = output.map(|(cost, _)| cost).unwrap_or_default();
+        let conditions = output
+            .map(|(_, output)| parse_conditions(allocator, output))
+            .unwrap_or_default();
+
+        let puzzle_kind = identify_puzzle(allocator, puzzle);
+        let puzzle_disassembly = self
+            .disassembly_limit
+            .map(|max_len| disassemble(allocator, puzzle, max_len));
+        let solution_disassembly = self
+            .disassembly_limit
+            .map(|max_len| disassemble(allocator, solution, max_len));
+
+        Some(ParsedSpend {
+            position,
+            coin: coin_info.clone(),
+            info: Some(CoinSpendInfo {
+                conditions,
+                execution_cost,
+                condition_cost: condition_cost.unwrap_or_default(),
+                puzzle_kind,
+                puzzle_disassembly,
+                solution_disassembly,
+                ..CoinSpendInfo::new(
+                    coin_info,
+                    puzzle_reveal.into(),
+                    solution_bytes.into(),
+                    true,
+                    "From transaction generator".to_string(),
+                    0,
+                    Vec::new(),
+                )
+            }),
+            create_coins,
+        })
+    }
 
-        Some(CoinSpendInfo::new(
-            coin_info,
-            hex::encode(puzzle_reveal),
-            hex::encode(solution_bytes),
-            true,
-            "From transaction generator".to_string(),
-            0,
-            created_coins,
-        ))
+    /// Whether a spend passes the coin filter, through its coin or a coin it creates
+    fn spend_matches_filter(&self, coin: &CoinInfo, create_coins: &[CreateCoinCondition]) -> bool {
+        let Some(filter) = &self.coin_filter else {
+            return true;
+        };
+
+        filter.matches_coin(coin)
+            || create_coins.iter().any(|create_coin| {
+                let created =
+                    CoinInfo::new(coin.coin_id, create_coin.puzzle_hash, create_coin.amount)
+                        .with_memos(&create_coin.memos);
+                filter.matches_coin(&created)
+            })
     }
 
     /// Extract parent coin info from a coin spend node
@@ -385,38 +863,10 @@ impl BlockParser {
         &self,
         allocator: &mut Allocator,
         coin_spend: NodePtr,
-    ) -> Option<Vec<u8>> {
+    ) -> Option<Bytes32> {
         let first_node = first(allocator, coin_spend).ok()?;
         let parent_atom = atom(allocator, first_node, ErrorCode::InvalidParentId).ok()?;
-        let parent_bytes = parent_atom.as_ref();
-
-        if parent_bytes.len() == 32 {
-            Some(parent_bytes.to_vec())
-        } else {
-            None
-        }
-    }
-
-    /// Extract created coins from spend bundle conditions
-    fn extract_created_coins(
-        &self,
-        spend_index: usize,
-        spend_bundle_conditions: &SpendBundleConditions,
-    ) -> Vec<CoinInfo> {
-        if spend_index >= spend_bundle_conditions.spends.len() {
-            return Vec::new();
-        }
-
-        let spend_cond = &spend_bundle_conditions.spends[spend_index];
-        spend_cond
-            .create_coin
-            .iter()
-            .map(|new_coin| CoinInfo {
-                parent_coin_info: hex::encode(spend_cond.coin_id.as_ref()),
-                puzzle_hash: hex::encode(&new_coin.puzzle_hash),
-                amount: new_coin.amount,
-            })
-            .collect()
+        Bytes32::try_from(parent_atom.as_ref()).ok()
     }
 
     /// Parse a full block from bytes (for backwards compatibility)
@@ -432,126 +882,2040 @@ impl BlockParser {
         self.parse_full_block(&block)
     }
 
-    /// Extract generator block info from a FullBlock
-    pub fn parse_block_info(&self, block: &FullBlock) -> Result<GeneratorBlockInfo> {
-        Ok(GeneratorBlockInfo {
-            prev_header_hash: block.foliage.prev_block_hash,
-            transactions_generator: block
-                .transactions_generator
-                .as_ref()
-                .map(|g| g.clone().into()),
-            transactions_generator_ref_list: block.transactions_generator_ref_list.clone(),
-        })
+    /// Parse a full block from a hex string
+    pub fn parse_full_block_from_hex(&self, block_hex: &str) -> Result<ParsedBlock> {
+        let block_bytes = hex::decode(block_hex)?;
+        self.parse_full_block_from_bytes(&block_bytes)
     }
 
-    /// Extract just the generator from a FullBlock
-    pub fn extract_generator_from_block(&self, block: &FullBlock) -> Result<Option<Vec<u8>>> {
-        Ok(block.transactions_generator.as_ref().map(|g| g.to_vec()))
-    }
+    /// Parse a SpendBundle, such as a mempool item or an offer, into the coin data a
+    /// block with the same spends would have
+    ///
+    /// Spends run under the network's latest consensus rules and go through the same
+    /// condition extraction as a block's generator. The aggregate signature is validated
+    /// when signature validation is enabled.
+    pub fn parse_spend_bundle(&self, spend_bundle: &SpendBundle) -> Result<ParsedSpendBundle> {
+        info!(
+            "Parsing SpendBundle with {} coin spends",
+            spend_bundle.coin_spends.len()
+        );
 
-    /// Get block height and transaction status from a FullBlock
-    pub fn get_height_and_tx_status_from_block(
-        &self,
-        block: &FullBlock,
-    ) -> Result<BlockHeightInfo> {
-        Ok(BlockHeightInfo {
-            height: block.reward_chain_block.height,
-            is_transaction_block: block.foliage_transaction_block.is_some(),
+        let GeneratorCoins {
+            mut coin_removals,
+            mut coin_spends,
+            mut coin_creations,
+            costs,
+            signature_validation,
+            diagnostics,
+        } = self.process_spend_bundle_for_coins(spend_bundle)?;
+
+        mark_ephemeral_coins(&mut coin_removals, &mut coin_spends, &mut coin_creations);
+        let hint_index = build_hint_index(&coin_creations);
+
+        Ok(ParsedSpendBundle {
+            coin_removals,
+            coin_spends,
+            coin_creations,
+            hint_index,
+            cost: costs.cost,
+            execution_cost: costs.execution_cost,
+            condition_cost: costs.condition_cost,
+            fees: costs.fees,
+            reserve_fee: costs.reserve_fee,
+            signature_validation,
+            diagnostics,
         })
     }
 
-    /*
-    /// Parse generator from hex string
+    /// Parse a SpendBundle from bytes
+    pub fn parse_spend_bundle_from_bytes(&self, bundle_bytes: &[u8]) -> Result<ParsedSpendBundle> {
+        let spend_bundle = SpendBundle::from_bytes(bundle_bytes).map_err(|e| {
+            GeneratorParserError::SerializationError(format!(
+                "Failed to deserialize SpendBundle: {}",
+                e
+            ))
+        })?;
+
+        self.parse_spend_bundle(&spend_bundle)
+    }
+
+    /// Parse a SpendBundle from a hex string
+    pub fn parse_spend_bundle_from_hex(&self, bundle_hex: &str) -> Result<ParsedSpendBundle> {
+        let bundle_bytes = hex::decode(bundle_hex)?;
+        self.parse_spend_bundle_from_bytes(&bundle_bytes)
+    }
+
+    /// Run a spend bundle's spends as the output of a generator listing them
+    ///
+    /// Costs follow `run_spendbundle`: the bundle pays for the size of that generator,
+    /// without its quote, and each puzzle reveal must hash to its coin's puzzle hash.
+    fn process_spend_bundle_for_coins(&self, spend_bundle: &SpendBundle) -> Result<GeneratorCoins> {
+        let mut allocator = make_allocator(clvmr::LIMIT_HEAP);
+
+        let constants = &self.constants;
+        let max_cost = constants.max_block_cost_clvm;
+        let signature = self
+            .bls_cache
+            .as_ref()
+            .map(|_| &spend_bundle.aggregated_signature);
+        let mut flags = flags_for_height(u32::MAX, constants);
+        if signature.is_none() {
+            flags |= DONT_VALIDATE_SIGNATURE;
+        }
+
+        let generator_length = calculate_generator_length(&spend_bundle.coin_spends);
+        let byte_cost =
+            generator_length.saturating_sub(QUOTE_BYTES) as u64 * constants.cost_per_byte;
+        let mut consensus = ConsensusConditions::new(&allocator, max_cost, byte_cost, 0);
+        let mut diagnostics = ParseDiagnostics::default();
+
+        // A coin spend that can't be parsed fails validation, but the rest are still parsed.
+        // Failures refer to spends by their index in `coin_spends`, so the index of each
+        // spend in the spend list is kept.
+        let mut spends = Vec::with_capacity(spend_bundle.coin_spends.len());
+        let mut bundle_indices = Vec::with_capacity(spend_bundle.coin_spends.len());
+        for (index, coin_spend) in spend_bundle.coin_spends.iter().enumerate() {
+            let (spend, puzzle) = match coin_spend_node(&mut allocator, coin_spend) {
+                Ok(nodes) => nodes,
+                Err(e) => {
+                    warn!("Failed to parse coin spend {}: {}", index, e);
+                    consensus.fail(ValidationErr(
+                        allocator.nil(),
+                        ErrorCode::InvalidSpendBundle,
+                    ));
+                    diagnostics.failures.push(ParseFailure {
+                        stage: ParseStage::ParseCoinSpend,
+                        error_code: None,
+                        message: format!("Failed to parse coin spend: {e}"),
+                        spend_index: Some(index as u32),
+                    });
+                    continue;
+                }
+            };
+
+            if Bytes32::from(tree_hash(&allocator, puzzle)) != coin_spend.coin.puzzle_hash {
+                consensus.fail(ValidationErr(puzzle, ErrorCode::WrongPuzzleHash));
+            }
+            spends.push(spend);
+            bundle_indices.push(index as u32);
+        }
+
+        // The spends are wrapped the way a generator outputs them: `(spends)`
+        let mut spend_list = allocator.nil();
+        for spend in spends.into_iter().rev() {
+            spend_list = allocator
+                .new_pair(spend, spend_list)
+                .map_err(|e| GeneratorParserError::ClvmExecutionError(e.to_string()))?;
+        }
+        let output = allocator
+            .new_pair(spend_list, allocator.nil())
+            .map_err(|e| GeneratorParserError::ClvmExecutionError(e.to_string()))?;
+
+        if self.strict {
+            if let Some(failure) = diagnostics.failures.first() {
+                return Err(GeneratorParserError::GeneratorFailed(failure.clone()));
+            }
+        }
+
+        let to_bundle_index = |mut failure: ParseFailure| {
+            failure.spend_index = failure
+                .spend_index
+                .and_then(|position| bundle_indices.get(position as usize).copied());
+            failure
+        };
+        let mut coins = self
+            .process_spend_list(
+                &mut allocator,
+                output,
+                &HashSet::new(),
+                consensus,
+                flags,
+                signature,
+            )
+            .map_err(|e| match e {
+                GeneratorParserError::GeneratorFailed(failure) => {
+                    GeneratorParserError::GeneratorFailed(to_bundle_index(failure))
+                }
+                e => e,
+            })?;
+        diagnostics
+            .failures
+            .extend(coins.diagnostics.failures.drain(..).map(to_bundle_index));
+        coins.diagnostics = diagnostics;
+        Ok(coins)
+    }
+
+    /// Parse many FullBlocks on the rayon thread pool
+    ///
+    /// Results are in input order, one per block, so a block that fails to parse doesn't
+    /// fail the batch. Generator refs to other blocks of the batch are resolved from the
+    /// batch itself; the rest go through the generator lookup.
+    pub fn parse_blocks_batch(&self, blocks: &[FullBlock]) -> Vec<Result<ParsedBlock>> {
+        let blocks: Vec<&FullBlock> = blocks.iter().collect();
+        let batch_parser = self.with_batch_generators(&blocks);
+        let parser = batch_parser.as_ref().unwrap_or(self);
+
+        blocks
+            .par_iter()
+            .map(|block| parser.parse_full_block(block))
+            .collect()
+    }
+
+    /// Parse many serialized FullBlocks on the rayon thread pool, like `parse_blocks_batch`
+    pub fn parse_blocks_batch_from_bytes<B>(&self, blocks: &[B]) -> Vec<Result<ParsedBlock>>
+    where
+        B: AsRef<[u8]> + Sync,
+    {
+        let blocks: Vec<Result<FullBlock>> = blocks
+            .par_iter()
+            .map(|block_bytes| {
+                FullBlock::from_bytes(block_bytes.as_ref()).map_err(|e| {
+                    GeneratorParserError::InvalidBlockFormat(format!(
+                        "Failed to deserialize FullBlock: {}",
+                        e
+                    ))
+                })
+            })
+            .collect();
+
+        let valid_blocks: Vec<&FullBlock> = blocks.iter().flatten().collect();
+        let batch_parser = self.with_batch_generators(&valid_blocks);
+        let parser = batch_parser.as_ref().unwrap_or(self);
+
+        blocks
+            .into_par_iter()
+            .map(|block| parser.parse_full_block(&block?))
+            .collect()
+    }
+
+    /// A copy of this parser that resolves generator refs from `blocks` first, when any
+    /// block references another one
+    fn with_batch_generators(&self, blocks: &[&FullBlock]) -> Option<BlockParser> {
+        let lookup = BatchGeneratorLookup::new(blocks, self.generator_lookup.clone())?;
+        Some(BlockParser {
+            generator_lookup: Some(Arc::new(lookup)),
+            ..self.clone()
+        })
+    }
+
+    /// Extract generator block info from a FullBlock
+    pub fn parse_block_info(&self, block: &FullBlock) -> Result<GeneratorBlockInfo> {
+        Ok(GeneratorBlockInfo {
+            prev_header_hash: block.foliage.prev_block_hash,
+            transactions_generator: block
+                .transactions_generator
+                .as_ref()
+                .map(|g| g.clone().into()),
+            transactions_generator_ref_list: block.transactions_generator_ref_list.clone(),
+        })
+    }
+
+    /// Extract just the generator from a FullBlock
+    pub fn extract_generator_from_block(&self, block: &FullBlock) -> Result<Option<Vec<u8>>> {
+        Ok(block.transactions_generator.as_ref().map(|g| g.to_vec()))
+    }
+
+    /// Get block height and transaction status from a FullBlock
+    pub fn get_height_and_tx_status_from_block(
+        &self,
+        block: &FullBlock,
+    ) -> Result<BlockHeightInfo> {
+        Ok(BlockHeightInfo {
+            height: block.reward_chain_block.height,
+            is_transaction_block: block.foliage_transaction_block.is_some(),
+        })
+    }
+
+    /// Parse a standalone generator from a hex string, see `parse_generator_from_bytes`
     pub fn parse_generator_from_hex(&self, generator_hex: &str) -> Result<ParsedGenerator> {
-        let generator_bytes =
-            hex::decode(generator_hex).map_err(|e| ParseError::HexDecodingError(e))?;
+        let generator_bytes = hex::decode(generator_hex)?;
         self.parse_generator_from_bytes(&generator_bytes)
     }
 
-    /// Parse generator from bytes
+    /// Run a generator that isn't part of a block and analyze its composition
+    ///
+    /// The generator runs under the network's latest consensus rules, without generator
+    /// refs or signature validation.
     pub fn parse_generator_from_bytes(&self, generator_bytes: &[u8]) -> Result<ParsedGenerator> {
-        // Create a dummy GeneratorBlockInfo for now
+        self.parse_generator(generator_bytes, &[], u32::MAX)
+    }
+
+    /// Analyze a standalone generator, see `parse_generator_from_bytes`
+    pub fn analyze_generator(&self, generator_bytes: &[u8]) -> Result<GeneratorAnalysis> {
+        Ok(self.parse_generator_from_bytes(generator_bytes)?.analysis)
+    }
+
+    /// Analyze the generator of a block, or `None` when it has none
+    ///
+    /// Generator refs are resolved through the generator lookup, as when parsing the block.
+    pub fn analyze_block_generator(&self, block: &FullBlock) -> Result<Option<GeneratorAnalysis>> {
+        block
+            .transactions_generator
+            .as_ref()
+            .map(|generator| {
+                self.parse_generator(
+                    generator,
+                    &block.transactions_generator_ref_list,
+                    block.reward_chain_block.height,
+                )
+                .map(|parsed| parsed.analysis)
+            })
+            .transpose()
+    }
+
+    /// Run a generator at `height` and analyze its spends and serialization
+    fn parse_generator(
+        &self,
+        generator_bytes: &[u8],
+        block_refs: &[u32],
+        height: u32,
+    ) -> Result<ParsedGenerator> {
+        let GeneratorCoins {
+            mut coin_removals,
+            mut coin_spends,
+            mut coin_creations,
+            costs,
+            diagnostics,
+            ..
+        } = self.process_generator_for_coins(generator_bytes, block_refs, height, None)?;
+        mark_ephemeral_coins(&mut coin_removals, &mut coin_spends, &mut coin_creations);
+
+        let size_bytes = generator_bytes.len() as u32;
+        let (uses_back_references, expanded_size_bytes) =
+            back_reference_stats(generator_bytes).unwrap_or((false, u64::from(size_bytes)));
+
+        let analysis = GeneratorAnalysis {
+            size_bytes,
+            expanded_size_bytes,
+            uses_back_references,
+            generator_ref_list: block_refs.to_vec(),
+            entropy: entropy(generator_bytes),
+            spend_count: coin_spends.len() as u32,
+            cost: costs.cost,
+            execution_cost: costs.execution_cost,
+            condition_cost: costs.condition_cost,
+            largest_puzzle_reveals: largest_puzzle_reveals(&coin_spends, LARGEST_PUZZLE_REVEALS),
+            repeated_puzzle_mods: repeated_puzzle_mods(&coin_spends),
+            diagnostics,
+        };
+
         Ok(ParsedGenerator {
-            block_info: GeneratorBlockInfo::new(
-                [0u8; 32].into(),
-                Some(generator_bytes.to_vec()),
-                vec![],
-            ),
-            generator_hex: Some(hex::encode(generator_bytes)),
-            analysis: self.analyze_generator(generator_bytes)?,
+            coin_spends,
+            coin_creations,
+            analysis,
         })
     }
+}
 
-    /// Analyze generator bytecode
-    pub fn analyze_generator(&self, generator_bytes: &[u8]) -> Result<GeneratorAnalysis> {
-        let size_bytes = generator_bytes.len();
-        let is_empty = generator_bytes.is_empty();
-
-        // Check for common CLVM patterns
-        let contains_clvm_patterns = generator_bytes.windows(2).any(|w| {
-            w == [0x01, 0x00] || // pair
-            w == [0x02, 0x00] || // cons
-            w == [0x03, 0x00] || // first
-            w == [0x04, 0x00]    // rest
-        });
+/// A coin spend in the shape of a generator's output,
+/// `(parent_id puzzle_reveal amount solution ())`, along with its puzzle
+fn coin_spend_node(
+    allocator: &mut Allocator,
+    coin_spend: &CoinSpend,
+) -> std::result::Result<(NodePtr, NodePtr), String> {
+    let puzzle = node_from_bytes(allocator, coin_spend.puzzle_reveal.as_ref())
+        .map_err(|e| format!("invalid puzzle reveal: {e}"))?;
+    let solution = node_from_bytes(allocator, coin_spend.solution.as_ref())
+        .map_err(|e| format!("invalid solution: {e}"))?;
+
+    let mut build = || {
+        let parent = allocator.new_atom(coin_spend.coin.parent_coin_info.as_ref())?;
+        let amount = allocator.new_number(coin_spend.coin.amount.into())?;
+        let mut spend = allocator.nil();
+        for node in [allocator.nil(), solution, amount, puzzle, parent] {
+            spend = allocator.new_pair(node, spend)?;
+        }
+        Ok::<_, EvalErr>(spend)
+    };
+    let spend = build().map_err(|e| e.1)?;
+    Ok((spend, puzzle))
+}
+
+/// Coins and costs extracted by running a block's generator
+#[derive(Default)]
+struct GeneratorCoins {
+    coin_removals: Vec<CoinInfo>,
+    coin_spends: Vec<CoinSpendInfo>,
+    coin_creations: Vec<CoinInfo>,
+    costs: BlockCosts,
+    signature_validation: SignatureValidation,
+    diagnostics: ParseDiagnostics,
+}
+
+/// Block-level cost and fee totals
+#[derive(Default)]
+struct BlockCosts {
+    cost: u64,
+    execution_cost: u64,
+    condition_cost: u64,
+    fees: u64,
+    reserve_fee: u64,
+}
+
+impl From<&SpendBundleConditions> for BlockCosts {
+    fn from(conditions: &SpendBundleConditions) -> Self {
+        // Reward coins are not part of the generator, so they are not in these amounts
+        let fees = conditions
+            .removal_amount
+            .saturating_sub(conditions.addition_amount);
+
+        Self {
+            cost: conditions.cost,
+            execution_cost: conditions.execution_cost,
+            condition_cost: conditions.condition_cost,
+            fees: u64::try_from(fees).unwrap_or(u64::MAX),
+            reserve_fee: conditions.reserve_fee,
+        }
+    }
+}
+
+/// A spend's puzzle and solution, with the result of running the puzzle
+struct SpendRun {
+    puzzle: NodePtr,
+    solution: NodePtr,
+    puzzle_hash: TreeHash,
+    output: std::result::Result<(Cost, NodePtr), ValidationErr>,
+}
+
+/// A coin spend read from the generator output, waiting for its created coins
+struct ParsedSpend {
+    /// Position in the generator output, which failures refer to
+    position: u32,
+    coin: CoinInfo,
+    /// `None` when the spend doesn't match the parser's coin filter
+    info: Option<CoinSpendInfo>,
+    create_coins: Vec<CreateCoinCondition>,
+}
 
-        // Check for coin patterns (32-byte sequences)
-        let contains_coin_patterns = generator_bytes.len() >= 32;
+/// Consensus processing of a block's spends, fed one spend at a time
+///
+/// Follows `run_block_generator2` step for step, using the puzzle outputs the parser
+/// already has instead of running every puzzle a second time.
+struct ConsensusConditions {
+    conditions: SpendBundleConditions,
+    state: ParseState,
+    max_cost: Cost,
+    cost_left: Cost,
+    error: Option<ValidationErr>,
+}
+
+impl ConsensusConditions {
+    fn new(allocator: &Allocator, max_cost: Cost, byte_cost: Cost, generator_cost: Cost) -> Self {
+        let mut consensus = Self {
+            conditions: SpendBundleConditions::default(),
+            state: ParseState::default(),
+            max_cost,
+            cost_left: max_cost,
+            error: None,
+        };
 
-        // Calculate simple entropy
-        let mut byte_counts = [0u64; 256];
-        for &byte in generator_bytes {
-            byte_counts[byte as usize] += 1;
+        if let Err(e) = subtract_cost(allocator, &mut consensus.cost_left, byte_cost)
+            .and_then(|()| subtract_cost(allocator, &mut consensus.cost_left, generator_cost))
+        {
+            consensus.fail(e);
         }
+        consensus.conditions.execution_cost += generator_cost;
+
+        consensus
+    }
+
+    /// Record the first error; later spends are no longer processed
+    fn fail(&mut self, error: ValidationErr) {
+        self.error.get_or_insert(error);
+    }
+
+    /// Cost the next spend's puzzle may use: what's left of the block's budget, as
+    /// `run_block_generator2` allows
+    ///
+    /// Once validation has failed the remaining budget no longer means anything, so
+    /// later puzzles get the whole block's budget and are still parsed.
+    fn spend_cost_limit(&self) -> Cost {
+        if self.error.is_some() {
+            self.max_cost
+        } else {
+            self.cost_left
+        }
+    }
+
+    /// Process the next spend, returning the cost charged for its conditions
+    ///
+    /// Returns `None` when the spend fails validation or an earlier spend already has.
+    fn add_spend(
+        &mut self,
+        allocator: &mut Allocator,
+        coin_spend: NodePtr,
+        spend_run: Option<&SpendRun>,
+        flags: u32,
+        constants: &ConsensusConstants,
+    ) -> Option<Cost> {
+        if self.error.is_some() {
+            return None;
+        }
+
+        match self.process_spend(allocator, coin_spend, spend_run, flags, constants) {
+            Ok(condition_cost) => Some(condition_cost),
+            Err(e) => {
+                self.fail(e);
+                None
+            }
+        }
+    }
+
+    fn process_spend(
+        &mut self,
+        allocator: &mut Allocator,
+        coin_spend: NodePtr,
+        spend_run: Option<&SpendRun>,
+        flags: u32,
+        constants: &ConsensusConstants,
+    ) -> std::result::Result<Cost, ValidationErr> {
+        let [parent_id, _puzzle, amount, _solution, _spend_level_extra] =
+            extract_n::<5>(allocator, coin_spend, ErrorCode::InvalidCondition)?;
+        let Some(spend_run) = spend_run else {
+            return Err(ValidationErr(coin_spend, ErrorCode::InvalidCondition));
+        };
+        let (clvm_cost, conditions) = spend_run.output?;
+
+        subtract_cost(allocator, &mut self.cost_left, clvm_cost)?;
+        self.conditions.execution_cost += clvm_cost;
+
+        let puzzle_hash = allocator.new_atom(spend_run.puzzle_hash.as_ref())?;
 
-        let total = generator_bytes.len() as f64;
-        let entropy = if total > 0.0 {
-            byte_counts.iter()
-                .filter(|&&count| count > 0)
-                .map(|&count| {
-                    let p = count as f64 / total;
-                    -p * p.log2()
+        // The block's condition cost only grows by what this spend's conditions add
+        let condition_cost = self.conditions.condition_cost;
+        process_single_spend::<EmptyVisitor>(
+            allocator,
+            &mut self.conditions,
+            &mut self.state,
+            parent_id,
+            puzzle_hash,
+            amount,
+            conditions,
+            flags,
+            &mut self.cost_left,
+            constants,
+        )?;
+
+        Ok(self.conditions.condition_cost - condition_cost)
+    }
+
+    /// Validate the block-wide conditions and, when given, the aggregate signature
+    ///
+    /// An invalid signature still returns the conditions, alongside
+    /// `SignatureValidation::Invalid`.
+    fn finish(
+        mut self,
+        allocator: &Allocator,
+        flags: u32,
+        signature: Option<&Signature>,
+        bls_cache: Option<&BlsCache>,
+    ) -> std::result::Result<(SpendBundleConditions, SignatureValidation), ValidationErr> {
+        if let Some(error) = self.error {
+            return Err(error);
+        }
+
+        validate_conditions(
+            allocator,
+            &self.conditions,
+            &self.state,
+            allocator.nil(),
+            flags,
+        )?;
+
+        let signature_validation = match signature {
+            Some(signature) => match validate_signature(&self.state, signature, flags, bls_cache) {
+                Ok(()) => {
+                    self.conditions.validated_signature = true;
+                    SignatureValidation::Valid
+                }
+                Err(ValidationErr(_, ErrorCode::BadAggregateSignature)) => {
+                    warn!("Block aggregate signature does not match its AGG_SIG conditions");
+                    SignatureValidation::Invalid
+                }
+                Err(e) => return Err(e),
+            },
+            None => SignatureValidation::NotValidated,
+        };
+
+        self.conditions.cost = self.max_cost - self.cost_left;
+        Ok((self.conditions, signature_validation))
+    }
+}
+
+/// Fill in each spend's created coins from its validated conditions
+///
+/// Spends are matched to their conditions by coin ID, so a spend missing from either
+/// side doesn't shift the others. Mismatches are reported in `diagnostics`. Without
+/// validated conditions, as for an offer whose announcements are asserted by spends it
+/// doesn't contain, each spend's own CREATE_COIN conditions are used. Spends left out by
+/// the coin filter only contribute their coins, and the coins they create don't keep
+/// their memos or `created_by_spend`.
+fn match_spend_conditions(
+    spends: Vec<ParsedSpend>,
+    spend_bundle_conditions: Option<&SpendBundleConditions>,
+    diagnostics: &mut ParseDiagnostics,
+) -> (Vec<CoinInfo>, Vec<CoinSpendInfo>, Vec<CoinInfo>) {
+    let mut conditions_by_coin: Option<HashMap<Bytes32, &SpendConditions>> =
+        spend_bundle_conditions.map(|conditions| {
+            conditions
+                .spends
+                .iter()
+                .map(|spend| (*spend.coin_id, spend))
+                .collect()
+        });
+
+    let mut coins_spent = Vec::new();
+    let mut coin_spends = Vec::new();
+    let mut coins_created = Vec::new();
+
+    for spend in spends {
+        let ParsedSpend {
+            position,
+            coin,
+            info,
+            create_coins,
+        } = spend;
+        let coin_id = coin.coin_id;
+        let spend_index = info.as_ref().map(|_| coin_spends.len());
+
+        // Each coin's conditions are claimed once, so any left over have no spend
+        let spend_conditions = conditions_by_coin.as_mut().and_then(|conditions| {
+            let spend_conditions = conditions.remove(&coin_id);
+            if spend_conditions.is_none() {
+                diagnostics.failures.push(ParseFailure {
+                    stage: ParseStage::MatchConditions,
+                    error_code: None,
+                    message: format!("No conditions for coin {}", hex::encode(coin_id)),
+                    spend_index: info.is_some().then_some(position),
+                });
+            }
+            spend_conditions
+        });
+
+        // Get created coins from conditions, with the memos of their CREATE_COIN. The
+        // validated conditions are a set, so coins are put back in condition order.
+        let mut created_coins: Vec<(usize, CoinInfo)> = if spend_bundle_conditions.is_some() {
+            extract_created_coins(spend_index, spend_conditions)
+                .into_iter()
+                .map(|coin| {
+                    let position = create_coins.iter().position(|cc| {
+                        cc.amount == coin.amount && cc.puzzle_hash == coin.puzzle_hash
+                    });
+                    (position.unwrap_or(usize::MAX), coin)
                 })
-                .sum()
+                .collect()
         } else {
-            0.0
+            create_coins
+                .iter()
+                .enumerate()
+                .map(|(position, cc)| {
+                    let coin = CoinInfo {
+                        created_by_spend: spend_index.map(|index| index as u32),
+                        ..CoinInfo::new(coin_id, cc.puzzle_hash, cc.amount)
+                    };
+                    (position, coin)
+                })
+                .collect()
         };
+        created_coins.sort_by_key(|(position, _)| *position);
+        let created_coins: Vec<CoinInfo> = created_coins
+            .into_iter()
+            .map(|(position, coin)| {
+                let Some(create_coin) = create_coins.get(position) else {
+                    return coin;
+                };
+                if info.is_some() {
+                    coin.with_memos(&create_coin.memos)
+                } else {
+                    // Filtered out, so only the hint is kept for the hint index
+                    CoinInfo {
+                        hint: create_coin
+                            .memos
+                            .first()
+                            .and_then(|memo| Bytes32::try_from(memo.as_slice()).ok()),
+                        ..coin
+                    }
+                }
+            })
+            .collect();
 
-        Ok(GeneratorAnalysis {
-            size_bytes,
-            is_empty,
-            contains_clvm_patterns,
-            contains_coin_patterns,
-            entropy,
-        })
+        coins_spent.push(coin);
+        coins_created.extend(created_coins.iter().cloned());
+        if let Some(mut info) = info {
+            info.created_coins = created_coins;
+            coin_spends.push(info);
+        }
     }
-    */
 
-    /// Calculate Shannon entropy of data
-    #[allow(dead_code)]
-    fn calculate_entropy(&self, data: &[u8]) -> f64 {
-        if data.is_empty() {
-            return 0.0;
+    // Whatever is left was validated but never parsed
+    if let (Some(conditions), Some(unmatched)) = (spend_bundle_conditions, conditions_by_coin) {
+        for spend in &conditions.spends {
+            if unmatched.contains_key(&*spend.coin_id) {
+                diagnostics.failures.push(ParseFailure {
+                    stage: ParseStage::MatchConditions,
+                    error_code: None,
+                    message: format!(
+                        "Conditions for coin {} have no matching coin spend",
+                        hex::encode(*spend.coin_id)
+                    ),
+                    spend_index: None,
+                });
+            }
         }
+    }
 
-        let mut freq = [0u32; 256];
-        for &byte in data {
-            freq[byte as usize] += 1;
+    info!(
+        "CLVM execution extracted {} spends, {} coins created",
+        coin_spends.len(),
+        coins_created.len()
+    );
+
+    (coins_spent, coin_spends, coins_created)
+}
+
+/// Extract created coins from the validated conditions of a spend
+fn extract_created_coins(
+    spend_index: Option<usize>,
+    spend_conditions: Option<&SpendConditions>,
+) -> Vec<CoinInfo> {
+    let Some(spend_cond) = spend_conditions else {
+        return Vec::new();
+    };
+
+    spend_cond
+        .create_coin
+        .iter()
+        .map(|new_coin| CoinInfo {
+            created_by_spend: spend_index.map(|index| index as u32),
+            ..CoinInfo::new(*spend_cond.coin_id, new_coin.puzzle_hash, new_coin.amount)
+        })
+        .collect()
+}
+
+/// Fees of spends whose conditions weren't validated: what the spent coins hold beyond
+/// the coins created
+fn unvalidated_fees(coin_removals: &[CoinInfo], coin_creations: &[CoinInfo]) -> u64 {
+    let removed: u128 = coin_removals
+        .iter()
+        .map(|coin| u128::from(coin.amount))
+        .sum();
+    let created: u128 = coin_creations
+        .iter()
+        .map(|coin| u128::from(coin.amount))
+        .sum();
+    u64::try_from(removed.saturating_sub(created)).unwrap_or(u64::MAX)
+}
+
+/// Flag coins that are both created and spent within the same block
+fn mark_ephemeral_coins(
+    coin_removals: &mut [CoinInfo],
+    coin_spends: &mut [CoinSpendInfo],
+    coin_creations: &mut [CoinInfo],
+) {
+    let created: HashSet<Bytes32> = coin_creations.iter().map(|c| c.coin_id).collect();
+    let spent: HashSet<Bytes32> = coin_removals.iter().map(|c| c.coin_id).collect();
+
+    for coin in coin_removals.iter_mut() {
+        coin.ephemeral = created.contains(&coin.coin_id);
+    }
+    for spend in coin_spends.iter_mut() {
+        spend.coin.ephemeral = created.contains(&spend.coin.coin_id);
+        for coin in spend.created_coins.iter_mut() {
+            coin.ephemeral = spent.contains(&coin.coin_id);
         }
+    }
+    for coin in coin_creations.iter_mut() {
+        coin.ephemeral = spent.contains(&coin.coin_id);
+    }
+}
 
-        let len = data.len() as f64;
-        freq.iter()
-            .filter(|&&count| count > 0)
-            .map(|&count| {
-                let p = count as f64 / len;
-                -p * p.log2()
-            })
-            .sum()
+/// Index the coins created in a block by their hint
+fn build_hint_index(coin_creations: &[CoinInfo]) -> BTreeMap<Bytes32, Vec<Bytes32>> {
+    let mut hint_index: BTreeMap<Bytes32, Vec<Bytes32>> = BTreeMap::new();
+    for coin in coin_creations {
+        if let Some(hint) = coin.hint {
+            hint_index.entry(hint).or_default().push(coin.coin_id);
+        }
     }
+    hint_index
 }
 
 impl Default for BlockParser {
     fn default() -> Self {
-        Self::new()
+        Self::new(Network::default())
+    }
+}
+
+/// Describe a failed consensus call by its stage and `ErrorCode`
+fn validation_failure(stage: ParseStage, err: ValidationErr) -> ParseFailure {
+    ParseFailure {
+        stage,
+        error_code: Some(u32::from(err.1)),
+        message: format!("{:?}", err.1),
+        spend_index: None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        fixtures::{
+            transaction_block, FARMER_PUZZLE_HASH, MAINNET_834768_GENERATOR,
+            MAINNET_CAT_TRADE_BUNDLE, POOL_PUZZLE_HASH,
+        },
+        generator_lookup::InMemoryGeneratorCache,
+        types::PuzzleModUsage,
+    };
+    use chia_bls::{sign, SecretKey};
+    use chia_consensus::{
+        opcodes::{AGG_SIG_UNSAFE, CREATE_COIN, CREATE_COIN_COST},
+        run_block_generator::run_block_generator2,
+        spendbundle_conditions::run_spendbundle,
+    };
+    use chia_protocol::{Bytes, Bytes32, Coin, PoolTarget, Program, ProofOfSpace};
+    use clvm_traits::{clvm_list, clvm_quote, ToClvm};
+    use clvmr::serde::node_to_bytes_backrefs;
+
+    #[test]
+    fn test_mark_ephemeral_coins() {
+        let parent = CoinInfo::new(Bytes32::new([1; 32]), Bytes32::new([2; 32]), 100);
+        let parent_id = parent.coin_id;
+        let child = CoinInfo {
+            created_by_spend: Some(0),
+            ..CoinInfo::new(parent_id, Bytes32::new([3; 32]), 40)
+        };
+        let change = CoinInfo {
+            created_by_spend: Some(0),
+            ..CoinInfo::new(parent_id, Bytes32::new([4; 32]), 60)
+        };
+
+        let mut coin_removals = vec![parent.clone(), child.clone()];
+        let mut coin_spends = vec![CoinSpendInfo::new(
+            parent,
+            Program::default(),
+            Program::default(),
+            true,
+            String::new(),
+            0,
+            vec![child.clone(), change.clone()],
+        )];
+        let mut coin_creations = vec![child, change];
+
+        mark_ephemeral_coins(&mut coin_removals, &mut coin_spends, &mut coin_creations);
+
+        assert!(!coin_removals[0].ephemeral);
+        assert!(coin_removals[1].ephemeral);
+        assert!(coin_spends[0].created_coins[0].ephemeral);
+        assert!(!coin_spends[0].created_coins[1].ephemeral);
+        assert!(coin_creations[0].ephemeral);
+        assert!(!coin_creations[1].ephemeral);
+    }
+
+    #[test]
+    fn test_block_costs_from_spend_bundle_conditions() {
+        let conditions = SpendBundleConditions {
+            reserve_fee: 10,
+            cost: 3_000,
+            execution_cost: 2_000,
+            condition_cost: 1_000,
+            removal_amount: 1_750,
+            addition_amount: 1_500,
+            ..Default::default()
+        };
+
+        let costs = BlockCosts::from(&conditions);
+
+        assert_eq!(costs.cost, 3_000);
+        assert_eq!(costs.execution_cost, 2_000);
+        assert_eq!(costs.condition_cost, 1_000);
+        assert_eq!(costs.fees, 250);
+        assert_eq!(costs.reserve_fee, 10);
+    }
+
+    /// A spend of the `1` puzzle, which outputs its solution as the condition list
+    fn create_coin_spend(
+        allocator: &mut Allocator,
+        parent: &[u8],
+        puzzle_hash: Bytes32,
+        amount: u64,
+    ) -> NodePtr {
+        let parent = Bytes::new(parent.to_vec());
+        let solution = clvm_list!(clvm_list!(51, puzzle_hash, amount));
+        clvm_list!(parent, 1, amount, solution, ())
+            .to_clvm(allocator)
+            .unwrap()
+    }
+
+    /// A generator that returns the given spends
+    fn generator_bytes(allocator: &mut Allocator, spends: &[NodePtr]) -> Vec<u8> {
+        let generator = clvm_quote!(clvm_list!(spends.to_vec()))
+            .to_clvm(allocator)
+            .unwrap();
+        node_to_bytes(allocator, generator).unwrap()
+    }
+
+    fn spend_bundle_conditions(
+        parser: &BlockParser,
+        allocator: &mut Allocator,
+        spends: &[NodePtr],
+    ) -> SpendBundleConditions {
+        let generator_bytes = generator_bytes(allocator, spends);
+        run_block_generator2(
+            &mut make_allocator(clvmr::LIMIT_HEAP),
+            &generator_bytes,
+            Vec::<&[u8]>::new(),
+            parser.constants.max_block_cost_clvm,
+            DONT_VALIDATE_SIGNATURE,
+            &Signature::default(),
+            None,
+            &parser.constants,
+        )
+        .unwrap()
+    }
+
+    fn extract(
+        parser: &BlockParser,
+        allocator: &mut Allocator,
+        spends: &[NodePtr],
+        conditions: &SpendBundleConditions,
+    ) -> (Vec<CoinSpendInfo>, ParseDiagnostics) {
+        let output = clvm_list!(spends.to_vec()).to_clvm(allocator).unwrap();
+        let mut consensus =
+            ConsensusConditions::new(allocator, parser.constants.max_block_cost_clvm, 0, 0);
+        let mut diagnostics = ParseDiagnostics::default();
+        let spends = parser.extract_coin_spends_from_output(
+            allocator,
+            output,
+            &HashSet::new(),
+            &mut consensus,
+            DONT_VALIDATE_SIGNATURE,
+            &mut diagnostics,
+        );
+        let (_, coin_spends, _) =
+            match_spend_conditions(spends, Some(conditions), &mut diagnostics);
+        (coin_spends, diagnostics)
+    }
+
+    #[test]
+    fn test_puzzles_run_with_remaining_block_cost() {
+        let parser = BlockParser::default();
+        let mut allocator = Allocator::new();
+        let puzzle = allocator.one();
+        let solution = clvm_list!(clvm_list!(CREATE_COIN, Bytes32::new([2; 32]), 1))
+            .to_clvm(&mut allocator)
+            .unwrap();
+        let spends: Vec<NodePtr> = (1..=2u8)
+            .map(|index| {
+                clvm_list!(Bytes32::new([index; 32]), puzzle, 1, solution, ())
+                    .to_clvm(&mut allocator)
+                    .unwrap()
+            })
+            .collect();
+        let output = clvm_list!(spends).to_clvm(&mut allocator).unwrap();
+
+        // The block's budget covers the first spend, leaving 1 for the second (a limit of
+        // 0 means no limit to clvmr)
+        let dialect = ChiaDialect::new(DONT_VALIDATE_SIGNATURE);
+        let Reduction(puzzle_cost, _) =
+            run_program(&mut allocator, &dialect, puzzle, solution, Cost::MAX).unwrap();
+        let mut consensus =
+            ConsensusConditions::new(&allocator, puzzle_cost + CREATE_COIN_COST + 1, 0, 0);
+
+        let mut diagnostics = ParseDiagnostics::default();
+        let spends = parser.extract_coin_spends_from_output(
+            &mut allocator,
+            output,
+            &HashSet::new(),
+            &mut consensus,
+            DONT_VALIDATE_SIGNATURE,
+            &mut diagnostics,
+        );
+
+        assert_eq!(spends.len(), 2);
+        assert_eq!(
+            diagnostics.failures,
+            vec![ParseFailure {
+                stage: ParseStage::RunPuzzle,
+                error_code: Some(u32::from(ErrorCode::CostExceeded)),
+                message: format!("{:?}", ErrorCode::CostExceeded),
+                spend_index: Some(1),
+            }]
+        );
+
+        // Nothing is left for the second spend, which clvmr would take as no limit
+        let mut consensus =
+            ConsensusConditions::new(&allocator, puzzle_cost + CREATE_COIN_COST, 0, 0);
+        let mut diagnostics = ParseDiagnostics::default();
+        parser.extract_coin_spends_from_output(
+            &mut allocator,
+            output,
+            &HashSet::new(),
+            &mut consensus,
+            DONT_VALIDATE_SIGNATURE,
+            &mut diagnostics,
+        );
+        assert_eq!(diagnostics.failures.len(), 1);
+        assert_eq!(
+            diagnostics.failures[0].error_code,
+            Some(u32::from(ErrorCode::CostExceeded))
+        );
+    }
+
+    #[test]
+    fn test_run_puzzle_failures_with_coin_filter() {
+        let mut allocator = Allocator::new();
+        // `(x)` raises whatever the solution is
+        let failing = node_from_bytes(&mut allocator, &[0xff, 0x08, 0x80]).unwrap();
+        let puzzle_hash = Bytes32::from(tree_hash(&allocator, failing));
+        let spends: Vec<NodePtr> = (1..=3u8)
+            .map(|index| {
+                clvm_list!(Bytes32::new([index; 32]), failing, 1, (), ())
+                    .to_clvm(&mut allocator)
+                    .unwrap()
+            })
+            .collect();
+        let generator_bytes = generator_bytes(&mut allocator, &spends);
+
+        let watched = CoinInfo::new(Bytes32::new([2; 32]), puzzle_hash, 1).coin_id;
+        let filter = CoinFilter::new().with_coin_ids([watched]);
+        let coins = BlockParser::default()
+            .with_coin_filter(Arc::new(filter))
+            .process_generator_for_coins(&generator_bytes, &[], 0, None)
+            .unwrap();
+
+        // Only the watched spend is reported, so only its failure has an index
+        assert_eq!(coins.coin_spends.len(), 1);
+        assert_eq!(coins.coin_spends[0].coin.coin_id, watched);
+        let spend_indices: Vec<Option<u32>> = coins
+            .diagnostics
+            .failures
+            .iter()
+            .filter(|failure| failure.stage == ParseStage::RunPuzzle)
+            .map(|failure| failure.spend_index)
+            .collect();
+        assert_eq!(spend_indices, [None, Some(1), None]);
+    }
+
+    #[test]
+    fn test_process_generator_validates_signature() {
+        let secret_key = SecretKey::from_seed(&[5; 32]);
+        let message = Bytes::new(b"block listener".to_vec());
+
+        let mut allocator = Allocator::new();
+        let puzzle = allocator.one();
+        let solution = clvm_list!(clvm_list!(
+            AGG_SIG_UNSAFE,
+            secret_key.public_key(),
+            message.clone()
+        ));
+        let spend = clvm_list!(Bytes32::new([1; 32]), puzzle, 1, solution, ())
+            .to_clvm(&mut allocator)
+            .unwrap();
+        let generator_bytes = generator_bytes(&mut allocator, &[spend]);
+
+        let parser =
+            BlockParser::default().with_signature_validation(Arc::new(BlsCache::default()));
+        let run = |signature: Option<&Signature>| {
+            parser
+                .process_generator_for_coins(&generator_bytes, &[], 0, signature)
+                .unwrap()
+        };
+
+        let coins = run(Some(&sign(&secret_key, &message)));
+        assert_eq!(coins.signature_validation, SignatureValidation::Valid);
+        assert_eq!(coins.coin_spends.len(), 1);
+        assert!(coins.diagnostics.is_clean());
+
+        let coins = run(Some(&Signature::default()));
+        assert_eq!(coins.signature_validation, SignatureValidation::Invalid);
+        assert_eq!(coins.coin_spends.len(), 1);
+        assert!(coins.costs.cost > 0);
+
+        let coins = run(None);
+        assert_eq!(
+            coins.signature_validation,
+            SignatureValidation::NotValidated
+        );
+    }
+
+    #[test]
+    fn test_single_pass_matches_run_block_generator2() {
+        let parser = BlockParser::default();
+        let mut allocator = Allocator::new();
+        let spends = [
+            create_coin_spend(&mut allocator, &[1; 32], Bytes32::new([10; 32]), 5),
+            create_coin_spend(&mut allocator, &[2; 32], Bytes32::new([11; 32]), 7),
+        ];
+        let expected = spend_bundle_conditions(&parser, &mut allocator, &spends);
+        let generator_bytes = generator_bytes(&mut allocator, &spends);
+
+        let coins = parser
+            .process_generator_for_coins(&generator_bytes, &[], 0, None)
+            .unwrap();
+
+        assert!(coins.diagnostics.is_clean());
+        assert_eq!(coins.costs.cost, expected.cost);
+        assert_eq!(coins.costs.execution_cost, expected.execution_cost);
+        assert_eq!(coins.costs.condition_cost, expected.condition_cost);
+        assert_eq!(coins.coin_removals.len(), expected.spends.len());
+        for (spend, expected) in coins.coin_spends.iter().zip(&expected.spends) {
+            assert_eq!(spend.coin.coin_id, *expected.coin_id);
+            assert_eq!(spend.created_coins.len(), expected.create_coin.len());
+        }
+    }
+
+    #[test]
+    fn test_coin_filter_keeps_matching_spends() {
+        let mut allocator = Allocator::new();
+        let watched = Bytes32::new([11; 32]);
+        let spends = [
+            create_coin_spend(&mut allocator, &[1; 32], Bytes32::new([10; 32]), 5),
+            create_coin_spend(&mut allocator, &[2; 32], watched, 7),
+            create_coin_spend(&mut allocator, &[3; 32], Bytes32::new([12; 32]), 9),
+        ];
+        let generator_bytes = generator_bytes(&mut allocator, &spends);
+
+        let unfiltered = BlockParser::default()
+            .process_generator_for_coins(&generator_bytes, &[], 0, None)
+            .unwrap();
+        let filter = CoinFilter::new().with_puzzle_hashes([watched]);
+        let filtered = BlockParser::default()
+            .with_coin_filter(Arc::new(filter))
+            .process_generator_for_coins(&generator_bytes, &[], 0, None)
+            .unwrap();
+
+        // Every coin is still listed, so the block's commitments can be checked
+        assert!(filtered.diagnostics.is_clean());
+        assert_eq!(filtered.costs.cost, unfiltered.costs.cost);
+        assert_eq!(filtered.coin_removals.len(), 3);
+        assert_eq!(filtered.coin_creations.len(), 3);
+
+        // Only the spend creating a watched coin is parsed
+        assert_eq!(filtered.coin_spends.len(), 1);
+        let spend = &filtered.coin_spends[0];
+        assert_eq!(spend.coin.coin_id, unfiltered.coin_spends[1].coin.coin_id);
+        assert_eq!(spend.created_coins[0].puzzle_hash, watched);
+        assert!(!spend.solution.is_empty());
+
+        let created_by: Vec<Option<u32>> = filtered
+            .coin_creations
+            .iter()
+            .map(|coin| coin.created_by_spend)
+            .collect();
+        assert_eq!(created_by, [None, Some(0), None]);
+
+        // A filter matching the spent coin keeps the spend too
+        let filter = CoinFilter::new().with_coin_ids([unfiltered.coin_spends[2].coin.coin_id]);
+        let filtered = BlockParser::default()
+            .with_coin_filter(Arc::new(filter))
+            .process_generator_for_coins(&generator_bytes, &[], 0, None)
+            .unwrap();
+        assert_eq!(filtered.coin_spends.len(), 1);
+        assert_eq!(
+            filtered.coin_spends[0].coin.coin_id,
+            unfiltered.coin_spends[2].coin.coin_id
+        );
+    }
+
+    #[test]
+    fn test_created_coins_matched_by_coin_id_after_skipped_spend() {
+        let parser = BlockParser::default();
+        let mut allocator = Allocator::new();
+        let first = create_coin_spend(&mut allocator, &[1; 32], Bytes32::new([10; 32]), 1);
+        let second = create_coin_spend(&mut allocator, &[2; 32], Bytes32::new([11; 32]), 2);
+        let malformed = create_coin_spend(&mut allocator, &[3; 31], Bytes32::new([12; 32]), 3);
+        let conditions = spend_bundle_conditions(&parser, &mut allocator, &[first, second]);
+
+        // The malformed spend shifts every later spend by one position
+        let (coin_spends, diagnostics) = extract(
+            &parser,
+            &mut allocator,
+            &[malformed, first, second],
+            &conditions,
+        );
+
+        assert_eq!(coin_spends.len(), 2);
+        for (index, (spend, puzzle_hash)) in
+            coin_spends.iter().zip([[10; 32], [11; 32]]).enumerate()
+        {
+            assert_eq!(spend.created_coins.len(), 1);
+            let created = &spend.created_coins[0];
+            assert_eq!(created.puzzle_hash, Bytes32::new(puzzle_hash));
+            assert_eq!(created.parent_coin_info, spend.coin.coin_id);
+            assert_eq!(created.created_by_spend, Some(index as u32));
+        }
+        assert_eq!(diagnostics.failures.len(), 1);
+        assert_eq!(diagnostics.failures[0].stage, ParseStage::ParseCoinSpend);
+        assert_eq!(diagnostics.failures[0].spend_index, Some(0));
+    }
+
+    #[test]
+    fn test_conditions_mismatch_diagnostics() {
+        let parser = BlockParser::default();
+        let mut allocator = Allocator::new();
+        let first = create_coin_spend(&mut allocator, &[1; 32], Bytes32::new([10; 32]), 1);
+        let second = create_coin_spend(&mut allocator, &[2; 32], Bytes32::new([11; 32]), 2);
+
+        // A spend without conditions gets no created coins
+        let conditions = spend_bundle_conditions(&parser, &mut allocator, &[first]);
+        let (coin_spends, diagnostics) =
+            extract(&parser, &mut allocator, &[first, second], &conditions);
+        assert_eq!(coin_spends.len(), 2);
+        assert_eq!(coin_spends[0].created_coins.len(), 1);
+        assert!(coin_spends[1].created_coins.is_empty());
+        assert_eq!(diagnostics.failures.len(), 1);
+        assert_eq!(diagnostics.failures[0].stage, ParseStage::MatchConditions);
+        assert_eq!(diagnostics.failures[0].spend_index, Some(1));
+
+        // Conditions without a spend are reported once the output is exhausted
+        let conditions = spend_bundle_conditions(&parser, &mut allocator, &[first, second]);
+        let (coin_spends, diagnostics) = extract(&parser, &mut allocator, &[first], &conditions);
+        assert_eq!(coin_spends.len(), 1);
+        assert_eq!(diagnostics.failures.len(), 1);
+        assert_eq!(diagnostics.failures[0].stage, ParseStage::MatchConditions);
+        assert_eq!(diagnostics.failures[0].spend_index, None);
+    }
+
+    #[test]
+    fn test_created_coins_keep_condition_order() {
+        // The validated conditions hold created coins in a hash set, so enough of them
+        // come back in another order
+        let parser = BlockParser::default();
+        let mut allocator = Allocator::new();
+        let created: Vec<(Bytes32, u64)> = (0u8..16)
+            .map(|index| (Bytes32::new([0x80 - index; 32]), 100 + u64::from(index)))
+            .collect();
+        let conditions: Vec<_> = created
+            .iter()
+            .map(|&(puzzle_hash, amount)| {
+                clvm_list!(51, puzzle_hash, amount, clvm_list!(puzzle_hash))
+            })
+            .collect();
+        let spend = clvm_list!(Bytes32::new([1; 32]), 1, 5000, conditions, ())
+            .to_clvm(&mut allocator)
+            .unwrap();
+
+        let expected: Vec<(Bytes32, u64, Option<Bytes32>)> = created
+            .iter()
+            .map(|&(puzzle_hash, amount)| (puzzle_hash, amount, Some(puzzle_hash)))
+            .collect();
+        let order = |coins: &[CoinInfo]| -> Vec<(Bytes32, u64, Option<Bytes32>)> {
+            coins
+                .iter()
+                .map(|coin| (coin.puzzle_hash, coin.amount, coin.hint))
+                .collect()
+        };
+
+        let validated = spend_bundle_conditions(&parser, &mut allocator, &[spend]);
+        assert_ne!(
+            validated.spends[0]
+                .create_coin
+                .iter()
+                .map(|coin| coin.puzzle_hash)
+                .collect::<Vec<_>>(),
+            created
+                .iter()
+                .map(|&(puzzle_hash, _)| puzzle_hash)
+                .collect::<Vec<_>>()
+        );
+        let (coin_spends, diagnostics) = extract(&parser, &mut allocator, &[spend], &validated);
+        assert!(diagnostics.is_clean());
+        assert_eq!(order(&coin_spends[0].created_coins), expected);
+
+        // Without validated conditions, the CREATE_COIN conditions are used as they are
+        let output = clvm_list!(vec![spend]).to_clvm(&mut allocator).unwrap();
+        let mut consensus =
+            ConsensusConditions::new(&allocator, parser.constants.max_block_cost_clvm, 0, 0);
+        let mut diagnostics = ParseDiagnostics::default();
+        let spends = parser.extract_coin_spends_from_output(
+            &mut allocator,
+            output,
+            &HashSet::new(),
+            &mut consensus,
+            DONT_VALIDATE_SIGNATURE,
+            &mut diagnostics,
+        );
+        let (_, coin_spends, coin_creations) =
+            match_spend_conditions(spends, None, &mut diagnostics);
+        assert_eq!(order(&coin_spends[0].created_coins), expected);
+        assert_eq!(order(&coin_creations), expected);
+    }
+
+    #[test]
+    fn test_generator_failure_diagnostics() {
+        // A generator that raises instead of returning a spend list
+        let mut allocator = Allocator::new();
+        let generator = clvm_list!(8).to_clvm(&mut allocator).unwrap();
+        let generator_bytes = node_to_bytes(&allocator, generator).unwrap();
+
+        let coins = BlockParser::default()
+            .process_generator_for_coins(&generator_bytes, &[], 0, None)
+            .unwrap();
+        assert!(coins.coin_spends.is_empty());
+        assert_eq!(coins.diagnostics.failures.len(), 1);
+        let failure = &coins.diagnostics.failures[0];
+        assert_eq!(failure.stage, ParseStage::RunGenerator);
+        assert_eq!(
+            failure.error_code,
+            Some(u32::from(ErrorCode::GeneratorRuntimeError))
+        );
+
+        let strict = BlockParser::default().with_strict_mode(true);
+        assert!(matches!(
+            strict.process_generator_for_coins(&generator_bytes, &[], 0, None),
+            Err(GeneratorParserError::GeneratorFailed(ParseFailure {
+                stage: ParseStage::RunGenerator,
+                ..
+            }))
+        ));
+
+        let coins = BlockParser::default()
+            .process_generator_for_coins(&[0xff], &[], 0, None)
+            .unwrap();
+        assert_eq!(
+            coins.diagnostics.failures[0].stage,
+            ParseStage::DeserializeGenerator
+        );
+    }
+
+    #[test]
+    fn test_parse_blocks_batch_keeps_order_and_per_block_errors() {
+        let mut allocator = Allocator::new();
+        let spend = create_coin_spend(&mut allocator, &[1; 32], Bytes32::new([10; 32]), 5);
+        let generator = generator_bytes(&mut allocator, &[spend]);
+
+        let blocks: Vec<FullBlock> = (1..=8)
+            .map(|height| transaction_block(height, Some(generator.clone())))
+            .collect();
+        let parser = BlockParser::default();
+
+        let parsed = parser.parse_blocks_batch(&blocks);
+        assert_eq!(parsed.len(), blocks.len());
+        for (block, parsed) in blocks.iter().zip(&parsed) {
+            let parsed = parsed.as_ref().unwrap();
+            assert_eq!(parsed.height, block.reward_chain_block.height);
+            assert_eq!(parsed.weight, block.reward_chain_block.weight);
+            assert_eq!(parsed.coin_spends.len(), 1);
+        }
+
+        // A block that fails to deserialize only fails its own entry
+        let mut block_bytes: Vec<Vec<u8>> = blocks.iter().map(|b| b.to_bytes().unwrap()).collect();
+        block_bytes[3] = vec![0xff];
+        let parsed = parser.parse_blocks_batch_from_bytes(&block_bytes);
+        assert_eq!(parsed.len(), blocks.len());
+        assert!(matches!(
+            parsed[3],
+            Err(GeneratorParserError::InvalidBlockFormat(_))
+        ));
+        for (index, parsed) in parsed.iter().enumerate().filter(|(i, _)| *i != 3) {
+            assert_eq!(parsed.as_ref().unwrap().height, index as u32 + 1);
+        }
+    }
+
+    #[test]
+    fn test_parse_blocks_batch_resolves_refs_within_batch() {
+        let mut allocator = Allocator::new();
+        let spend = create_coin_spend(&mut allocator, &[1; 32], Bytes32::new([10; 32]), 5);
+        let generator = generator_bytes(&mut allocator, &[spend]);
+
+        let referenced = transaction_block(1, Some(generator.clone()));
+        let mut compressed = transaction_block(2, Some(generator));
+        compressed.transactions_generator_ref_list = vec![1];
+        let parser = BlockParser::default();
+
+        // Without a generator lookup, the ref can't be resolved for a single block
+        let parsed = parser.parse_full_block(&compressed).unwrap();
+        assert_eq!(
+            parsed.diagnostics.failures[0].stage,
+            ParseStage::ResolveGeneratorRefs
+        );
+
+        let parsed = parser.parse_blocks_batch(&[referenced, compressed]);
+        assert!(parsed[1].as_ref().unwrap().diagnostics.is_clean());
+    }
+
+    #[test]
+    fn test_farming_info() {
+        let parser = BlockParser::default();
+        let plot_public_key = SecretKey::from_seed(&[7; 32]).public_key();
+        let mut block = transaction_block(7, None);
+        block.reward_chain_block.proof_of_space = ProofOfSpace::new(
+            Bytes32::default(),
+            None,
+            Some(Bytes32::new([1; 32])),
+            plot_public_key,
+            0x80 | 28,
+            Bytes::default(),
+        );
+        block.reward_chain_block.signage_point_index = 62;
+        block.foliage.foliage_block_data.farmer_reward_puzzle_hash = Bytes32::new([2; 32]);
+        block.foliage.foliage_block_data.pool_target = PoolTarget::new(Bytes32::new([3; 32]), 0);
+
+        let farming = parser.parse_full_block(&block).unwrap().farming;
+        assert_eq!(farming.plot_public_key, plot_public_key);
+        assert_eq!(farming.pool_public_key, None);
+        assert_eq!(
+            farming.pool_contract_puzzle_hash,
+            Some(Bytes32::new([1; 32]))
+        );
+        assert_eq!(farming.farmer_reward_puzzle_hash, Bytes32::new([2; 32]));
+        assert_eq!(farming.pool_target.puzzle_hash, Bytes32::new([3; 32]));
+        assert_eq!(farming.signage_point_index, 62);
+        assert_eq!((farming.plot_size, farming.plot_version), (28, 2));
+        // The last 3 of the 64 signage points are infused in the next sub-slot
+        assert!(farming.overflow);
+
+        block.reward_chain_block.signage_point_index = 60;
+        let farming = parser.parse_full_block(&block).unwrap().farming;
+        assert!(!farming.overflow);
+        assert_eq!((farming.plot_size, farming.plot_version), (28, 2));
+
+        let farming = parser
+            .parse_full_block(&transaction_block(7, None))
+            .unwrap()
+            .farming;
+        assert_eq!((farming.plot_size, farming.plot_version), (32, 1));
+    }
+
+    #[test]
+    fn test_parse_header_block_matches_full_block() {
+        let mut allocator = Allocator::new();
+        let spend = create_coin_spend(&mut allocator, &[1; 32], Bytes32::new([10; 32]), 5);
+        let mut full = transaction_block(7, Some(generator_bytes(&mut allocator, &[spend])));
+        let reward = Coin::new(
+            Bytes32::new([2; 32]),
+            Bytes32::new([3; 32]),
+            1_750_000_000_000,
+        );
+        if let Some(info) = full.transactions_info.as_mut() {
+            info.reward_claims_incorporated.push(reward);
+        }
+
+        let filter = Bytes::new(vec![1, 2, 3]);
+        let header = HeaderBlock::new(
+            full.finished_sub_slots.clone(),
+            full.reward_chain_block.clone(),
+            full.challenge_chain_sp_proof.clone(),
+            full.challenge_chain_ip_proof.clone(),
+            full.reward_chain_sp_proof.clone(),
+            full.reward_chain_ip_proof.clone(),
+            full.infused_challenge_chain_ip_proof.clone(),
+            full.foliage.clone(),
+            full.foliage_transaction_block.clone(),
+            filter.clone(),
+            full.transactions_info.clone(),
+        );
+        let parser = BlockParser::default();
+
+        let parsed_full = parser.parse_full_block(&full).unwrap();
+        let parsed = parser
+            .parse_header_block_from_bytes(&header.to_bytes().unwrap())
+            .unwrap();
+        assert_eq!(parsed.height, 7);
+        assert_eq!(parsed.weight, parsed_full.weight);
+        assert_eq!(parsed.header_hash, parsed_full.header_hash);
+        assert_eq!(parsed.header_hash, header.header_hash());
+        assert_eq!(parsed.timestamp, Some(1_700_000_000));
+        assert_eq!(parsed.farming, parsed_full.farming);
+        assert_eq!(parsed.reward_claims.len(), 1);
+        assert_eq!(parsed.reward_claims[0].coin_id, reward.coin_id());
+        assert_eq!(parsed.transactions_filter, filter);
+
+        assert!(matches!(
+            parser.parse_header_block_from_bytes(&[0xff]),
+            Err(GeneratorParserError::InvalidBlockFormat(_))
+        ));
+    }
+
+    #[test]
+    fn test_analyze_generator() {
+        let mut allocator = Allocator::new();
+        let first = create_coin_spend(&mut allocator, &[1; 32], Bytes32::new([10; 32]), 5);
+        let second = create_coin_spend(&mut allocator, &[2; 32], Bytes32::new([10; 32]), 6);
+        // Two spends of the same quoted puzzle, which back references can share
+        let quoted = clvm_quote!(clvm_list!(clvm_list!(51, Bytes32::new([11; 32]), 7)))
+            .to_clvm(&mut allocator)
+            .unwrap();
+        let quoted_spends: Vec<NodePtr> = [[3; 32], [4; 32]]
+            .iter()
+            .map(|parent| {
+                clvm_list!(Bytes32::new(*parent), quoted, 7, (), ())
+                    .to_clvm(&mut allocator)
+                    .unwrap()
+            })
+            .collect();
+        let generator = clvm_quote!(clvm_list!(vec![
+            first,
+            second,
+            quoted_spends[0],
+            quoted_spends[1]
+        ]))
+        .to_clvm(&mut allocator)
+        .unwrap();
+        let plain = node_to_bytes(&allocator, generator).unwrap();
+        let compressed = node_to_bytes_backrefs(&allocator, generator).unwrap();
+        assert!(compressed.len() < plain.len());
+
+        let parser = BlockParser::default();
+        let analysis = parser.analyze_generator(&plain).unwrap();
+        assert!(analysis.diagnostics.is_clean());
+        assert_eq!(analysis.spend_count, 4);
+        assert_eq!(analysis.size_bytes, plain.len() as u32);
+        assert_eq!(analysis.expanded_size_bytes, plain.len() as u64);
+        assert!(!analysis.uses_back_references);
+        assert!(analysis.cost > analysis.execution_cost + analysis.condition_cost);
+        assert!(analysis.entropy > 0.0);
+
+        // The quoted puzzles are the largest reveals, in spend order
+        let quoted_size = node_to_bytes(&allocator, quoted).unwrap().len() as u32;
+        let reveals: Vec<(Bytes32, u32)> = analysis
+            .largest_puzzle_reveals
+            .iter()
+            .map(|reveal| (reveal.coin_id, reveal.size_bytes))
+            .collect();
+        let coin_id = |parent: u8, puzzle_hash: Bytes32, amount: u64| {
+            Coin::new(Bytes32::new([parent; 32]), puzzle_hash, amount).coin_id()
+        };
+        let quoted_hash = Bytes32::from(tree_hash_cached(
+            &allocator,
+            quoted,
+            &HashSet::new(),
+            &mut HashMap::new(),
+        ));
+        let one_hash = Bytes32::from(clvm_utils::tree_hash_atom(&[1]));
+        assert_eq!(
+            reveals,
+            vec![
+                (coin_id(3, quoted_hash, 7), quoted_size),
+                (coin_id(4, quoted_hash, 7), quoted_size),
+                (coin_id(1, one_hash, 5), 1),
+                (coin_id(2, one_hash, 6), 1),
+            ]
+        );
+
+        assert_eq!(
+            analysis.repeated_puzzle_mods,
+            vec![
+                PuzzleModUsage {
+                    mod_hash: quoted_hash,
+                    kind: "unknown".to_string(),
+                    spend_count: 2,
+                    reveal_bytes: u64::from(quoted_size) * 2,
+                },
+                PuzzleModUsage {
+                    mod_hash: one_hash,
+                    kind: "unknown".to_string(),
+                    spend_count: 2,
+                    reveal_bytes: 2,
+                },
+            ]
+        );
+
+        // Back references change the serialization but not the spends
+        let parsed = parser.parse_generator_from_bytes(&compressed).unwrap();
+        assert!(parsed.analysis.uses_back_references);
+        assert_eq!(parsed.analysis.size_bytes, compressed.len() as u32);
+        assert_eq!(parsed.analysis.expanded_size_bytes, plain.len() as u64);
+        assert!(parsed.analysis.compression_ratio() > 1.0);
+        assert_eq!(parsed.coin_spends.len(), 4);
+        assert_eq!(parsed.coin_creations.len(), 4);
+        assert_eq!(
+            parsed.analysis.repeated_puzzle_mods,
+            analysis.repeated_puzzle_mods
+        );
+
+        // A block's generator refs are reported
+        let mut block = transaction_block(9, Some(plain));
+        block.transactions_generator_ref_list = vec![4, 2];
+        let cache = InMemoryGeneratorCache::new();
+        cache.insert(2, vec![0x80]);
+        cache.insert(4, vec![0x80]);
+        let parser = parser.with_generator_lookup(Arc::new(cache));
+        let analysis = parser.analyze_block_generator(&block).unwrap().unwrap();
+        assert_eq!(analysis.generator_ref_list, vec![4, 2]);
+        assert_eq!(analysis.spend_count, 4);
+        assert!(parser
+            .analyze_block_generator(&transaction_block(10, None))
+            .unwrap()
+            .is_none());
+
+        assert!(matches!(
+            parser.parse_generator_from_hex("not hex"),
+            Err(GeneratorParserError::HexDecodingError(_))
+        ));
+    }
+
+    #[test]
+    fn test_parse_spend_bundle() {
+        let mut allocator = Allocator::new();
+        let one = allocator.one();
+        let puzzle = Program::new(node_to_bytes(&allocator, one).unwrap().into());
+        let one_hash = Bytes32::from(clvm_utils::tree_hash_atom(&[1]));
+        let spent = Coin::new(Bytes32::new([1; 32]), one_hash, 5);
+        let created = Coin::new(spent.coin_id(), Bytes32::new([10; 32]), 3);
+        // The created coin is spent in the same bundle, creating another coin
+        let ephemeral = Coin::new(spent.coin_id(), one_hash, 1);
+        let solution = |conditions: NodePtr, allocator: &Allocator| {
+            Program::new(node_to_bytes(allocator, conditions).unwrap().into())
+        };
+        let spent_conditions = clvm_list!(
+            clvm_list!(51, created.puzzle_hash, 3),
+            clvm_list!(51, one_hash, 1)
+        )
+        .to_clvm(&mut allocator)
+        .unwrap();
+        let ephemeral_conditions = clvm_list!(clvm_list!(51, Bytes32::new([11; 32]), 1))
+            .to_clvm(&mut allocator)
+            .unwrap();
+        let spend_bundle = SpendBundle::new(
+            vec![
+                CoinSpend::new(
+                    spent,
+                    puzzle.clone(),
+                    solution(spent_conditions, &allocator),
+                ),
+                CoinSpend::new(
+                    ephemeral,
+                    puzzle,
+                    solution(ephemeral_conditions, &allocator),
+                ),
+            ],
+            Signature::default(),
+        );
+
+        let parser =
+            BlockParser::default().with_signature_validation(Arc::new(BlsCache::default()));
+        let parsed = parser
+            .parse_spend_bundle_from_hex(&hex::encode(spend_bundle.to_bytes().unwrap()))
+            .unwrap();
+        assert!(parsed.diagnostics.is_clean());
+        assert_eq!(parsed.signature_validation, SignatureValidation::Valid);
+        assert_eq!(parsed.coin_spends.len(), 2);
+        assert_eq!(parsed.coin_spends[0].coin.coin_id, spent.coin_id());
+        assert_eq!(parsed.coin_spends[0].created_coins.len(), 2);
+        assert!(parsed.coin_spends[1].coin.ephemeral);
+        assert_eq!(parsed.coin_removals.len(), 2);
+        assert_eq!(parsed.coin_creations.len(), 3);
+        assert_eq!(parsed.fees, 1);
+
+        // Costs match the mempool's own accounting
+        let (conditions, _) = run_spendbundle(
+            &mut make_allocator(clvmr::LIMIT_HEAP),
+            &spend_bundle,
+            parser.constants.max_block_cost_clvm,
+            u32::MAX,
+            0,
+            &parser.constants,
+        )
+        .unwrap();
+        assert_eq!(parsed.cost, conditions.cost);
+        assert_eq!(parsed.execution_cost, conditions.execution_cost);
+        assert_eq!(parsed.condition_cost, conditions.condition_cost);
+
+        // A puzzle reveal that doesn't match the coin fails validation
+        let mut wrong_puzzle = spend_bundle.clone();
+        wrong_puzzle.coin_spends[0].coin.puzzle_hash = Bytes32::new([9; 32]);
+        let parsed = parser.parse_spend_bundle(&wrong_puzzle).unwrap();
+        assert_eq!(
+            parsed.diagnostics.failures[0].stage,
+            ParseStage::RunBlockGenerator
+        );
+        assert_eq!(
+            parsed.diagnostics.failures[0].error_code,
+            Some(u32::from(ErrorCode::WrongPuzzleHash))
+        );
+        // Coins and fees then come from the CREATE_COIN conditions
+        assert_eq!(parsed.coin_creations.len(), 3);
+        assert_eq!(parsed.coin_spends[0].created_coins.len(), 2);
+        assert_eq!(parsed.fees, 1);
+        assert_eq!(parsed.cost, 0);
+
+        // A coin spend that can't be parsed doesn't stop the others
+        let mut bad_solution = spend_bundle.clone();
+        bad_solution.coin_spends[0].solution = Program::new(vec![0xff].into());
+        let parsed = parser.parse_spend_bundle(&bad_solution).unwrap();
+        assert_eq!(
+            parsed.diagnostics.failures[0].stage,
+            ParseStage::ParseCoinSpend
+        );
+        assert_eq!(parsed.diagnostics.failures[0].spend_index, Some(0));
+        assert_eq!(parsed.coin_spends.len(), 1);
+        assert_eq!(parsed.coin_spends[0].coin.coin_id, ephemeral.coin_id());
+        assert_eq!(parsed.coin_creations.len(), 1);
+
+        // Failures of later stages still refer to the spend's index in the bundle
+        let mut failing_puzzle = bad_solution.clone();
+        failing_puzzle.coin_spends[1].puzzle_reveal = Program::new(vec![0xff, 0x08, 0x80].into());
+        let parsed = parser.parse_spend_bundle(&failing_puzzle).unwrap();
+        let failure = parsed
+            .diagnostics
+            .failures
+            .iter()
+            .find(|failure| failure.stage == ParseStage::RunPuzzle)
+            .unwrap();
+        assert_eq!(failure.spend_index, Some(1));
+
+        assert!(matches!(
+            parser
+                .clone()
+                .with_strict_mode(true)
+                .parse_spend_bundle(&bad_solution),
+            Err(GeneratorParserError::GeneratorFailed(ParseFailure {
+                stage: ParseStage::ParseCoinSpend,
+                ..
+            }))
+        ));
+
+        assert!(matches!(
+            parser.parse_spend_bundle_from_bytes(&[0xff]),
+            Err(GeneratorParserError::SerializationError(_))
+        ));
+    }
+
+    #[test]
+    fn test_parse_offer_spend_bundle() {
+        // The maker's side of a mainnet trade as its offer carries it: the CAT spend, and
+        // the XCH it requests as a settlement spend of a coin without a parent
+        let trade = SpendBundle::from_bytes(MAINNET_CAT_TRADE_BUNDLE).unwrap();
+        let settlement = &trade.coin_spends[0];
+        let offered = &trade.coin_spends[1];
+        let requested_payment = CoinSpend::new(
+            Coin::new(Bytes32::default(), settlement.coin.puzzle_hash, 0),
+            settlement.puzzle_reveal.clone(),
+            settlement.solution.clone(),
+        );
+        let offer = SpendBundle::new(
+            vec![requested_payment.clone(), offered.clone()],
+            trade.aggregated_signature.clone(),
+        );
+
+        let parser = BlockParser::default();
+        let traded = parser.parse_spend_bundle(&trade).unwrap();
+        assert!(traded.diagnostics.is_clean());
+
+        // The requested payment creates coins out of nothing, and the offered CAT asserts
+        // a payment that only the taker makes
+        let parsed = parser.parse_spend_bundle(&offer).unwrap();
+        let failure = &parsed.diagnostics.failures[0];
+        assert_eq!(failure.stage, ParseStage::RunBlockGenerator);
+        assert_eq!(failure.error_code, Some(u32::from(ErrorCode::MintingCoin)));
+        let maker_only = SpendBundle::new(vec![offered.clone()], Signature::default());
+        let parsed_maker = parser.parse_spend_bundle(&maker_only).unwrap();
+        assert_eq!(
+            parsed_maker.diagnostics.failures[0].error_code,
+            Some(u32::from(ErrorCode::AssertPuzzleAnnouncementFailed))
+        );
+        assert_eq!(parsed_maker.coin_creations.len(), 1);
+
+        // Both spends still report the coins they create
+        assert_eq!(parsed.coin_spends.len(), 2);
+        let payment = &parsed.coin_spends[0].created_coins;
+        assert_eq!(payment.len(), 1);
+        assert_eq!(
+            payment[0].parent_coin_info,
+            requested_payment.coin.coin_id()
+        );
+        assert_eq!(
+            payment[0].puzzle_hash,
+            traded.coin_spends[0].created_coins[0].puzzle_hash
+        );
+        assert_eq!(payment[0].amount, 67_082_400_000);
+        let settled = &parsed.coin_spends[1].created_coins;
+        assert_eq!(settled.len(), 1);
+        assert_eq!(
+            settled[0].coin_id,
+            traded.coin_spends[1].created_coins[0].coin_id
+        );
+        assert_eq!(
+            settled[0].memos,
+            traded.coin_spends[1].created_coins[0].memos
+        );
+        assert_eq!(parsed.coin_creations.len(), 2);
+        assert_eq!(
+            parsed.hint_index.get(&settlement.coin.puzzle_hash),
+            Some(&vec![settled[0].coin_id])
+        );
+        // The offer requests more than it spends
+        assert_eq!(parsed.fees, 0);
+    }
+
+    /// Fill in the commitments of a block built by `full_block` from its parsed coins
+    fn commit_block(parser: &BlockParser, block: &mut FullBlock) {
+        let parsed = parser.parse_full_block(block).unwrap();
+        let filter = transactions_filter(&parsed.coin_creations, &parsed.coin_removals);
+        let generator = block.transactions_generator.as_ref().map(|g| g.as_ref());
+        let transactions_info = block.transactions_info.as_mut().unwrap();
+        transactions_info.generator_root = generator_root(generator);
+        transactions_info.generator_refs_root =
+            generator_refs_root(&block.transactions_generator_ref_list);
+        let transactions_info_hash = Bytes32::new(block.transactions_info.as_ref().unwrap().hash());
+        let ftb = block.foliage_transaction_block.as_mut().unwrap();
+        ftb.additions_root = additions_root(&parsed.coin_additions);
+        ftb.removals_root = removals_root(&parsed.coin_removals);
+        ftb.filter_hash = filter_hash(&filter);
+        ftb.transactions_info_hash = transactions_info_hash;
+
+        let reward_block_hash = Bytes32::new(block.reward_chain_block.get_unfinished().hash());
+        block.foliage.reward_block_hash = reward_block_hash;
+        block
+            .foliage
+            .foliage_block_data
+            .unfinished_reward_block_hash = reward_block_hash;
+        block.foliage.foliage_transaction_block_hash = Some(Bytes32::new(ftb.hash()));
+    }
+
+    #[test]
+    fn test_verify_block() {
+        let mut allocator = Allocator::new();
+        let spend = create_coin_spend(&mut allocator, &[1; 32], Bytes32::new([10; 32]), 5);
+        let mut block = transaction_block(3, Some(generator_bytes(&mut allocator, &[spend])));
+        let reward = Coin::new(
+            Bytes32::new([2; 32]),
+            Bytes32::new([3; 32]),
+            250_000_000_000,
+        );
+        block
+            .transactions_info
+            .as_mut()
+            .unwrap()
+            .reward_claims_incorporated
+            .push(reward);
+        let parser = BlockParser::default();
+        commit_block(&parser, &mut block);
+
+        let verify = |block: &FullBlock| {
+            let parsed = parser.parse_full_block(block).unwrap();
+            parser.verify_block(block, &parsed).unwrap()
+        };
+        let verification = verify(&block);
+        assert!(verification.is_valid());
+        assert!(verification
+            .checks()
+            .iter()
+            .all(|(_, check)| *check == CommitmentCheck::Valid));
+
+        // A peer swapping the generator changes its hash and the coins the roots commit to
+        let mut altered = block.clone();
+        let spend = create_coin_spend(&mut allocator, &[1; 32], Bytes32::new([11; 32]), 5);
+        altered.transactions_generator =
+            Some(Program::from(generator_bytes(&mut allocator, &[spend])));
+        let verification = verify(&altered);
+        assert_eq!(
+            verification.failed_checks(),
+            vec!["generator_root", "additions_root", "filter_hash"]
+        );
+        assert_eq!(
+            verification.additions_root,
+            CommitmentCheck::Invalid {
+                committed: Some(
+                    block
+                        .foliage_transaction_block
+                        .as_ref()
+                        .unwrap()
+                        .additions_root
+                ),
+                computed: Some(additions_root(
+                    &parser.parse_full_block(&altered).unwrap().coin_additions
+                )),
+            }
+        );
+
+        let mut altered = block.clone();
+        altered.transactions_info.as_mut().unwrap().fees = 1;
+        assert_eq!(
+            verify(&altered).failed_checks(),
+            vec!["transactions_info_hash"]
+        );
+
+        let mut altered = block.clone();
+        altered.transactions_generator_ref_list = vec![1];
+        assert_eq!(
+            verify(&altered).failed_checks(),
+            vec!["generator_refs_root"]
+        );
+
+        let mut altered = block.clone();
+        altered.reward_chain_block.total_iters += 1;
+        assert_eq!(
+            verify(&altered).failed_checks(),
+            vec!["reward_block_hash", "unfinished_reward_block_hash"]
+        );
+
+        let mut altered = block.clone();
+        altered
+            .foliage
+            .foliage_block_data
+            .unfinished_reward_block_hash = Bytes32::new([4; 32]);
+        assert_eq!(
+            verify(&altered).failed_checks(),
+            vec!["unfinished_reward_block_hash"]
+        );
+
+        // Without the referenced generator the coins can't be recomputed, which doesn't
+        // make the block valid
+        let mut unverifiable = block.clone();
+        unverifiable.transactions_generator_ref_list = vec![1];
+        commit_block(&parser, &mut unverifiable);
+        let verification = verify(&unverifiable);
+        assert!(verification.failed_checks().is_empty());
+        assert_eq!(
+            verification.unverifiable_checks(),
+            vec!["additions_root", "removals_root", "filter_hash"]
+        );
+        assert!(!verification.is_valid());
+
+        let mut altered = block.clone();
+        altered.foliage_transaction_block = None;
+        let verification = verify(&altered);
+        assert_eq!(
+            verification.failed_checks(),
+            vec!["foliage_transaction_block_hash", "transactions_info_hash"]
+        );
+        assert_eq!(verification.additions_root, CommitmentCheck::NotChecked);
+
+        // A parsed block of another block is rejected
+        let parsed = parser
+            .parse_full_block(&transaction_block(4, None))
+            .unwrap();
+        assert!(matches!(
+            parser.verify_block(&block, &parsed),
+            Err(GeneratorParserError::InvalidBlockFormat(_))
+        ));
+    }
+
+    #[test]
+    fn test_verify_synthetic_block_with_834768_generator() {
+        // Only the generator is from mainnet block 834768. The block around it and its
+        // reward claims are synthetic, so the foliage roots aren't the on-chain ones.
+        let bytes32 = |hex: &str| Bytes32::try_from(hex::decode(hex).unwrap()).unwrap();
+
+        // chia_rs lists each spend as "- coin id: <id> ph: <puzzle hash> exe-cost: <cost>
+        // cond-cost: <cost> ...", followed by "CREATE_COIN: ph: <puzzle hash> amount:
+        // <amount>" for each coin it creates
+        let mut lines = MAINNET_834768_GENERATOR.lines();
+        let generator = hex::decode(lines.next().unwrap()).unwrap();
+        let mut removals = HashSet::new();
+        let mut creations = HashSet::new();
+        let mut spend_costs = HashMap::new();
+        let mut spent_coin_id = Bytes32::default();
+        for line in lines {
+            match line.split_whitespace().collect::<Vec<_>>().as_slice() {
+                ["-", "coin", "id:", coin_id, "ph:", _, "exe-cost:", execution_cost, "cond-cost:", condition_cost, ..] =>
+                {
+                    spent_coin_id = bytes32(coin_id);
+                    removals.insert(spent_coin_id);
+                    spend_costs.insert(
+                        spent_coin_id,
+                        (
+                            execution_cost.parse::<u64>().unwrap(),
+                            condition_cost.parse::<u64>().unwrap(),
+                        ),
+                    );
+                }
+                ["CREATE_COIN:", "ph:", puzzle_hash, "amount:", amount] => {
+                    creations.insert(Coin::new(
+                        spent_coin_id,
+                        bytes32(puzzle_hash),
+                        amount.parse().unwrap(),
+                    ));
+                }
+                _ => {}
+            }
+        }
+
+        let mut block = transaction_block(834_768, Some(generator));
+        block
+            .transactions_info
+            .as_mut()
+            .unwrap()
+            .reward_claims_incorporated = vec![
+            Coin::new(
+                Bytes32::new([0x72; 32]),
+                POOL_PUZZLE_HASH,
+                1_750_000_000_000,
+            ),
+            Coin::new(
+                Bytes32::new([0x73; 32]),
+                FARMER_PUZZLE_HASH,
+                250_000_000_000,
+            ),
+        ];
+        // The filter hash is chiabip158's, over the puzzle hashes of the listed created
+        // coins and the listed coin IDs. The roots are chia_consensus merkle set roots of
+        // the listed coins, with the additions root also covering the reward claims.
+        let ftb = block.foliage_transaction_block.as_mut().unwrap();
+        ftb.filter_hash =
+            bytes32("c483f9d3f1f5d0846c87c3c1b368f4ddd6477e795f07b17df451d1205cf95913");
+        ftb.additions_root =
+            bytes32("3bcd1a1127e472f4400427e1f3778cc3ae7b473dcaa685263d62c09da368983d");
+        ftb.removals_root =
+            bytes32("3175dab8b7630bf101c7197c687eaeb9cf7664b1b4147ae170625bb907158ac5");
+
+        let parser = BlockParser::new(Network::Mainnet);
+        let parsed = parser.parse_full_block(&block).unwrap();
+        assert!(parsed.diagnostics.is_clean());
+        assert_eq!(
+            parsed
+                .coin_removals
+                .iter()
+                .map(|coin| coin.coin_id)
+                .collect::<HashSet<_>>(),
+            removals
+        );
+        assert_eq!(
+            parsed
+                .coin_creations
+                .iter()
+                .map(|coin| Coin::new(coin.parent_coin_info, coin.puzzle_hash, coin.amount))
+                .collect::<HashSet<_>>(),
+            creations
+        );
+        assert_eq!(
+            parsed
+                .coin_spends
+                .iter()
+                .map(|spend| (
+                    spend.coin.coin_id,
+                    (spend.execution_cost, spend.condition_cost)
+                ))
+                .collect::<HashMap<_, _>>(),
+            spend_costs
+        );
+
+        let verification = parser.verify_block(&block, &parsed).unwrap();
+        assert_eq!(verification.filter_hash, CommitmentCheck::Valid);
+        assert_eq!(verification.additions_root, CommitmentCheck::Valid);
+        assert_eq!(verification.removals_root, CommitmentCheck::Valid);
+    }
+
+    #[test]
+    fn test_spend_disassembly() {
+        let mut allocator = Allocator::new();
+        let spend = create_coin_spend(&mut allocator, &[1; 32], Bytes32::new([10; 32]), 5);
+        let block = transaction_block(1, Some(generator_bytes(&mut allocator, &[spend])));
+
+        let parsed = BlockParser::default().parse_full_block(&block).unwrap();
+        assert_eq!(parsed.coin_spends[0].puzzle_disassembly, None);
+
+        let parsed = BlockParser::default()
+            .with_disassembly(1024)
+            .parse_full_block(&block)
+            .unwrap();
+        let spend = &parsed.coin_spends[0];
+        assert_eq!(spend.puzzle_disassembly.as_deref(), Some("1"));
+        assert_eq!(
+            spend.solution_disassembly,
+            Some(format!("((51 0x{} 5))", "0a".repeat(32)))
+        );
+
+        let parsed = BlockParser::default()
+            .with_disassembly(16)
+            .parse_full_block(&block)
+            .unwrap();
+        assert_eq!(
+            parsed.coin_spends[0].solution_disassembly.as_deref(),
+            Some("((51 0x0a0a0a...")
+        );
     }
 }
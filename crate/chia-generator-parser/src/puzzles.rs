@@ -0,0 +1,415 @@
+use crate::types::PuzzleKind;
+use chia_protocol::{Bytes32, Program};
+use chia_puzzle_types::{
+    cat::CatArgs,
+    did::DidArgs,
+    nft::{NftOwnershipLayerArgs, NftStateLayerArgs},
+    singleton::SingletonArgs,
+    standard::StandardArgs,
+};
+use chia_puzzles::{
+    CAT_PUZZLE_HASH, DID_INNERPUZ_HASH, NFT_OWNERSHIP_LAYER_HASH, NFT_STATE_LAYER_HASH,
+    P2_1_OF_N_HASH, P2_DELEGATED_PUZZLE_OR_HIDDEN_PUZZLE_HASH, SETTLEMENT_PAYMENT_HASH,
+    SINGLETON_LAUNCHER_HASH, SINGLETON_TOP_LAYER_V1_1_HASH,
+};
+use clvm_traits::FromClvm;
+use clvm_utils::{tree_hash, tree_hash_atom, CurriedProgram};
+use clvmr::{serde::node_to_bytes, Allocator, NodePtr, SExp};
+
+/// Layers deeper than this are reported as unknown
+const MAX_LAYER_DEPTH: usize = 8;
+
+/// Metadata updater used by DataLayer stores: `11`, which returns the new metadata as-is
+pub(crate) const DL_METADATA_UPDATER: [u8; 1] = [11];
+
+#[derive(FromClvm)]
+#[clvm(curry)]
+struct P2OneOfNArgs {
+    merkle_root: Bytes32,
+}
+
+/// Identify a puzzle reveal, uncurrying nested layers down to the innermost puzzle
+pub fn identify_puzzle(allocator: &Allocator, puzzle: NodePtr) -> PuzzleKind {
+    identify_layer(allocator, puzzle, 0)
+}
+
+/// Tree hash of a puzzle's outermost mod: the uncurried program of a curried puzzle,
+/// otherwise the puzzle itself
+pub fn puzzle_mod_hash(allocator: &Allocator, puzzle: NodePtr) -> Bytes32 {
+    let puzzle_hash = tree_hash(allocator, puzzle);
+    if matches!(
+        puzzle_hash.to_bytes(),
+        SETTLEMENT_PAYMENT_HASH | SINGLETON_LAUNCHER_HASH
+    ) {
+        return puzzle_hash.into();
+    }
+
+    match CurriedProgram::<NodePtr, NodePtr>::from_clvm(allocator, puzzle) {
+        Ok(curried) => tree_hash(allocator, curried.program).into(),
+        Err(_) => puzzle_hash.into(),
+    }
+}
+
+fn identify_layer(allocator: &Allocator, puzzle: NodePtr, depth: usize) -> PuzzleKind {
+    // Compiled mods can have the same shape as a curried program, so match them first
+    match tree_hash(allocator, puzzle).to_bytes() {
+        SETTLEMENT_PAYMENT_HASH => return PuzzleKind::SettlementPayments,
+        SINGLETON_LAUNCHER_HASH => return PuzzleKind::SingletonLauncher,
+        _ => {}
+    }
+
+    let Ok(curried) = CurriedProgram::<NodePtr, NodePtr>::from_clvm(allocator, puzzle) else {
+        return unknown(allocator, puzzle);
+    };
+    if depth >= MAX_LAYER_DEPTH {
+        return unknown(allocator, curried.program);
+    }
+
+    let mod_hash = tree_hash(allocator, curried.program).to_bytes();
+    let args = curried.args;
+    let inner =
+        |inner_puzzle: NodePtr| Box::new(identify_layer(allocator, inner_puzzle, depth + 1));
+    let hash = |node: NodePtr| Bytes32::from(tree_hash(allocator, node));
+
+    let kind = match mod_hash {
+        P2_DELEGATED_PUZZLE_OR_HIDDEN_PUZZLE_HASH => StandardArgs::from_clvm(allocator, args)
+            .ok()
+            .map(|args| PuzzleKind::StandardTransaction {
+                synthetic_key: args.synthetic_key,
+            }),
+        CAT_PUZZLE_HASH => CatArgs::<NodePtr>::from_clvm(allocator, args)
+            .ok()
+            .map(|args| PuzzleKind::Cat {
+                asset_id: args.asset_id,
+                inner_puzzle_hash: hash(args.inner_puzzle),
+                inner: inner(args.inner_puzzle),
+            }),
+        SINGLETON_TOP_LAYER_V1_1_HASH => SingletonArgs::<NodePtr>::from_clvm(allocator, args)
+            .ok()
+            .map(|args| PuzzleKind::Singleton {
+                launcher_id: args.singleton_struct.launcher_id,
+                inner_puzzle_hash: hash(args.inner_puzzle),
+                inner: inner(args.inner_puzzle),
+            }),
+        NFT_STATE_LAYER_HASH => NftStateLayerArgs::<NodePtr, NodePtr>::from_clvm(allocator, args)
+            .ok()
+            .map(|args| nft_state_layer(allocator, args, depth)),
+        NFT_OWNERSHIP_LAYER_HASH => {
+            NftOwnershipLayerArgs::<NodePtr, NodePtr>::from_clvm(allocator, args)
+                .ok()
+                .map(|args| PuzzleKind::NftOwnership {
+                    current_owner: args.current_owner,
+                    transfer_program_hash: hash(args.transfer_program),
+                    inner_puzzle_hash: hash(args.inner_puzzle),
+                    inner: inner(args.inner_puzzle),
+                })
+        }
+        DID_INNERPUZ_HASH => DidArgs::<NodePtr, NodePtr>::from_clvm(allocator, args)
+            .ok()
+            .map(|args| PuzzleKind::Did {
+                launcher_id: args.singleton_struct.launcher_id,
+                recovery_list_hash: args.recovery_list_hash,
+                num_verifications_required: args.num_verifications_required,
+                metadata: serialize(allocator, args.metadata),
+                inner_puzzle_hash: hash(args.inner_puzzle),
+                inner: inner(args.inner_puzzle),
+            }),
+        P2_1_OF_N_HASH => {
+            P2OneOfNArgs::from_clvm(allocator, args)
+                .ok()
+                .map(|args| PuzzleKind::Clawback {
+                    merkle_root: args.merkle_root,
+                })
+        }
+        _ => None,
+    };
+
+    kind.unwrap_or_else(|| unknown(allocator, curried.program))
+}
+
+/// NFT state layers with the DataLayer metadata updater are DataLayer stores
+fn nft_state_layer(
+    allocator: &Allocator,
+    args: NftStateLayerArgs<NodePtr, NodePtr>,
+    depth: usize,
+) -> PuzzleKind {
+    let metadata = serialize(allocator, args.metadata);
+    let inner_puzzle_hash = tree_hash(allocator, args.inner_puzzle).into();
+    let inner = Box::new(identify_layer(allocator, args.inner_puzzle, depth + 1));
+
+    if args.metadata_updater_puzzle_hash == tree_hash_atom(&DL_METADATA_UPDATER).into() {
+        PuzzleKind::DataLayer {
+            root_hash: dl_root_hash(allocator, args.metadata),
+            metadata,
+            inner_puzzle_hash,
+            inner,
+        }
+    } else {
+        PuzzleKind::NftState {
+            metadata,
+            metadata_updater_puzzle_hash: args.metadata_updater_puzzle_hash,
+            inner_puzzle_hash,
+            inner,
+        }
+    }
+}
+
+fn unknown(allocator: &Allocator, program: NodePtr) -> PuzzleKind {
+    PuzzleKind::Unknown {
+        mod_hash: tree_hash(allocator, program).into(),
+    }
+}
+
+pub(crate) fn serialize(allocator: &Allocator, node: NodePtr) -> Program {
+    node_to_bytes(allocator, node)
+        .map(Program::from)
+        .unwrap_or_default()
+}
+
+/// Root hash of a DataLayer store: the metadata itself, or the first item of a metadata list
+pub(crate) fn dl_root_hash(allocator: &Allocator, metadata: NodePtr) -> Option<Bytes32> {
+    let root = match allocator.sexp(metadata) {
+        SExp::Atom => metadata,
+        SExp::Pair(first, _) => first,
+    };
+
+    match allocator.sexp(root) {
+        SExp::Atom => {
+            let atom = allocator.atom(root);
+            Bytes32::try_from(atom.as_ref()).ok()
+        }
+        SExp::Pair(..) => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chia_bls::PublicKey;
+    use chia_puzzle_types::singleton::SingletonStruct;
+    use chia_puzzles::{
+        DID_INNERPUZ, NFT_OWNERSHIP_LAYER, NFT_STATE_LAYER, P2_1_OF_N,
+        P2_DELEGATED_PUZZLE_OR_HIDDEN_PUZZLE, SETTLEMENT_PAYMENT, SINGLETON_TOP_LAYER_V1_1,
+    };
+    use clvm_traits::{clvm_curried_args, ToClvm};
+    use clvmr::serde::node_from_bytes;
+
+    fn curry(allocator: &mut Allocator, program: &[u8], args: impl ToClvm<Allocator>) -> NodePtr {
+        let program = node_from_bytes(allocator, program).unwrap();
+        CurriedProgram { program, args }.to_clvm(allocator).unwrap()
+    }
+
+    fn standard(allocator: &mut Allocator) -> NodePtr {
+        curry(
+            allocator,
+            &P2_DELEGATED_PUZZLE_OR_HIDDEN_PUZZLE,
+            StandardArgs::new(PublicKey::default()),
+        )
+    }
+
+    #[test]
+    fn test_identify_cat_wrapping_standard_transaction() {
+        let mut allocator = Allocator::new();
+        let standard_mod =
+            node_from_bytes(&mut allocator, &P2_DELEGATED_PUZZLE_OR_HIDDEN_PUZZLE).unwrap();
+        let standard = CurriedProgram {
+            program: standard_mod,
+            args: StandardArgs::new(PublicKey::default()),
+        }
+        .to_clvm(&mut allocator)
+        .unwrap();
+
+        let cat_mod = node_from_bytes(&mut allocator, &chia_puzzles::CAT_PUZZLE).unwrap();
+        let asset_id = Bytes32::new([7; 32]);
+        let cat = CurriedProgram {
+            program: cat_mod,
+            args: CatArgs::new(asset_id, standard),
+        }
+        .to_clvm(&mut allocator)
+        .unwrap();
+
+        let PuzzleKind::Cat {
+            asset_id: parsed_asset_id,
+            inner_puzzle_hash,
+            inner,
+        } = identify_puzzle(&allocator, cat)
+        else {
+            panic!("expected a CAT");
+        };
+
+        assert_eq!(parsed_asset_id, asset_id);
+        assert_eq!(
+            inner_puzzle_hash,
+            StandardArgs::curry_tree_hash(PublicKey::default()).into()
+        );
+        assert!(matches!(*inner, PuzzleKind::StandardTransaction { .. }));
+    }
+
+    #[test]
+    fn test_identify_uncurried_puzzles() {
+        let mut allocator = Allocator::new();
+        let settlement = node_from_bytes(&mut allocator, &SETTLEMENT_PAYMENT).unwrap();
+        let other = allocator.new_atom(&[1]).unwrap();
+
+        assert_eq!(
+            identify_puzzle(&allocator, settlement),
+            PuzzleKind::SettlementPayments
+        );
+        assert!(matches!(
+            identify_puzzle(&allocator, other),
+            PuzzleKind::Unknown { .. }
+        ));
+    }
+
+    #[test]
+    fn test_identify_singleton() {
+        let mut allocator = Allocator::new();
+        let launcher_id = Bytes32::new([1; 32]);
+        let inner_puzzle = standard(&mut allocator);
+        let singleton = curry(
+            &mut allocator,
+            &SINGLETON_TOP_LAYER_V1_1,
+            SingletonArgs::new(launcher_id, inner_puzzle),
+        );
+
+        assert_eq!(
+            identify_puzzle(&allocator, singleton),
+            PuzzleKind::Singleton {
+                launcher_id,
+                inner_puzzle_hash: tree_hash(&allocator, inner_puzzle).into(),
+                inner: Box::new(PuzzleKind::StandardTransaction {
+                    synthetic_key: PublicKey::default(),
+                }),
+            }
+        );
+    }
+
+    #[test]
+    fn test_identify_nft() {
+        let mut allocator = Allocator::new();
+        let owner = Bytes32::new([2; 32]);
+        let inner_puzzle = standard(&mut allocator);
+        let transfer_program = allocator.new_atom(&[1]).unwrap();
+        let ownership = curry(
+            &mut allocator,
+            &NFT_OWNERSHIP_LAYER,
+            NftOwnershipLayerArgs::new(Some(owner), transfer_program, inner_puzzle),
+        );
+        let metadata = allocator.new_atom(b"metadata").unwrap();
+        let state = curry(
+            &mut allocator,
+            &NFT_STATE_LAYER,
+            NftStateLayerArgs::new(metadata, ownership),
+        );
+        let nft = curry(
+            &mut allocator,
+            &SINGLETON_TOP_LAYER_V1_1,
+            SingletonArgs::new(Bytes32::new([3; 32]), state),
+        );
+
+        let PuzzleKind::Singleton { inner, .. } = identify_puzzle(&allocator, nft) else {
+            panic!("expected a singleton");
+        };
+        let PuzzleKind::NftState {
+            metadata: parsed_metadata,
+            inner,
+            ..
+        } = *inner
+        else {
+            panic!("expected an NFT state layer");
+        };
+        assert_eq!(parsed_metadata, serialize(&allocator, metadata));
+        let PuzzleKind::NftOwnership {
+            current_owner,
+            transfer_program_hash,
+            inner,
+            ..
+        } = *inner
+        else {
+            panic!("expected an NFT ownership layer");
+        };
+        assert_eq!(current_owner, Some(owner));
+        assert_eq!(
+            transfer_program_hash,
+            tree_hash(&allocator, transfer_program).into()
+        );
+        assert!(matches!(*inner, PuzzleKind::StandardTransaction { .. }));
+    }
+
+    #[test]
+    fn test_identify_did() {
+        let mut allocator = Allocator::new();
+        let launcher_id = Bytes32::new([4; 32]);
+        let recovery_list_hash = Bytes32::new([5; 32]);
+        let inner_puzzle = standard(&mut allocator);
+        let did = curry(
+            &mut allocator,
+            &DID_INNERPUZ,
+            DidArgs::new(
+                inner_puzzle,
+                Some(recovery_list_hash),
+                1,
+                SingletonStruct::new(launcher_id),
+                NodePtr::NIL,
+            ),
+        );
+
+        let PuzzleKind::Did {
+            launcher_id: parsed_launcher_id,
+            recovery_list_hash: parsed_recovery_list_hash,
+            num_verifications_required,
+            inner,
+            ..
+        } = identify_puzzle(&allocator, did)
+        else {
+            panic!("expected a DID");
+        };
+        assert_eq!(parsed_launcher_id, launcher_id);
+        assert_eq!(parsed_recovery_list_hash, Some(recovery_list_hash));
+        assert_eq!(num_verifications_required, 1);
+        assert!(matches!(*inner, PuzzleKind::StandardTransaction { .. }));
+    }
+
+    #[test]
+    fn test_identify_datalayer_store() {
+        let mut allocator = Allocator::new();
+        let root_hash = Bytes32::new([6; 32]);
+        let inner_puzzle = standard(&mut allocator);
+        let label = allocator.new_atom(b"label").unwrap();
+        let root = allocator.new_atom(&root_hash).unwrap();
+        let metadata = allocator.new_pair(root, label).unwrap();
+        let store = curry(
+            &mut allocator,
+            &NFT_STATE_LAYER,
+            NftStateLayerArgs {
+                mod_hash: NFT_STATE_LAYER_HASH.into(),
+                metadata,
+                metadata_updater_puzzle_hash: tree_hash_atom(&DL_METADATA_UPDATER).into(),
+                inner_puzzle,
+            },
+        );
+
+        let PuzzleKind::DataLayer {
+            root_hash: parsed_root_hash,
+            inner,
+            ..
+        } = identify_puzzle(&allocator, store)
+        else {
+            panic!("expected a DataLayer store");
+        };
+        assert_eq!(parsed_root_hash, Some(root_hash));
+        assert!(matches!(*inner, PuzzleKind::StandardTransaction { .. }));
+    }
+
+    #[test]
+    fn test_identify_clawback() {
+        let mut allocator = Allocator::new();
+        let merkle_root = Bytes32::new([7; 32]);
+        let clawback = curry(&mut allocator, &P2_1_OF_N, clvm_curried_args!(merkle_root));
+
+        assert_eq!(
+            identify_puzzle(&allocator, clawback),
+            PuzzleKind::Clawback { merkle_root }
+        );
+    }
+}
@@ -1,6 +1,10 @@
+#[cfg(feature = "serde")]
 use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
 
-pub use chia_protocol::{Bytes32, Coin};
+pub use chia_bls::PublicKey;
+pub use chia_consensus::opcodes::ConditionOpcode;
+pub use chia_protocol::{Bytes, Bytes32, Coin, PoolTarget, Program};
 
 // Basic numeric types - use standard Rust types for simplicity
 pub type Uint32 = u32;
@@ -17,20 +21,25 @@ pub type BlockHeight = Uint32;
 pub type Hash32 = Bytes32;
 
 /// Comprehensive parsed block information including all coin data
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub struct ParsedBlock {
     /// Block height
     pub height: u32,
 
     /// Block weight
-    pub weight: String,
+    #[cfg_attr(feature = "serde", serde(with = "crate::encoding::decimal"))]
+    pub weight: Uint128,
 
-    /// Block header hash (hex string for serialization)
-    pub header_hash: String,
+    /// Block header hash
+    pub header_hash: Bytes32,
 
     /// Block timestamp (optional)
     pub timestamp: Option<u32>,
 
+    /// Proof of space that won the block and where its rewards go
+    pub farming: FarmingInfo,
+
     /// Coin additions (new coins created)
     pub coin_additions: Vec<CoinInfo>,
 
@@ -43,6 +52,41 @@ pub struct ParsedBlock {
     /// Coins created by spends (if generator present)
     pub coin_creations: Vec<CoinInfo>,
 
+    /// Coin IDs of the coins created in this block, keyed by hint
+    pub hint_index: BTreeMap<Bytes32, Vec<Bytes32>>,
+
+    /// Total cost of the generator: byte cost, CLVM execution and conditions
+    #[cfg_attr(feature = "serde", serde(with = "crate::encoding::decimal"))]
+    pub cost: Uint64,
+
+    /// CLVM execution cost of the generator
+    #[cfg_attr(feature = "serde", serde(with = "crate::encoding::decimal"))]
+    pub execution_cost: Uint64,
+
+    /// Cost of the conditions output by all spends
+    #[cfg_attr(feature = "serde", serde(with = "crate::encoding::decimal"))]
+    pub condition_cost: Uint64,
+
+    /// Total fee: amount of spent coins minus amount of created coins, excluding rewards
+    #[cfg_attr(feature = "serde", serde(with = "crate::encoding::decimal"))]
+    pub fees: Uint64,
+
+    /// Sum of all RESERVE_FEE conditions
+    #[cfg_attr(feature = "serde", serde(with = "crate::encoding::decimal"))]
+    pub reserve_fee: Uint64,
+
+    /// Result of checking the block's aggregate signature
+    pub signature_validation: SignatureValidation,
+
+    /// Failures hit while running the generator; empty when it parsed cleanly
+    pub diagnostics: ParseDiagnostics,
+
+    /// DataLayer stores launched or updated in this block
+    pub data_store_updates: Vec<DataStoreUpdate>,
+
+    /// DataLayer mirror coins created in this block
+    pub data_store_mirrors: Vec<DataStoreMirror>,
+
     /// Whether block has transactions generator
     pub has_transactions_generator: bool,
 
@@ -50,55 +94,457 @@ pub struct ParsedBlock {
     pub generator_size: Option<u32>,
 }
 
+/// Block information available from a HeaderBlock, which carries no generator
+///
+/// Returned by `BlockParser::parse_header_block` for light monitors that get blocks
+/// through the wallet protocol's `RequestBlockHeader` / `RequestHeaderBlocks`.
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct ParsedHeaderBlock {
+    /// Block height
+    pub height: u32,
+
+    /// Block weight
+    #[cfg_attr(feature = "serde", serde(with = "crate::encoding::decimal"))]
+    pub weight: Uint128,
+
+    /// Block header hash
+    pub header_hash: Bytes32,
+
+    /// Block timestamp, for transaction blocks
+    pub timestamp: Option<u32>,
+
+    /// Proof of space that won the block and where its rewards go
+    pub farming: FarmingInfo,
+
+    /// Farmer and pool reward coins incorporated by this block
+    pub reward_claims: Vec<CoinInfo>,
+
+    /// BIP158 filter over the puzzle hashes of added coins and the IDs of removed coins
+    pub transactions_filter: Bytes,
+}
+
+/// Farmer and pool attribution of a block, from its proof of space and foliage
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct FarmingInfo {
+    /// Key of the plot that won the block, which signs its foliage
+    pub plot_public_key: PublicKey,
+
+    /// Pool key of an OG plot, which has no pool contract
+    pub pool_public_key: Option<PublicKey>,
+
+    /// Pool contract (plot NFT) puzzle hash of a portable plot
+    pub pool_contract_puzzle_hash: Option<Bytes32>,
+
+    /// Puzzle hash the farmer reward is paid to
+    pub farmer_reward_puzzle_hash: Bytes32,
+
+    /// Puzzle hash the pool reward is paid to, valid up to `max_height` unless it is 0
+    pub pool_target: PoolTarget,
+
+    /// Signage point in the sub-slot the proof was found for
+    pub signage_point_index: u8,
+
+    /// Plot size `k`
+    pub plot_size: u8,
+
+    /// Plot format: 1 for the original plots, 2 for v2 plots
+    pub plot_version: u8,
+
+    /// Whether the block was found at one of the last signage points of a sub-slot and
+    /// infused in the next one
+    pub overflow: bool,
+}
+
+/// Coin data of a SpendBundle, such as a mempool item or an offer, in the same shape as
+/// the coin data of a block
+///
+/// Returned by `BlockParser::parse_spend_bundle`.
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct ParsedSpendBundle {
+    /// Coins spent
+    pub coin_removals: Vec<CoinInfo>,
+
+    /// Detailed coin spends
+    pub coin_spends: Vec<CoinSpendInfo>,
+
+    /// Coins created by the spends
+    pub coin_creations: Vec<CoinInfo>,
+
+    /// Coin IDs of the created coins, keyed by hint
+    pub hint_index: BTreeMap<Bytes32, Vec<Bytes32>>,
+
+    /// Total cost: byte cost, CLVM execution and conditions
+    #[cfg_attr(feature = "serde", serde(with = "crate::encoding::decimal"))]
+    pub cost: Uint64,
+
+    /// CLVM execution cost of the spends
+    #[cfg_attr(feature = "serde", serde(with = "crate::encoding::decimal"))]
+    pub execution_cost: Uint64,
+
+    /// Cost of the conditions output by all spends
+    #[cfg_attr(feature = "serde", serde(with = "crate::encoding::decimal"))]
+    pub condition_cost: Uint64,
+
+    /// Total fee: amount of spent coins minus amount of created coins
+    #[cfg_attr(feature = "serde", serde(with = "crate::encoding::decimal"))]
+    pub fees: Uint64,
+
+    /// Sum of all RESERVE_FEE conditions
+    #[cfg_attr(feature = "serde", serde(with = "crate::encoding::decimal"))]
+    pub reserve_fee: Uint64,
+
+    /// Result of checking the aggregate signature
+    pub signature_validation: SignatureValidation,
+
+    /// Failures hit while running the spends; empty when they parsed cleanly
+    pub diagnostics: ParseDiagnostics,
+}
+
+/// Step of generator processing at which parsing failed
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "snake_case"))]
+pub enum ParseStage {
+    /// Fetching the generators of blocks in `transactions_generator_ref_list`
+    ResolveGeneratorRefs,
+    /// Deserializing the generator program
+    DeserializeGenerator,
+    /// Building the generator arguments from the referenced generators
+    SetupGeneratorArgs,
+    /// Running the generator to get the list of coin spends
+    RunGenerator,
+    /// Validating the block's spends and conditions, as `run_block_generator2` does
+    RunBlockGenerator,
+    /// Reading a coin spend from the generator output
+    ParseCoinSpend,
+    /// Running a single coin's puzzle for its conditions
+    RunPuzzle,
+    /// Matching a coin spend to its validated conditions by coin ID
+    MatchConditions,
+}
+
+impl ParseStage {
+    /// Stage name, e.g. "run_generator"
+    pub fn name(&self) -> &'static str {
+        match self {
+            ParseStage::ResolveGeneratorRefs => "resolve_generator_refs",
+            ParseStage::DeserializeGenerator => "deserialize_generator",
+            ParseStage::SetupGeneratorArgs => "setup_generator_args",
+            ParseStage::RunGenerator => "run_generator",
+            ParseStage::RunBlockGenerator => "run_block_generator",
+            ParseStage::ParseCoinSpend => "parse_coin_spend",
+            ParseStage::RunPuzzle => "run_puzzle",
+            ParseStage::MatchConditions => "match_conditions",
+        }
+    }
+}
+
+/// A failure hit while parsing a block
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct ParseFailure {
+    pub stage: ParseStage,
+
+    /// Numeric `chia_consensus` ErrorCode, when the failure came from consensus code
+    pub error_code: Option<Uint32>,
+
+    /// Description of the failure, with the ErrorCode name when there is one
+    pub message: String,
+
+    /// Spend the failure applies to, for `parse_coin_spend`, `run_puzzle` and
+    /// `match_conditions`
+    ///
+    /// Every stage uses the same index: the spend's position in the generator output,
+    /// which for a spend bundle is its index in `coin_spends`. A block's `coin_spends`
+    /// leave out spends that fail to parse or don't match the coin filter, so this isn't
+    /// an index into them. Spends left out by the coin filter have no index.
+    pub spend_index: Option<Uint32>,
+}
+
+/// Failures hit while parsing a block
+///
+/// Outside strict mode a failing stage leaves the block's coin data empty or partial, so
+/// check `is_clean()` before treating a block without spends as empty.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct ParseDiagnostics {
+    pub failures: Vec<ParseFailure>,
+}
+
+impl ParseDiagnostics {
+    /// Whether every stage succeeded
+    pub fn is_clean(&self) -> bool {
+        self.failures.is_empty()
+    }
+}
+
+/// Outcome of validating a block's aggregate signature against its AGG_SIG conditions
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "snake_case"))]
+pub enum SignatureValidation {
+    /// Validation is disabled, the block has no generator, or the generator failed to run
+    #[default]
+    NotValidated,
+    Valid,
+    Invalid,
+}
+
+impl SignatureValidation {
+    /// Status name, e.g. "not_validated"
+    pub fn name(&self) -> &'static str {
+        match self {
+            SignatureValidation::NotValidated => "not_validated",
+            SignatureValidation::Valid => "valid",
+            SignatureValidation::Invalid => "invalid",
+        }
+    }
+}
+
+/// Outcome of checking a hash a block commits to against the value recomputed from it
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "serde", serde(tag = "status", rename_all = "snake_case"))]
+pub enum CommitmentCheck {
+    /// The block doesn't make the commitment
+    #[default]
+    NotChecked,
+    /// The block makes the commitment, but its generator didn't parse cleanly so the
+    /// value can't be recomputed
+    Unverifiable,
+    Valid,
+    /// `committed` is the hash in the block and `computed` the one recomputed from it;
+    /// either is missing when the block has only one side, e.g. a foliage transaction
+    /// block hash without a foliage transaction block
+    Invalid {
+        committed: Option<Bytes32>,
+        computed: Option<Bytes32>,
+    },
+}
+
+impl CommitmentCheck {
+    /// Compare a committed hash with the recomputed one, when the block has either
+    pub fn compare(committed: Option<Bytes32>, computed: Option<Bytes32>) -> Self {
+        match (committed, computed) {
+            (None, None) => CommitmentCheck::NotChecked,
+            (committed, computed) if committed == computed => CommitmentCheck::Valid,
+            (committed, computed) => CommitmentCheck::Invalid {
+                committed,
+                computed,
+            },
+        }
+    }
+
+    /// Status name, e.g. "not_checked"
+    pub fn name(&self) -> &'static str {
+        match self {
+            CommitmentCheck::NotChecked => "not_checked",
+            CommitmentCheck::Unverifiable => "unverifiable",
+            CommitmentCheck::Valid => "valid",
+            CommitmentCheck::Invalid { .. } => "invalid",
+        }
+    }
+
+    pub fn is_invalid(&self) -> bool {
+        matches!(self, CommitmentCheck::Invalid { .. })
+    }
+}
+
+/// Report of `BlockParser::verify_block`: each hash the block's foliage commits to,
+/// checked against the value recomputed from the block
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct BlockVerification {
+    /// Block header hash, the hash of the foliage
+    pub header_hash: Bytes32,
+
+    /// Foliage `reward_block_hash` against the unfinished reward chain block
+    pub reward_block_hash: CommitmentCheck,
+
+    /// Foliage block data `unfinished_reward_block_hash` against the unfinished reward
+    /// chain block
+    pub unfinished_reward_block_hash: CommitmentCheck,
+
+    /// Foliage `foliage_transaction_block_hash` against the foliage transaction block
+    pub foliage_transaction_block_hash: CommitmentCheck,
+
+    /// Foliage transaction block `transactions_info_hash` against the transactions info
+    pub transactions_info_hash: CommitmentCheck,
+
+    /// Transactions info `generator_root` against the block's generator
+    pub generator_root: CommitmentCheck,
+
+    /// Transactions info `generator_refs_root` against the block's generator refs
+    pub generator_refs_root: CommitmentCheck,
+
+    /// Foliage transaction block `additions_root` against the parsed coin additions
+    pub additions_root: CommitmentCheck,
+
+    /// Foliage transaction block `removals_root` against the parsed coin removals
+    pub removals_root: CommitmentCheck,
+
+    /// Foliage transaction block `filter_hash` against the transactions filter built from
+    /// the parsed coins
+    pub filter_hash: CommitmentCheck,
+}
+
+impl BlockVerification {
+    /// Every check with its name, e.g. "additions_root"
+    pub fn checks(&self) -> [(&'static str, CommitmentCheck); 9] {
+        [
+            ("reward_block_hash", self.reward_block_hash),
+            (
+                "unfinished_reward_block_hash",
+                self.unfinished_reward_block_hash,
+            ),
+            (
+                "foliage_transaction_block_hash",
+                self.foliage_transaction_block_hash,
+            ),
+            ("transactions_info_hash", self.transactions_info_hash),
+            ("generator_root", self.generator_root),
+            ("generator_refs_root", self.generator_refs_root),
+            ("additions_root", self.additions_root),
+            ("removals_root", self.removals_root),
+            ("filter_hash", self.filter_hash),
+        ]
+    }
+
+    /// Names of the checks that failed
+    pub fn failed_checks(&self) -> Vec<&'static str> {
+        self.checks()
+            .into_iter()
+            .filter(|(_, check)| check.is_invalid())
+            .map(|(name, _)| name)
+            .collect()
+    }
+
+    /// Names of the checks whose commitment couldn't be recomputed
+    pub fn unverifiable_checks(&self) -> Vec<&'static str> {
+        self.checks()
+            .into_iter()
+            .filter(|(_, check)| *check == CommitmentCheck::Unverifiable)
+            .map(|(name, _)| name)
+            .collect()
+    }
+
+    /// Whether every commitment the block makes was recomputed and matches
+    ///
+    /// Commitments the block doesn't make don't count against it, but one that couldn't
+    /// be recomputed does: such a block is neither valid nor shown to be invalid.
+    pub fn is_valid(&self) -> bool {
+        self.checks()
+            .iter()
+            .all(|(_, check)| matches!(check, CommitmentCheck::NotChecked | CommitmentCheck::Valid))
+    }
+}
+
 /// Information about a coin (unspent transaction output)
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub struct CoinInfo {
-    /// Parent coin ID (hex string)
-    pub parent_coin_info: String,
+    /// Coin ID, sha256(parent_coin_info || puzzle_hash || amount)
+    pub coin_id: Bytes32,
 
-    /// Puzzle hash (hex string)  
-    pub puzzle_hash: String,
+    /// Parent coin ID
+    pub parent_coin_info: Bytes32,
+
+    /// Puzzle hash
+    pub puzzle_hash: Bytes32,
 
     /// Amount in mojos
+    #[cfg_attr(feature = "serde", serde(with = "crate::encoding::decimal"))]
     pub amount: Uint64,
+
+    /// Index in `coin_spends` of the spend that created this coin, if created by this block
+    /// by a spend that wasn't left out by the parser's coin filter
+    pub created_by_spend: Option<Uint32>,
+
+    /// Whether the coin was both created and spent in this block
+    pub ephemeral: bool,
+
+    /// Memos attached to the CREATE_COIN condition
+    pub memos: Vec<Bytes>,
+
+    /// Hint for wallet lookups: the first memo, when it is 32 bytes
+    pub hint: Option<Bytes32>,
 }
 
 impl CoinInfo {
     pub fn new(parent_coin_info: Bytes32, puzzle_hash: Bytes32, amount: Uint64) -> Self {
         Self {
-            parent_coin_info: hex::encode(parent_coin_info),
-            puzzle_hash: hex::encode(puzzle_hash),
+            coin_id: Coin::new(parent_coin_info, puzzle_hash, amount).coin_id(),
+            parent_coin_info,
+            puzzle_hash,
             amount,
+            created_by_spend: None,
+            ephemeral: false,
+            memos: Vec::new(),
+            hint: None,
         }
     }
 
-    /// Create from raw bytes
-    pub fn from_bytes(parent_coin_info: &[u8], puzzle_hash: &[u8], amount: Uint64) -> Self {
-        Self {
-            parent_coin_info: hex::encode(parent_coin_info),
-            puzzle_hash: hex::encode(puzzle_hash),
+    /// Attach the memos of the CREATE_COIN condition that created this coin
+    pub fn with_memos(mut self, memos: &[Vec<u8>]) -> Self {
+        self.hint = memos
+            .first()
+            .and_then(|memo| Bytes32::try_from(memo.as_slice()).ok());
+        self.memos = memos.iter().cloned().map(Bytes::from).collect();
+        self
+    }
+
+    /// Create from raw bytes, when both hashes are 32 bytes
+    pub fn from_bytes(parent_coin_info: &[u8], puzzle_hash: &[u8], amount: Uint64) -> Option<Self> {
+        Some(Self::new(
+            parent_coin_info.try_into().ok()?,
+            puzzle_hash.try_into().ok()?,
             amount,
-        }
+        ))
     }
 }
 
 /// Detailed coin spend information with transaction data
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub struct CoinSpendInfo {
     pub coin: CoinInfo,
-    pub puzzle_reveal: String,
-    pub solution: String,
+    pub puzzle_reveal: Program,
+    pub solution: Program,
     pub real_data: bool,
     pub parsing_method: String,
     pub offset: Uint32,
     pub created_coins: Vec<CoinInfo>,
+
+    /// Conditions output by the puzzle, other than CREATE_COIN
+    pub conditions: Vec<ConditionInfo>,
+
+    /// CLVM cost of running the puzzle with its solution
+    #[cfg_attr(feature = "serde", serde(with = "crate::encoding::decimal"))]
+    pub execution_cost: Uint64,
+
+    /// Cost consensus charged for the spend's conditions, `0` when validation stopped at
+    /// or before this spend
+    #[cfg_attr(feature = "serde", serde(with = "crate::encoding::decimal"))]
+    pub condition_cost: Uint64,
+
+    /// Well-known puzzle identified from the puzzle reveal
+    pub puzzle_kind: PuzzleKind,
+
+    /// Chialisp-style text of the puzzle reveal, when the parser disassembles spends
+    pub puzzle_disassembly: Option<String>,
+
+    /// Chialisp-style text of the solution, when the parser disassembles spends
+    pub solution_disassembly: Option<String>,
 }
 
 impl CoinSpendInfo {
     pub fn new(
         coin: CoinInfo,
-        puzzle_reveal: String,
-        solution: String,
+        puzzle_reveal: Program,
+        solution: Program,
         real_data: bool,
         parsing_method: String,
         offset: Uint32,
@@ -112,6 +558,361 @@ impl CoinSpendInfo {
             parsing_method,
             offset,
             created_coins,
+            conditions: Vec::new(),
+            execution_cost: 0,
+            condition_cost: 0,
+            puzzle_kind: PuzzleKind::default(),
+            puzzle_disassembly: None,
+            solution_disassembly: None,
+        }
+    }
+}
+
+/// A DataLayer store singleton launched or spent in a block
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct DataStoreUpdate {
+    /// Launcher ID of the store singleton
+    pub launcher_id: Bytes32,
+
+    /// Coin ID of the spent launcher or store coin
+    pub coin_id: Bytes32,
+
+    /// Root hash of the store after this spend
+    pub new_root_hash: Option<Bytes32>,
+
+    /// Root hash before this spend; `None` when the store is launched
+    pub previous_root_hash: Option<Bytes32>,
+
+    /// Serialized store metadata after this spend
+    pub metadata: Program,
+
+    /// Inner puzzle hash of the store owner after this spend; `None` when the store is melted
+    pub owner_puzzle_hash: Option<Bytes32>,
+}
+
+/// A DataLayer mirror coin, which advertises URLs serving a store's data
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct DataStoreMirror {
+    /// Coin ID of the mirror coin
+    pub coin_id: Bytes32,
+
+    /// Launcher ID of the mirrored store
+    pub launcher_id: Bytes32,
+
+    /// Amount in mojos
+    #[cfg_attr(feature = "serde", serde(with = "crate::encoding::decimal"))]
+    pub amount: Uint64,
+
+    /// Mirror URLs from the coin's memos
+    pub urls: Vec<String>,
+}
+
+/// Well-known puzzle, with its curried arguments
+///
+/// Outer layers such as CAT or singleton hold the kind of their inner puzzle in `inner`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "serde", serde(tag = "type", rename_all = "snake_case"))]
+pub enum PuzzleKind {
+    /// p2_delegated_puzzle_or_hidden_puzzle
+    StandardTransaction {
+        synthetic_key: PublicKey,
+    },
+    /// CAT2 outer layer
+    Cat {
+        asset_id: Bytes32,
+        inner_puzzle_hash: Bytes32,
+        inner: Box<PuzzleKind>,
+    },
+    /// Singleton top layer v1.1
+    Singleton {
+        launcher_id: Bytes32,
+        inner_puzzle_hash: Bytes32,
+        inner: Box<PuzzleKind>,
+    },
+    SingletonLauncher,
+    NftState {
+        metadata: Program,
+        metadata_updater_puzzle_hash: Bytes32,
+        inner_puzzle_hash: Bytes32,
+        inner: Box<PuzzleKind>,
+    },
+    NftOwnership {
+        current_owner: Option<Bytes32>,
+        transfer_program_hash: Bytes32,
+        inner_puzzle_hash: Bytes32,
+        inner: Box<PuzzleKind>,
+    },
+    /// DID inner puzzle; the launcher ID comes from its curried singleton struct
+    Did {
+        launcher_id: Bytes32,
+        recovery_list_hash: Option<Bytes32>,
+        #[cfg_attr(feature = "serde", serde(with = "crate::encoding::decimal"))]
+        num_verifications_required: Uint64,
+        metadata: Program,
+        inner_puzzle_hash: Bytes32,
+        inner: Box<PuzzleKind>,
+    },
+    /// NFT state layer with the DataLayer metadata updater
+    DataLayer {
+        root_hash: Option<Bytes32>,
+        metadata: Program,
+        inner_puzzle_hash: Bytes32,
+        inner: Box<PuzzleKind>,
+    },
+    /// Offer settlement payments
+    SettlementPayments,
+    /// p2_1_of_n, which wallet clawback coins use to choose between claim and clawback
+    Clawback {
+        merkle_root: Bytes32,
+    },
+    /// Any other puzzle; for curried puzzles this is the uncurried mod hash
+    Unknown {
+        mod_hash: Bytes32,
+    },
+}
+
+impl Default for PuzzleKind {
+    fn default() -> Self {
+        PuzzleKind::Unknown {
+            mod_hash: Bytes32::default(),
+        }
+    }
+}
+
+impl PuzzleKind {
+    /// Puzzle kind name, e.g. "cat"
+    pub fn name(&self) -> &'static str {
+        match self {
+            PuzzleKind::StandardTransaction { .. } => "standard_transaction",
+            PuzzleKind::Cat { .. } => "cat",
+            PuzzleKind::Singleton { .. } => "singleton",
+            PuzzleKind::SingletonLauncher => "singleton_launcher",
+            PuzzleKind::NftState { .. } => "nft_state",
+            PuzzleKind::NftOwnership { .. } => "nft_ownership",
+            PuzzleKind::Did { .. } => "did",
+            PuzzleKind::DataLayer { .. } => "data_layer",
+            PuzzleKind::SettlementPayments => "settlement_payments",
+            PuzzleKind::Clawback { .. } => "clawback",
+            PuzzleKind::Unknown { .. } => "unknown",
+        }
+    }
+
+    /// Kind of the inner puzzle, for layers that wrap one
+    pub fn inner(&self) -> Option<&PuzzleKind> {
+        match self {
+            PuzzleKind::Cat { inner, .. }
+            | PuzzleKind::Singleton { inner, .. }
+            | PuzzleKind::NftState { inner, .. }
+            | PuzzleKind::NftOwnership { inner, .. }
+            | PuzzleKind::Did { inner, .. }
+            | PuzzleKind::DataLayer { inner, .. } => Some(inner),
+            _ => None,
+        }
+    }
+
+    /// This layer followed by every inner layer, outermost first
+    pub fn layers(&self) -> impl Iterator<Item = &PuzzleKind> {
+        std::iter::successors(Some(self), |kind| kind.inner())
+    }
+}
+
+/// Variant of an AGG_SIG_* condition, which selects the data appended to the message
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "snake_case"))]
+pub enum AggSigKind {
+    Parent,
+    Puzzle,
+    Amount,
+    PuzzleAmount,
+    ParentAmount,
+    ParentPuzzle,
+    Unsafe,
+    Me,
+}
+
+/// A condition output by a coin spend
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "serde", serde(tag = "type", rename_all = "snake_case"))]
+pub enum ConditionInfo {
+    AggSig {
+        kind: AggSigKind,
+        public_key: PublicKey,
+        message: Bytes,
+    },
+    ReserveFee {
+        #[cfg_attr(feature = "serde", serde(with = "crate::encoding::decimal"))]
+        amount: Uint64,
+    },
+    CreateCoinAnnouncement {
+        message: Bytes,
+    },
+    AssertCoinAnnouncement {
+        announcement_id: Bytes32,
+    },
+    CreatePuzzleAnnouncement {
+        message: Bytes,
+    },
+    AssertPuzzleAnnouncement {
+        announcement_id: Bytes32,
+    },
+    AssertConcurrentSpend {
+        coin_id: Bytes32,
+    },
+    AssertConcurrentPuzzle {
+        puzzle_hash: Bytes32,
+    },
+    /// `mode` encodes which coin properties commit the sender and receiver
+    SendMessage {
+        mode: u8,
+        message: Bytes,
+        counterparty: Vec<Bytes>,
+    },
+    ReceiveMessage {
+        mode: u8,
+        message: Bytes,
+        counterparty: Vec<Bytes>,
+    },
+    AssertSecondsRelative {
+        #[cfg_attr(feature = "serde", serde(with = "crate::encoding::decimal"))]
+        seconds: Uint64,
+    },
+    AssertSecondsAbsolute {
+        #[cfg_attr(feature = "serde", serde(with = "crate::encoding::decimal"))]
+        seconds: Uint64,
+    },
+    AssertHeightRelative {
+        height: Uint32,
+    },
+    AssertHeightAbsolute {
+        height: Uint32,
+    },
+    AssertBeforeSecondsRelative {
+        #[cfg_attr(feature = "serde", serde(with = "crate::encoding::decimal"))]
+        seconds: Uint64,
+    },
+    AssertBeforeSecondsAbsolute {
+        #[cfg_attr(feature = "serde", serde(with = "crate::encoding::decimal"))]
+        seconds: Uint64,
+    },
+    AssertBeforeHeightRelative {
+        height: Uint32,
+    },
+    AssertBeforeHeightAbsolute {
+        height: Uint32,
+    },
+    AssertMyCoinId {
+        coin_id: Bytes32,
+    },
+    AssertMyParentId {
+        parent_id: Bytes32,
+    },
+    AssertMyPuzzleHash {
+        puzzle_hash: Bytes32,
+    },
+    AssertMyAmount {
+        #[cfg_attr(feature = "serde", serde(with = "crate::encoding::decimal"))]
+        amount: Uint64,
+    },
+    AssertMyBirthSeconds {
+        #[cfg_attr(feature = "serde", serde(with = "crate::encoding::decimal"))]
+        seconds: Uint64,
+    },
+    AssertMyBirthHeight {
+        height: Uint32,
+    },
+    AssertEphemeral,
+    /// Arguments are arbitrary CLVM, so they're kept as the serialized argument list
+    Remark {
+        args: Bytes,
+    },
+}
+
+impl ConditionInfo {
+    /// Condition opcode, as used in CLVM
+    pub fn opcode(&self) -> ConditionOpcode {
+        use chia_consensus::opcodes::*;
+        match self {
+            ConditionInfo::AggSig { kind, .. } => match kind {
+                AggSigKind::Parent => AGG_SIG_PARENT,
+                AggSigKind::Puzzle => AGG_SIG_PUZZLE,
+                AggSigKind::Amount => AGG_SIG_AMOUNT,
+                AggSigKind::PuzzleAmount => AGG_SIG_PUZZLE_AMOUNT,
+                AggSigKind::ParentAmount => AGG_SIG_PARENT_AMOUNT,
+                AggSigKind::ParentPuzzle => AGG_SIG_PARENT_PUZZLE,
+                AggSigKind::Unsafe => AGG_SIG_UNSAFE,
+                AggSigKind::Me => AGG_SIG_ME,
+            },
+            ConditionInfo::ReserveFee { .. } => RESERVE_FEE,
+            ConditionInfo::CreateCoinAnnouncement { .. } => CREATE_COIN_ANNOUNCEMENT,
+            ConditionInfo::AssertCoinAnnouncement { .. } => ASSERT_COIN_ANNOUNCEMENT,
+            ConditionInfo::CreatePuzzleAnnouncement { .. } => CREATE_PUZZLE_ANNOUNCEMENT,
+            ConditionInfo::AssertPuzzleAnnouncement { .. } => ASSERT_PUZZLE_ANNOUNCEMENT,
+            ConditionInfo::AssertConcurrentSpend { .. } => ASSERT_CONCURRENT_SPEND,
+            ConditionInfo::AssertConcurrentPuzzle { .. } => ASSERT_CONCURRENT_PUZZLE,
+            ConditionInfo::SendMessage { .. } => SEND_MESSAGE,
+            ConditionInfo::ReceiveMessage { .. } => RECEIVE_MESSAGE,
+            ConditionInfo::AssertSecondsRelative { .. } => ASSERT_SECONDS_RELATIVE,
+            ConditionInfo::AssertSecondsAbsolute { .. } => ASSERT_SECONDS_ABSOLUTE,
+            ConditionInfo::AssertHeightRelative { .. } => ASSERT_HEIGHT_RELATIVE,
+            ConditionInfo::AssertHeightAbsolute { .. } => ASSERT_HEIGHT_ABSOLUTE,
+            ConditionInfo::AssertBeforeSecondsRelative { .. } => ASSERT_BEFORE_SECONDS_RELATIVE,
+            ConditionInfo::AssertBeforeSecondsAbsolute { .. } => ASSERT_BEFORE_SECONDS_ABSOLUTE,
+            ConditionInfo::AssertBeforeHeightRelative { .. } => ASSERT_BEFORE_HEIGHT_RELATIVE,
+            ConditionInfo::AssertBeforeHeightAbsolute { .. } => ASSERT_BEFORE_HEIGHT_ABSOLUTE,
+            ConditionInfo::AssertMyCoinId { .. } => ASSERT_MY_COIN_ID,
+            ConditionInfo::AssertMyParentId { .. } => ASSERT_MY_PARENT_ID,
+            ConditionInfo::AssertMyPuzzleHash { .. } => ASSERT_MY_PUZZLEHASH,
+            ConditionInfo::AssertMyAmount { .. } => ASSERT_MY_AMOUNT,
+            ConditionInfo::AssertMyBirthSeconds { .. } => ASSERT_MY_BIRTH_SECONDS,
+            ConditionInfo::AssertMyBirthHeight { .. } => ASSERT_MY_BIRTH_HEIGHT,
+            ConditionInfo::AssertEphemeral => ASSERT_EPHEMERAL,
+            ConditionInfo::Remark { .. } => REMARK,
+        }
+    }
+
+    /// Condition name, e.g. "AGG_SIG_ME"
+    pub fn name(&self) -> &'static str {
+        match self {
+            ConditionInfo::AggSig { kind, .. } => match kind {
+                AggSigKind::Parent => "AGG_SIG_PARENT",
+                AggSigKind::Puzzle => "AGG_SIG_PUZZLE",
+                AggSigKind::Amount => "AGG_SIG_AMOUNT",
+                AggSigKind::PuzzleAmount => "AGG_SIG_PUZZLE_AMOUNT",
+                AggSigKind::ParentAmount => "AGG_SIG_PARENT_AMOUNT",
+                AggSigKind::ParentPuzzle => "AGG_SIG_PARENT_PUZZLE",
+                AggSigKind::Unsafe => "AGG_SIG_UNSAFE",
+                AggSigKind::Me => "AGG_SIG_ME",
+            },
+            ConditionInfo::ReserveFee { .. } => "RESERVE_FEE",
+            ConditionInfo::CreateCoinAnnouncement { .. } => "CREATE_COIN_ANNOUNCEMENT",
+            ConditionInfo::AssertCoinAnnouncement { .. } => "ASSERT_COIN_ANNOUNCEMENT",
+            ConditionInfo::CreatePuzzleAnnouncement { .. } => "CREATE_PUZZLE_ANNOUNCEMENT",
+            ConditionInfo::AssertPuzzleAnnouncement { .. } => "ASSERT_PUZZLE_ANNOUNCEMENT",
+            ConditionInfo::AssertConcurrentSpend { .. } => "ASSERT_CONCURRENT_SPEND",
+            ConditionInfo::AssertConcurrentPuzzle { .. } => "ASSERT_CONCURRENT_PUZZLE",
+            ConditionInfo::SendMessage { .. } => "SEND_MESSAGE",
+            ConditionInfo::ReceiveMessage { .. } => "RECEIVE_MESSAGE",
+            ConditionInfo::AssertSecondsRelative { .. } => "ASSERT_SECONDS_RELATIVE",
+            ConditionInfo::AssertSecondsAbsolute { .. } => "ASSERT_SECONDS_ABSOLUTE",
+            ConditionInfo::AssertHeightRelative { .. } => "ASSERT_HEIGHT_RELATIVE",
+            ConditionInfo::AssertHeightAbsolute { .. } => "ASSERT_HEIGHT_ABSOLUTE",
+            ConditionInfo::AssertBeforeSecondsRelative { .. } => "ASSERT_BEFORE_SECONDS_RELATIVE",
+            ConditionInfo::AssertBeforeSecondsAbsolute { .. } => "ASSERT_BEFORE_SECONDS_ABSOLUTE",
+            ConditionInfo::AssertBeforeHeightRelative { .. } => "ASSERT_BEFORE_HEIGHT_RELATIVE",
+            ConditionInfo::AssertBeforeHeightAbsolute { .. } => "ASSERT_BEFORE_HEIGHT_ABSOLUTE",
+            ConditionInfo::AssertMyCoinId { .. } => "ASSERT_MY_COIN_ID",
+            ConditionInfo::AssertMyParentId { .. } => "ASSERT_MY_PARENT_ID",
+            ConditionInfo::AssertMyPuzzleHash { .. } => "ASSERT_MY_PUZZLEHASH",
+            ConditionInfo::AssertMyAmount { .. } => "ASSERT_MY_AMOUNT",
+            ConditionInfo::AssertMyBirthSeconds { .. } => "ASSERT_MY_BIRTH_SECONDS",
+            ConditionInfo::AssertMyBirthHeight { .. } => "ASSERT_MY_BIRTH_HEIGHT",
+            ConditionInfo::AssertEphemeral => "ASSERT_EPHEMERAL",
+            ConditionInfo::Remark { .. } => "REMARK",
         }
     }
 }
@@ -144,7 +945,8 @@ impl GeneratorBlockInfo {
 }
 
 /// Information about a block's height and type
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub struct BlockHeightInfo {
     /// Block height
     pub height: Uint32,
@@ -161,3 +963,104 @@ impl BlockHeightInfo {
         }
     }
 }
+
+/// A transactions generator run on its own, outside of a block
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct ParsedGenerator {
+    /// Coin spends output by the generator
+    pub coin_spends: Vec<CoinSpendInfo>,
+
+    /// Coins created by the spends
+    pub coin_creations: Vec<CoinInfo>,
+
+    /// Composition of the generator
+    pub analysis: GeneratorAnalysis,
+}
+
+/// Composition of a transactions generator: its size, cost and the puzzles it reveals
+#[derive(Debug, Clone, Default, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct GeneratorAnalysis {
+    /// Serialized size of the generator
+    pub size_bytes: Uint32,
+
+    /// Size the generator would serialize to without back references
+    #[cfg_attr(feature = "serde", serde(with = "crate::encoding::decimal"))]
+    pub expanded_size_bytes: Uint64,
+
+    /// Whether the generator is serialized with back references
+    pub uses_back_references: bool,
+
+    /// Heights of the blocks whose generators this one references
+    pub generator_ref_list: Vec<BlockHeight>,
+
+    /// Shannon entropy of the serialized generator, in bits per byte
+    pub entropy: f64,
+
+    /// Number of coin spends output by the generator
+    pub spend_count: Uint32,
+
+    /// Total cost, including the generator's byte and execution cost
+    #[cfg_attr(feature = "serde", serde(with = "crate::encoding::decimal"))]
+    pub cost: Uint64,
+
+    /// CLVM cost of running the generator and its puzzles
+    #[cfg_attr(feature = "serde", serde(with = "crate::encoding::decimal"))]
+    pub execution_cost: Uint64,
+
+    /// Fixed cost of the spends' conditions
+    #[cfg_attr(feature = "serde", serde(with = "crate::encoding::decimal"))]
+    pub condition_cost: Uint64,
+
+    /// Largest puzzle reveals, largest first
+    pub largest_puzzle_reveals: Vec<PuzzleRevealSize>,
+
+    /// Puzzle mods revealed by more than one spend, most revealed first
+    pub repeated_puzzle_mods: Vec<PuzzleModUsage>,
+
+    /// Failures while running the generator; the spend figures are partial unless clean
+    pub diagnostics: ParseDiagnostics,
+}
+
+impl GeneratorAnalysis {
+    /// Expanded size over serialized size, above 1 when back references save space
+    pub fn compression_ratio(&self) -> f64 {
+        if self.size_bytes == 0 {
+            return 1.0;
+        }
+        self.expanded_size_bytes as f64 / f64::from(self.size_bytes)
+    }
+}
+
+/// Size of a spend's puzzle reveal
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct PuzzleRevealSize {
+    /// Coin ID of the spent coin
+    pub coin_id: Bytes32,
+
+    /// Puzzle hash of the spent coin
+    pub puzzle_hash: Bytes32,
+
+    /// Serialized size of the puzzle reveal, without back references
+    pub size_bytes: Uint32,
+}
+
+/// A puzzle mod revealed by several spends of a generator
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct PuzzleModUsage {
+    /// Tree hash of the outermost mod, uncurried
+    pub mod_hash: Bytes32,
+
+    /// Puzzle kind name of the mod, e.g. "cat"
+    pub kind: String,
+
+    /// Number of spends revealing the mod
+    pub spend_count: Uint32,
+
+    /// Total serialized size of those puzzle reveals
+    #[cfg_attr(feature = "serde", serde(with = "crate::encoding::decimal"))]
+    pub reveal_bytes: Uint64,
+}
@@ -0,0 +1,221 @@
+use crate::types::CoinInfo;
+use chia_consensus::merkle_set::compute_merkle_set_root;
+use chia_protocol::{Bytes32, FullBlock};
+use sha2::{Digest, Sha256};
+use siphasher::sip::SipHasher24;
+use std::collections::{BTreeSet, HashMap};
+use std::hash::Hasher;
+
+/// Golomb-Rice parameter of Chia's transactions filter (chiabip158 uses `{0, 0, 20, 1 << 20}`)
+const FILTER_P: u8 = 20;
+
+/// False positive rate inverse of Chia's transactions filter
+const FILTER_M: u64 = 1 << 20;
+
+/// Root of the merkle set the foliage commits additions to
+///
+/// The set holds every puzzle hash receiving coins in the block, each followed by the
+/// hash of the IDs of the coins it received.
+pub fn additions_root(additions: &[CoinInfo]) -> Bytes32 {
+    let mut coin_ids_by_puzzle_hash: HashMap<Bytes32, Vec<Bytes32>> = HashMap::new();
+    for coin in additions {
+        coin_ids_by_puzzle_hash
+            .entry(coin.puzzle_hash)
+            .or_default()
+            .push(coin.coin_id);
+    }
+
+    let mut leafs: Vec<[u8; 32]> = coin_ids_by_puzzle_hash
+        .into_iter()
+        .flat_map(|(puzzle_hash, mut coin_ids)| {
+            [puzzle_hash.into(), hash_coin_ids(&mut coin_ids).into()]
+        })
+        .collect();
+    Bytes32::new(compute_merkle_set_root(&mut leafs))
+}
+
+/// Root of the merkle set of the IDs of the coins spent in the block
+pub fn removals_root(removals: &[CoinInfo]) -> Bytes32 {
+    let mut leafs: Vec<[u8; 32]> = removals.iter().map(|coin| coin.coin_id.into()).collect();
+    Bytes32::new(compute_merkle_set_root(&mut leafs))
+}
+
+/// Hash of a puzzle hash's coin IDs in an additions merkle set: the ID itself is hashed
+/// when there is one, otherwise the IDs are concatenated in descending order
+pub fn hash_coin_ids(coin_ids: &mut [Bytes32]) -> Bytes32 {
+    let mut hasher = Sha256::new();
+    if let [coin_id] = coin_ids {
+        hasher.update(coin_id);
+    } else {
+        coin_ids.sort_unstable_by(|a, b| b.cmp(a));
+        for coin_id in coin_ids.iter() {
+            hasher.update(coin_id);
+        }
+    }
+    Bytes32::new(hasher.finalize().into())
+}
+
+/// Encoded BIP158 filter over the puzzle hashes of coins created by spends and the IDs of
+/// removed coins, as served in `HeaderBlock::transactions_filter`
+///
+/// Reward claims aren't in the filter, so pass `coin_creations` rather than
+/// `coin_additions`.
+pub fn transactions_filter(creations: &[CoinInfo], removals: &[CoinInfo]) -> Vec<u8> {
+    let elements: BTreeSet<Bytes32> = creations
+        .iter()
+        .map(|coin| coin.puzzle_hash)
+        .chain(removals.iter().map(|coin| coin.coin_id))
+        .collect();
+    encode_filter(&elements)
+}
+
+/// Hash the foliage commits the transactions filter to
+pub fn filter_hash(filter: &[u8]) -> Bytes32 {
+    Bytes32::new(Sha256::digest(filter).into())
+}
+
+/// Hash the transactions info commits the generator to: the hash of its serialized
+/// program, or all zeros for a block without one
+pub fn generator_root(generator: Option<&[u8]>) -> Bytes32 {
+    match generator {
+        Some(generator) => Bytes32::new(Sha256::digest(generator).into()),
+        None => Bytes32::new([0; 32]),
+    }
+}
+
+/// Whether `block` carries the generator its own transactions info commits to
+///
+/// A referenced generator is only as trustworthy as the peer that served the block it
+/// comes from, so it is checked before it is cached for the blocks that reference it.
+pub fn generator_matches_root(block: &FullBlock) -> bool {
+    let generator = block.transactions_generator.as_ref().map(|g| g.as_ref());
+    block
+        .transactions_info
+        .as_ref()
+        .is_some_and(|info| info.generator_root == generator_root(generator))
+}
+
+/// Hash the transactions info commits the generator refs to: the hash of the heights as
+/// big-endian u32s, or all ones for a block without refs
+pub fn generator_refs_root(generator_refs: &[u32]) -> Bytes32 {
+    if generator_refs.is_empty() {
+        return Bytes32::new([1; 32]);
+    }
+
+    let mut hasher = Sha256::new();
+    for height in generator_refs {
+        hasher.update(height.to_be_bytes());
+    }
+    Bytes32::new(hasher.finalize().into())
+}
+
+/// Golomb-coded set of `elements`, encoded the way Bitcoin Core's `GCSFilter` does it
+fn encode_filter(elements: &BTreeSet<Bytes32>) -> Vec<u8> {
+    let count = elements.len() as u64;
+    let range = count * FILTER_M;
+
+    let mut values: Vec<u64> = elements
+        .iter()
+        .map(|element| {
+            let mut hasher = SipHasher24::new_with_keys(0, 0);
+            hasher.write(element.as_ref());
+            ((u128::from(hasher.finish()) * u128::from(range)) >> 64) as u64
+        })
+        .collect();
+    values.sort_unstable();
+
+    let mut writer = BitWriter {
+        bytes: compact_size(count),
+        used_bits: 8,
+    };
+    let mut last_value = 0;
+    for value in values {
+        let delta = value - last_value;
+        for _ in 0..delta >> FILTER_P {
+            writer.write_bit(true);
+        }
+        writer.write_bit(false);
+        for bit in (0..FILTER_P).rev() {
+            writer.write_bit(delta >> bit & 1 == 1);
+        }
+        last_value = value;
+    }
+    writer.bytes
+}
+
+/// Bitcoin's variable length integer encoding
+fn compact_size(value: u64) -> Vec<u8> {
+    match value {
+        0..=0xfc => vec![value as u8],
+        0xfd..=0xffff => [&[0xfd][..], &(value as u16).to_le_bytes()].concat(),
+        0x1_0000..=0xffff_ffff => [&[0xfe][..], &(value as u32).to_le_bytes()].concat(),
+        _ => [&[0xff][..], &value.to_le_bytes()].concat(),
+    }
+}
+
+/// Appends bits most significant first, padding the last byte with zeros
+struct BitWriter {
+    bytes: Vec<u8>,
+    used_bits: u8,
+}
+
+impl BitWriter {
+    fn write_bit(&mut self, bit: bool) {
+        if self.used_bits == 8 {
+            self.bytes.push(0);
+            self.used_bits = 0;
+        }
+        if bit {
+            *self.bytes.last_mut().unwrap() |= 0x80 >> self.used_bits;
+        }
+        self.used_bits += 1;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fixtures::{non_transaction_block, transaction_block};
+    use chia_protocol::Program;
+
+    #[test]
+    fn test_encode_filter_matches_chiabip158() {
+        assert_eq!(encode_filter(&BTreeSet::new()), vec![0]);
+
+        // Encoded by chiabip158 from sha256 of 0, 1 and 2 as little endian u64s
+        let elements: BTreeSet<Bytes32> = (0u64..3)
+            .map(|i| Bytes32::new(Sha256::digest(i.to_le_bytes()).into()))
+            .collect();
+        assert_eq!(hex::encode(encode_filter(&elements)), "0325d4da80186ca245");
+    }
+
+    #[test]
+    fn test_generator_matches_root() {
+        let generator = vec![0xff, 0x01, 0x80];
+        let mut block = transaction_block(10, Some(generator.clone()));
+        block.transactions_info.as_mut().unwrap().generator_root = generator_root(Some(&generator));
+        assert!(generator_matches_root(&block));
+
+        // A generator swapped by the peer that served the block
+        block.transactions_generator = Some(Program::new(vec![0x80].into()));
+        assert!(!generator_matches_root(&block));
+
+        // Nothing commits to the generator of a block without transactions info
+        assert!(!generator_matches_root(&non_transaction_block(10)));
+    }
+
+    #[test]
+    fn test_hash_coin_ids() {
+        let low = Bytes32::new([1; 32]);
+        let high = Bytes32::new([2; 32]);
+        assert_eq!(
+            hash_coin_ids(&mut [low]),
+            Bytes32::new(Sha256::digest(low).into())
+        );
+
+        let descending =
+            Bytes32::new(Sha256::digest([high.as_ref(), low.as_ref()].concat()).into());
+        assert_eq!(hash_coin_ids(&mut [low, high]), descending);
+        assert_eq!(hash_coin_ids(&mut [high, low]), descending);
+    }
+}
@@ -0,0 +1,131 @@
+//! Block fixtures shared by the unit tests, benches and examples
+//!
+//! Included with `#[path]`, so only the crate's dependencies are in scope here.
+
+#![allow(dead_code)]
+
+use chia_bls::{G1Element, G2Element};
+use chia_protocol::{
+    Bytes, Bytes32, ClassgroupElement, Foliage, FoliageBlockData, FoliageTransactionBlock,
+    FullBlock, PoolTarget, Program, ProofOfSpace, RewardChainBlock, TransactionsInfo, VDFInfo,
+    VDFProof,
+};
+
+/// Generator of mainnet block 834,768 in hex, followed by chia_rs's listing of its spends
+/// and the coins they create (from chia_rs's `generator-tests`)
+pub const MAINNET_834768_GENERATOR: &str = include_str!("../fixtures/block-834768.txt");
+
+/// A mainnet spend bundle trading a CAT for XCH through offers, settlement spends
+/// included (from chia_rs's `test-bundles`)
+pub const MAINNET_CAT_TRADE_BUNDLE: &[u8] = include_bytes!(
+    "../fixtures/2d2ad9eb0be3b6cda65183b525fb24a02eca9eba654d146a16b7e007dbb6191c.bundle"
+);
+
+/// Timestamp of every fixture transaction block
+pub const TIMESTAMP: u64 = 1_700_000_000;
+
+/// Pool target puzzle hash of every fixture block
+pub const POOL_PUZZLE_HASH: Bytes32 = Bytes32::new([0x70; 32]);
+
+/// Farmer reward puzzle hash of every fixture block
+pub const FARMER_PUZZLE_HASH: Bytes32 = Bytes32::new([0x71; 32]);
+
+/// A transaction block at `height` running `generator`; only the fields the parser reads
+/// matter
+///
+/// Blocks at different heights have different header hashes. The block claims no
+/// rewards.
+pub fn transaction_block(height: u32, generator: Option<Vec<u8>>) -> FullBlock {
+    let foliage_transaction_block = FoliageTransactionBlock::new(
+        Bytes32::default(),
+        TIMESTAMP,
+        Bytes32::default(),
+        Bytes32::default(),
+        Bytes32::default(),
+        Bytes32::default(),
+    );
+    let transactions_info = TransactionsInfo::new(
+        Bytes32::default(),
+        Bytes32::default(),
+        G2Element::default(),
+        0,
+        0,
+        Vec::new(),
+    );
+
+    full_block(
+        height,
+        Some(foliage_transaction_block),
+        Some(transactions_info),
+        generator.map(Program::from),
+    )
+}
+
+/// A block at `height` without transactions
+pub fn non_transaction_block(height: u32) -> FullBlock {
+    full_block(height, None, None, None)
+}
+
+fn full_block(
+    height: u32,
+    foliage_transaction_block: Option<FoliageTransactionBlock>,
+    transactions_info: Option<TransactionsInfo>,
+    generator: Option<Program>,
+) -> FullBlock {
+    let is_transaction_block = foliage_transaction_block.is_some();
+    let vdf_info = VDFInfo::new(Bytes32::default(), 0, ClassgroupElement::default());
+    let vdf_proof = VDFProof::new(0, Bytes::default(), false);
+    let proof_of_space = ProofOfSpace::new(
+        Bytes32::default(),
+        None,
+        None,
+        G1Element::default(),
+        32,
+        Bytes::default(),
+    );
+    let reward_chain_block = RewardChainBlock::new(
+        u128::from(height) * 10_000_000_000_000,
+        height,
+        0,
+        0,
+        Bytes32::default(),
+        proof_of_space,
+        None,
+        G2Element::default(),
+        vdf_info.clone(),
+        None,
+        G2Element::default(),
+        vdf_info,
+        None,
+        is_transaction_block,
+    );
+    let foliage = Foliage::new(
+        Bytes32::new([(height % 256) as u8; 32]),
+        Bytes32::default(),
+        FoliageBlockData::new(
+            Bytes32::default(),
+            PoolTarget::new(POOL_PUZZLE_HASH, 0),
+            None,
+            FARMER_PUZZLE_HASH,
+            Bytes32::default(),
+        ),
+        G2Element::default(),
+        is_transaction_block.then(Bytes32::default),
+        is_transaction_block.then(G2Element::default),
+    );
+
+    FullBlock::new(
+        Vec::new(),
+        reward_chain_block,
+        None,
+        vdf_proof.clone(),
+        None,
+        vdf_proof,
+        None,
+        foliage,
+        foliage_transaction_block,
+        transactions_info,
+        generator,
+        Vec::new(),
+    )
+}
@@ -0,0 +1,219 @@
+//! Golden-file regression suite: every block in `tests/corpus` is parsed offline and
+//! compared with the expected `ParsedBlock` JSON stored next to it
+//!
+//! Blocks are `<source>_<description>.bin` files holding a serialized `FullBlock`, and
+//! each block's expected output is `<source>_<description>.json`. The source is
+//! `synthetic` for the blocks written by `examples/build_corpus.rs`, parsed with mainnet
+//! rules, or `mainnet` or `testnet11` for blocks captured from that network with
+//! `examples/import_corpus_block.rs`. After a change that is meant to alter the output,
+//! rewrite the expected files with `UPDATE_CORPUS=1 cargo test --features serde --test
+//! corpus` and review their diff.
+
+use chia_generator_parser::{BlockParser, InMemoryGeneratorCache, Network, ParsedBlock};
+use chia_protocol::FullBlock;
+use chia_traits::Streamable;
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+fn corpus_dir() -> PathBuf {
+    Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/corpus")
+}
+
+fn network(name: &str) -> Network {
+    match name.split('_').next() {
+        Some("synthetic" | "mainnet") => Network::Mainnet,
+        Some("testnet11") => Network::Testnet11,
+        _ => panic!("corpus block {name} doesn't start with synthetic_, mainnet_ or testnet11_"),
+    }
+}
+
+/// Every corpus block as serialized, keyed by file name without the extension
+fn read_corpus() -> BTreeMap<String, Vec<u8>> {
+    let mut blocks = BTreeMap::new();
+    for entry in std::fs::read_dir(corpus_dir()).unwrap() {
+        let path = entry.unwrap().path();
+        if path.extension().is_none_or(|extension| extension != "bin") {
+            continue;
+        }
+
+        let name = path.file_stem().unwrap().to_string_lossy().into_owned();
+        blocks.insert(name, std::fs::read(&path).unwrap());
+    }
+    blocks
+}
+
+/// Every corpus block, parsed with the other blocks of its source so generator refs
+/// between them resolve, keyed by file name without the extension
+fn parse_corpus() -> BTreeMap<String, ParsedBlock> {
+    let mut by_source: BTreeMap<String, Vec<(String, Vec<u8>)>> = BTreeMap::new();
+    for (name, block) in read_corpus() {
+        let source = name.split('_').next().unwrap().to_string();
+        by_source.entry(source).or_default().push((name, block));
+    }
+
+    let mut parsed = BTreeMap::new();
+    for blocks in by_source.values() {
+        let parser = BlockParser::new(network(&blocks[0].0));
+        let block_bytes: Vec<&[u8]> = blocks.iter().map(|(_, block)| block.as_slice()).collect();
+        let results = parser.parse_blocks_batch_from_bytes(&block_bytes);
+
+        for ((name, _), result) in blocks.iter().zip(results) {
+            let block = result.unwrap_or_else(|e| panic!("corpus block {name}: {e}"));
+            parsed.insert(name.clone(), block);
+        }
+    }
+    parsed
+}
+
+/// First line where `actual` and `expected` differ, for the failure message
+fn first_difference(actual: &str, expected: &str) -> String {
+    let mut expected_lines = expected.lines();
+    for (index, actual_line) in actual.lines().enumerate() {
+        match expected_lines.next() {
+            Some(expected_line) if expected_line == actual_line => {}
+            expected_line => {
+                return format!(
+                    "line {}: expected {:?}, got {:?}",
+                    index + 1,
+                    expected_line.unwrap_or_default(),
+                    actual_line
+                )
+            }
+        }
+    }
+    "expected output has extra lines".to_string()
+}
+
+#[test]
+fn test_corpus_matches_expected_output() {
+    let update = std::env::var_os("UPDATE_CORPUS").is_some();
+    let parsed = parse_corpus();
+    assert!(!parsed.is_empty(), "the corpus has no blocks");
+
+    let mut mismatches = Vec::new();
+    for (name, block) in &parsed {
+        let path = corpus_dir().join(format!("{name}.json"));
+        let actual = block.to_json_pretty().unwrap() + "\n";
+
+        if update {
+            std::fs::write(&path, &actual).unwrap();
+            continue;
+        }
+
+        match std::fs::read_to_string(&path) {
+            Ok(expected) if expected == actual => {
+                // The expected output is also an archive that must keep decoding
+                let decoded = ParsedBlock::from_json(&expected).unwrap();
+                assert_eq!(format!("{decoded:?}"), format!("{block:?}"), "{name}");
+            }
+            Ok(expected) => {
+                mismatches.push(format!("{name}: {}", first_difference(&actual, &expected)))
+            }
+            Err(e) => mismatches.push(format!("{name}: no expected output ({e})")),
+        }
+    }
+
+    assert!(
+        mismatches.is_empty(),
+        "parsed blocks differ from the corpus, rerun with UPDATE_CORPUS=1 if intended:\n{}",
+        mismatches.join("\n")
+    );
+}
+
+#[test]
+fn test_corpus_coverage() {
+    let parsed = parse_corpus();
+
+    for (name, block) in &parsed {
+        assert!(
+            block.diagnostics.is_clean(),
+            "corpus block {name} failed to parse: {:?}",
+            block.diagnostics.failures
+        );
+    }
+
+    let blocks: Vec<&ParsedBlock> = parsed.values().collect();
+    let has_layer = |kind: &str| {
+        blocks.iter().any(|block| {
+            block
+                .coin_spends
+                .iter()
+                .any(|spend| spend.puzzle_kind.layers().any(|layer| layer.name() == kind))
+        })
+    };
+
+    assert!(blocks.iter().any(|block| !block.has_transactions_generator));
+    assert!(blocks.iter().any(|block| !block.coin_spends.is_empty()));
+    assert!(blocks
+        .iter()
+        .any(|block| block.coin_creations.iter().any(|coin| coin.ephemeral)));
+    assert!(has_layer("standard_transaction"));
+    assert!(has_layer("cat"));
+    assert!(has_layer("nft_state"));
+    assert!(has_layer("data_layer"));
+    assert!(blocks
+        .iter()
+        .any(|block| !block.data_store_updates.is_empty()));
+    assert!(blocks
+        .iter()
+        .any(|block| !block.data_store_mirrors.is_empty()));
+}
+
+/// Compressed blocks must read their refs: with empty generators in their place, the
+/// generator fails
+#[test]
+fn test_corpus_compressed_blocks_read_refs() {
+    let mut compressed = 0;
+    for (name, block_bytes) in read_corpus() {
+        let block = FullBlock::from_bytes(&block_bytes).unwrap();
+        if block.transactions_generator_ref_list.is_empty() {
+            continue;
+        }
+        compressed += 1;
+
+        let empty_refs = InMemoryGeneratorCache::new();
+        for &height in &block.transactions_generator_ref_list {
+            empty_refs.insert(height, vec![0x80]);
+        }
+        let parsed = BlockParser::new(network(&name))
+            .with_generator_lookup(Arc::new(empty_refs))
+            .parse_full_block(&block)
+            .unwrap();
+        assert!(
+            !parsed.diagnostics.is_clean(),
+            "corpus block {name} parses the same without its refs"
+        );
+    }
+    assert!(compressed > 0, "the corpus has no compressed blocks");
+}
+
+#[test]
+fn test_captured_blocks_verify() {
+    let parsed = parse_corpus();
+
+    // A captured block must match every commitment it makes, which a block that was
+    // built or edited by hand doesn't
+    let mut captured = 0;
+    for (name, bytes) in read_corpus() {
+        if name.starts_with("synthetic_") {
+            continue;
+        }
+        captured += 1;
+
+        let block = FullBlock::from_bytes(&bytes).unwrap();
+        let verification = BlockParser::new(network(&name))
+            .verify_block(&block, &parsed[&name])
+            .unwrap();
+        assert!(
+            verification.is_valid(),
+            "captured block {name} doesn't match its commitments, failed: {:?}, unverifiable: {:?}",
+            verification.failed_checks(),
+            verification.unverifiable_checks()
+        );
+    }
+    assert!(
+        captured > 0,
+        "the corpus has no captured blocks, import them with examples/import_corpus_block.rs"
+    );
+}
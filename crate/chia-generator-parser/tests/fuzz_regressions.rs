@@ -0,0 +1,48 @@
+//! Inputs the fuzz targets in `fuzz/` flagged, minimised and parsed the way the targets
+//! parse them
+//!
+//! Both are the `synthetic_transaction` seed with one byte of the first spend's puzzle
+//! changed, so that it recurses until the allocator runs out of pairs. The run takes
+//! seconds, as it would for consensus, and the block must still parse, with the failure
+//! reported for that spend and its coins listed.
+
+use chia_consensus::validation_error::ErrorCode;
+use chia_generator_parser::{BlockParser, Network, ParseStage, ParsedBlock};
+
+const PAIR_LIMIT_BLOCK: &[u8] = include_bytes!("fixtures/fuzz-parse_full_block-pair-limit.bin");
+const PAIR_LIMIT_BLOCK_HEX: &str =
+    include_str!("fixtures/fuzz-parse_full_block_hex-pair-limit.hex");
+
+fn assert_first_puzzle_fails(parsed: &ParsedBlock) {
+    let failure = &parsed.diagnostics.failures[0];
+    assert_eq!(failure.stage, ParseStage::RunPuzzle);
+    assert_eq!(failure.spend_index, Some(0));
+    assert_eq!(
+        failure.error_code,
+        Some(u32::from(ErrorCode::GeneratorRuntimeError))
+    );
+    assert!(parsed
+        .diagnostics
+        .failures
+        .iter()
+        .any(|failure| failure.stage == ParseStage::RunBlockGenerator));
+    assert_eq!(parsed.coin_removals.len(), 3);
+}
+
+#[test]
+fn test_parse_full_block_pair_limit() {
+    let parser = BlockParser::new(Network::Mainnet);
+    let parsed = parser
+        .parse_full_block_from_bytes(PAIR_LIMIT_BLOCK)
+        .unwrap();
+    assert_first_puzzle_fails(&parsed);
+}
+
+#[test]
+fn test_parse_full_block_hex_pair_limit() {
+    let parser = BlockParser::new(Network::Mainnet);
+    let parsed = parser
+        .parse_full_block_from_hex(PAIR_LIMIT_BLOCK_HEX)
+        .unwrap();
+    assert_first_puzzle_fails(&parsed);
+}
@@ -0,0 +1,21 @@
+//! `protocol::Message::from_bytes` on untrusted wire bytes; decoded messages must
+//! survive a round trip
+
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+
+#[allow(dead_code)]
+#[path = "../../src/protocol.rs"]
+mod protocol;
+
+fuzz_target!(|data: &[u8]| {
+    let Ok(message) = protocol::Message::from_bytes(data) else {
+        return;
+    };
+
+    let decoded = protocol::Message::from_bytes(&message.to_bytes().unwrap()).unwrap();
+    assert_eq!(decoded.msg_type, message.msg_type);
+    assert_eq!(decoded.id, message.id);
+    assert_eq!(decoded.data, message.data);
+});
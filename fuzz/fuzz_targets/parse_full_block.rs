@@ -0,0 +1,12 @@
+//! `BlockParser::parse_full_block_from_bytes` on untrusted block bytes, as received from
+//! peers and `ChiaBlockParser.parseFullBlockFromBytes`
+
+#![no_main]
+
+use chia_generator_parser::{BlockParser, Network};
+use libfuzzer_sys::fuzz_target;
+
+fuzz_target!(|data: &[u8]| {
+    let parser = BlockParser::new(Network::Mainnet);
+    let _ = parser.parse_full_block_from_bytes(data);
+});
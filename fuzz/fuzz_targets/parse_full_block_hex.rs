@@ -0,0 +1,12 @@
+//! `BlockParser::parse_full_block_from_hex` on untrusted strings, as passed to
+//! `ChiaBlockParser.parseFullBlockFromHex`
+
+#![no_main]
+
+use chia_generator_parser::{BlockParser, Network};
+use libfuzzer_sys::fuzz_target;
+
+fuzz_target!(|block_hex: &str| {
+    let parser = BlockParser::new(Network::Mainnet);
+    let _ = parser.parse_full_block_from_hex(block_hex);
+});
@@ -23,9 +23,50 @@ export interface AddressResult {
   count: number
 }
 export interface CoinInfoJs {
+  coinId: string
   parentCoinInfo: string
   puzzleHash: string
   amount: string
+  createdBySpend?: number
+  ephemeral: boolean
+  memos: Array<string>
+  hint?: string
+}
+export interface HintEntryJs {
+  hint: string
+  coinIds: Array<string>
+}
+export interface ConditionJs {
+  opcode: number
+  name: string
+  publicKey?: string
+  message?: string
+  amount?: string
+  announcementId?: string
+  coinId?: string
+  parentId?: string
+  puzzleHash?: string
+  mode?: number
+  counterparty?: Array<string>
+  seconds?: string
+  height?: number
+  args?: string
+}
+export interface PuzzleLayerJs {
+  kind: string
+  syntheticKey?: string
+  assetId?: string
+  launcherId?: string
+  innerPuzzleHash?: string
+  metadata?: string
+  metadataUpdaterPuzzleHash?: string
+  currentOwner?: string
+  transferProgramHash?: string
+  recoveryListHash?: string
+  numVerificationsRequired?: string
+  rootHash?: string
+  merkleRoot?: string
+  modHash?: string
 }
 export interface CoinSpendInfoJs {
   coin: CoinInfoJs
@@ -35,32 +76,151 @@ export interface CoinSpendInfoJs {
   parsingMethod: string
   offset: number
   createdCoins: Array<CoinInfoJs>
+  conditions: Array<ConditionJs>
+  executionCost: string
+  conditionCost: string
+  puzzleLayers: Array<PuzzleLayerJs>
+  puzzleDisassembly?: string
+  solutionDisassembly?: string
+}
+export interface DataStoreUpdateJs {
+  launcherId: string
+  coinId: string
+  newRootHash?: string
+  previousRootHash?: string
+  metadata: string
+  ownerPuzzleHash?: string
+}
+export interface DataStoreMirrorJs {
+  coinId: string
+  launcherId: string
+  amount: string
+  urls: Array<string>
+}
+export interface ParseFailureJs {
+  stage: string
+  errorCode?: number
+  message: string
+  spendIndex?: number
+}
+export interface FarmingInfoJs {
+  plotPublicKey: string
+  poolPublicKey?: string
+  poolContractPuzzleHash?: string
+  farmerRewardPuzzleHash: string
+  poolTargetPuzzleHash: string
+  poolTargetMaxHeight: number
+  signagePointIndex: number
+  plotSize: number
+  plotVersion: number
+  overflow: boolean
 }
 export interface ParsedBlockJs {
   height: number
   weight: string
   headerHash: string
   timestamp?: number
+  farming: FarmingInfoJs
   coinAdditions: Array<CoinInfoJs>
   coinRemovals: Array<CoinInfoJs>
   coinSpends: Array<CoinSpendInfoJs>
   coinCreations: Array<CoinInfoJs>
+  hintIndex: Array<HintEntryJs>
+  cost: string
+  executionCost: string
+  conditionCost: string
+  fees: string
+  reserveFee: string
+  signatureValidation: string
+  diagnostics: Array<ParseFailureJs>
+  dataStoreUpdates: Array<DataStoreUpdateJs>
+  dataStoreMirrors: Array<DataStoreMirrorJs>
   hasTransactionsGenerator: boolean
   generatorSize?: number
 }
+export interface ParsedHeaderBlockJs {
+  height: number
+  weight: string
+  headerHash: string
+  timestamp?: number
+  farming: FarmingInfoJs
+  rewardClaims: Array<CoinInfoJs>
+  transactionsFilter: string
+}
+export interface ParsedSpendBundleJs {
+  coinRemovals: Array<CoinInfoJs>
+  coinSpends: Array<CoinSpendInfoJs>
+  coinCreations: Array<CoinInfoJs>
+  hintIndex: Array<HintEntryJs>
+  cost: string
+  executionCost: string
+  conditionCost: string
+  fees: string
+  reserveFee: string
+  signatureValidation: string
+  diagnostics: Array<ParseFailureJs>
+}
+export interface CommitmentCheckJs {
+  name: string
+  status: string
+  committed?: string
+  computed?: string
+}
+export interface BlockVerificationJs {
+  headerHash: string
+  valid: boolean
+  checks: Array<CommitmentCheckJs>
+}
+export interface BlockParseResultJs {
+  block?: ParsedBlockJs
+  error?: string
+}
 export interface GeneratorBlockInfoJs {
   prevHeaderHash: string
   transactionsGenerator?: string
   transactionsGeneratorRefList: Array<number>
 }
+export interface PuzzleRevealSizeJs {
+  coinId: string
+  puzzleHash: string
+  sizeBytes: number
+}
+export interface PuzzleModUsageJs {
+  modHash: string
+  kind: string
+  spendCount: number
+  revealBytes: string
+}
+export interface GeneratorAnalysisJs {
+  sizeBytes: number
+  expandedSizeBytes: string
+  compressionRatio: number
+  usesBackReferences: boolean
+  generatorRefList: Array<number>
+  entropy: number
+  spendCount: number
+  cost: string
+  executionCost: string
+  conditionCost: string
+  largestPuzzleReveals: Array<PuzzleRevealSizeJs>
+  repeatedPuzzleMods: Array<PuzzleModUsageJs>
+  diagnostics: Array<ParseFailureJs>
+}
 export interface BlockHeightInfoJs {
   height: number
   isTransactionBlock: boolean
 }
+export interface ChiaBlockParserOptions {
+  network?: string
+  validateSignatures?: boolean
+  strict?: boolean
+  disassemblyLimit?: number
+}
 export interface EventTypes {
   blockReceived: string
   peerConnected: string
   peerDisconnected: string
+  dataStoreUpdated: string
 }
 export declare function getEventTypes(): EventTypes
 export interface PeerConnectedEvent {
@@ -74,29 +234,64 @@ export interface PeerDisconnectedEvent {
   port: number
   message?: string
 }
+export interface DataStoreUpdatedEvent {
+  peerId: string
+  height: number
+  headerHash: string
+  launcherId: string
+  coinId: string
+  newRootHash?: string
+  previousRootHash?: string
+  metadata: string
+  ownerPuzzleHash?: string
+}
 export interface BlockReceivedEvent {
   peerId: string
   height: number
   weight: string
   headerHash: string
   timestamp: number
+  timestampExact: boolean
+  farming: FarmingInfoJs
   coinAdditions: Array<CoinRecord>
   coinRemovals: Array<CoinRecord>
   coinSpends: Array<CoinSpend>
   coinCreations: Array<CoinRecord>
+  hintIndex: Array<HintEntryJs>
+  cost: string
+  executionCost: string
+  conditionCost: string
+  fees: string
+  reserveFee: string
+  dataStoreUpdates: Array<DataStoreUpdateJs>
+  dataStoreMirrors: Array<DataStoreMirrorJs>
   hasTransactionsGenerator: boolean
   generatorSize: number
 }
+export interface CoinFilterJs {
+  puzzleHashes?: Array<string>
+  coinIds?: Array<string>
+  hints?: Array<string>
+}
 export interface CoinRecord {
+  coinId: string
   parentCoinInfo: string
   puzzleHash: string
   amount: string
+  createdBySpend?: number
+  ephemeral: boolean
+  memos: Array<string>
+  hint?: string
 }
 export interface CoinSpend {
   coin: CoinRecord
   puzzleReveal: string
   solution: string
   offset: number
+  conditions: Array<ConditionJs>
+  executionCost: string
+  conditionCost: string
+  puzzleLayers: Array<PuzzleLayerJs>
 }
 export interface NewPeakHeightEvent {
   oldPeak?: number
@@ -105,18 +300,98 @@ export interface NewPeakHeightEvent {
 }
 export declare function initTracing(): void
 export declare class ChiaBlockParser {
-  /** Create a new block parser */
-  constructor()
+  /**
+   * Create a new block parser
+   *
+   * `network` is a network ID ("mainnet", "testnet11") or genesis challenge hex.
+   * Defaults to mainnet.
+   *
+   * With `validateSignatures`, each block's aggregate signature is checked and
+   * reported in `signatureValidation`.
+   *
+   * Generator failures are reported in `diagnostics`; with `strict`, parsing throws
+   * instead.
+   *
+   * With `disassemblyLimit`, each spend's puzzle reveal and solution are disassembled
+   * into chialisp-style text of at most that many characters.
+   */
+  constructor(options?: ChiaBlockParserOptions | undefined | null)
+  /**
+   * Register the generator of a block at the given height, so compressed blocks
+   * that reference it can be parsed
+   */
+  cacheGenerator(height: number, generator: Buffer): void
+  /**
+   * Register the generator of a serialized FullBlock, so compressed blocks
+   * that reference it can be parsed
+   */
+  cacheBlockGenerator(blockBytes: Buffer): void
   /** Parse a FullBlock from bytes */
   parseFullBlockFromBytes(blockBytes: Buffer): ParsedBlockJs
   /** Parse a FullBlock from hex string */
   parseFullBlockFromHex(blockHex: string): ParsedBlockJs
+  /**
+   * Parse a FullBlock from bytes and encode it as versioned JSON, for archiving
+   *
+   * The JSON follows the Rust `ParsedBlock` layout rather than `ParsedBlockJS`, wrapped
+   * as `{"format_version": 1, "block": {...}}`.
+   */
+  toJson(blockBytes: Buffer): string
+  /**
+   * Parse a FullBlock from bytes and encode it as versioned CBOR, the compact form of
+   * `toJson`
+   */
+  toBinary(blockBytes: Buffer): Buffer
+  /** Decode a parsed block archived with `toJson` */
+  fromJson(json: string): ParsedBlockJs
+  /** Decode a parsed block archived with `toBinary` */
+  fromBinary(data: Buffer): ParsedBlockJs
+  /** Parse a HeaderBlock from bytes, as returned by the wallet protocol */
+  parseHeaderBlockFromBytes(blockBytes: Buffer): ParsedHeaderBlockJs
+  /** Parse a HeaderBlock from hex string */
+  parseHeaderBlockFromHex(blockHex: string): ParsedHeaderBlockJs
+  /**
+   * Parse a SpendBundle from bytes, such as a mempool item or an offer, into the same
+   * coin data as a block
+   */
+  parseSpendBundleFromBytes(bundleBytes: Buffer): ParsedSpendBundleJs
+  /** Parse a SpendBundle from hex string */
+  parseSpendBundleFromHex(bundleHex: string): ParsedSpendBundleJs
+  /** Parse a FullBlock from bytes and check it against the hashes its foliage commits to */
+  verifyBlockFromBytes(blockBytes: Buffer): BlockVerificationJs
+  /**
+   * Parse many FullBlocks from bytes across a CPU thread pool
+   *
+   * Parsing runs off the event loop, resolving to results in input order. A block that
+   * fails to parse sets `error` on its own result instead of failing the batch.
+   * Generator refs to other blocks of the batch are resolved from the batch itself.
+   */
+  parseBlocksBatch(blocks: Array<Buffer>): Promise<Array<BlockParseResultJs>>
   /** Extract generator from block bytes */
   extractGeneratorFromBlockBytes(blockBytes: Buffer): string | null
   /** Get block height and transaction status from block bytes */
   getHeightAndTxStatusFromBlockBytes(blockBytes: Buffer): BlockHeightInfoJs
   /** Parse block info from block bytes */
   parseBlockInfoFromBytes(blockBytes: Buffer): GeneratorBlockInfoJs
+  /**
+   * Run a serialized generator on its own and analyze its composition
+   *
+   * The generator runs under the network's latest consensus rules, without generator
+   * refs.
+   */
+  analyzeGenerator(generator: Buffer): GeneratorAnalysisJs
+  /**
+   * Analyze the generator of a FullBlock from bytes, or null when it has none
+   *
+   * Generator refs are resolved from the generators registered with
+   * `cacheGenerator` and `cacheBlockGenerator`.
+   */
+  analyzeBlockGeneratorFromBytes(blockBytes: Buffer): GeneratorAnalysisJs | null
+  /**
+   * Disassemble serialized CLVM hex, such as a puzzle reveal or solution, into
+   * chialisp-style text of at most `maxLength` characters (16384 by default)
+   */
+  disassemble(programHex: string, maxLength?: number | undefined | null): string
 }
 export declare class DnsDiscoveryClient {
   /** Create a new DNS discovery client */
@@ -144,6 +419,14 @@ export declare class ChiaBlockListener {
   off(event: string, callback: (...args: any[]) => any): void
   getBlockByHeight(peerId: string, height: number): BlockReceivedEvent
   getBlocksRange(peerId: string, startHeight: number, endHeight: number): Array<BlockReceivedEvent>
+  /**
+   * Only fully parse the spends of watched coins in blocks streamed from peers
+   *
+   * Removals and additions still list every coin, but `coinSpends` only holds spends
+   * of or creating a coin with a listed ID, puzzle hash or hint. Pass `null` to parse
+   * every spend again.
+   */
+  setCoinFilter(filter?: CoinFilterJs | undefined | null): void
 }
 export declare class ChiaPeerPool {
   constructor()
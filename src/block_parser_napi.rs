@@ -1,34 +1,317 @@
+use crate::protocol;
 use chia_generator_parser::{
+    disassemble_bytes,
     parser::BlockParser as RustBlockParser,
-    types::{BlockHeightInfo, CoinInfo, CoinSpendInfo, GeneratorBlockInfo, ParsedBlock},
+    types::{
+        BlockHeightInfo, BlockVerification, CoinInfo, CoinSpendInfo, CommitmentCheck,
+        ConditionInfo, DataStoreMirror, DataStoreUpdate, FarmingInfo, GeneratorAnalysis,
+        GeneratorBlockInfo, ParseFailure, ParsedBlock, ParsedHeaderBlock, ParsedSpendBundle,
+        PuzzleKind, PuzzleModUsage, PuzzleRevealSize,
+    },
+    BlsCache, InMemoryGeneratorCache, Network, DEFAULT_DISASSEMBLY_LIMIT,
 };
-use chia_protocol::FullBlock;
+use chia_protocol::{Bytes32, FullBlock};
 use chia_traits::streamable::Streamable;
 use napi::bindgen_prelude::*;
 use napi_derive::napi;
+use std::collections::BTreeMap;
+use std::sync::Arc;
 use tracing::{debug, info};
 
 // Export CoinInfo for TypeScript
 #[napi(object)]
 #[derive(Clone)]
 pub struct CoinInfoJS {
+    #[napi(js_name = "coinId")]
+    pub coin_id: String,
     #[napi(js_name = "parentCoinInfo")]
     pub parent_coin_info: String,
     #[napi(js_name = "puzzleHash")]
     pub puzzle_hash: String,
     pub amount: String, // Use string for u64 to avoid JS precision issues
+    #[napi(js_name = "createdBySpend")]
+    pub created_by_spend: Option<u32>, // Index into coinSpends
+    pub ephemeral: bool,
+    pub memos: Vec<String>,
+    pub hint: Option<String>,
 }
 
 impl From<&CoinInfo> for CoinInfoJS {
     fn from(coin: &CoinInfo) -> Self {
         Self {
-            parent_coin_info: coin.parent_coin_info.clone(),
-            puzzle_hash: coin.puzzle_hash.clone(),
+            coin_id: hex::encode(coin.coin_id),
+            parent_coin_info: hex::encode(coin.parent_coin_info),
+            puzzle_hash: hex::encode(coin.puzzle_hash),
             amount: coin.amount.to_string(),
+            created_by_spend: coin.created_by_spend,
+            ephemeral: coin.ephemeral,
+            memos: coin.memos.iter().map(hex::encode).collect(),
+            hint: coin.hint.map(hex::encode),
         }
     }
 }
 
+// Export a hint index entry for TypeScript
+#[napi(object)]
+#[derive(Clone)]
+pub struct HintEntryJS {
+    pub hint: String,
+    #[napi(js_name = "coinIds")]
+    pub coin_ids: Vec<String>,
+}
+
+/// Flatten a block's hint index into JS entries
+pub fn hint_entries(hint_index: &BTreeMap<Bytes32, Vec<Bytes32>>) -> Vec<HintEntryJS> {
+    hint_index
+        .iter()
+        .map(|(hint, coin_ids)| HintEntryJS {
+            hint: hex::encode(hint),
+            coin_ids: coin_ids.iter().map(hex::encode).collect(),
+        })
+        .collect()
+}
+
+// Export ConditionInfo for TypeScript; only the fields of the condition's type are set
+#[napi(object)]
+#[derive(Clone, Default)]
+pub struct ConditionJS {
+    pub opcode: u32,
+    pub name: String,
+    #[napi(js_name = "publicKey")]
+    pub public_key: Option<String>,
+    pub message: Option<String>,
+    pub amount: Option<String>, // Use string for u64 to avoid JS precision issues
+    #[napi(js_name = "announcementId")]
+    pub announcement_id: Option<String>,
+    #[napi(js_name = "coinId")]
+    pub coin_id: Option<String>,
+    #[napi(js_name = "parentId")]
+    pub parent_id: Option<String>,
+    #[napi(js_name = "puzzleHash")]
+    pub puzzle_hash: Option<String>,
+    pub mode: Option<u32>,
+    pub counterparty: Option<Vec<String>>,
+    pub seconds: Option<String>,
+    pub height: Option<u32>,
+    pub args: Option<String>,
+}
+
+impl From<&ConditionInfo> for ConditionJS {
+    fn from(condition: &ConditionInfo) -> Self {
+        let base = Self {
+            opcode: condition.opcode().into(),
+            name: condition.name().to_string(),
+            ..Default::default()
+        };
+
+        match condition {
+            ConditionInfo::AggSig {
+                public_key,
+                message,
+                ..
+            } => Self {
+                public_key: Some(hex::encode(public_key.to_bytes())),
+                message: Some(hex::encode(message)),
+                ..base
+            },
+            ConditionInfo::ReserveFee { amount } | ConditionInfo::AssertMyAmount { amount } => {
+                Self {
+                    amount: Some(amount.to_string()),
+                    ..base
+                }
+            }
+            ConditionInfo::CreateCoinAnnouncement { message }
+            | ConditionInfo::CreatePuzzleAnnouncement { message } => Self {
+                message: Some(hex::encode(message)),
+                ..base
+            },
+            ConditionInfo::AssertCoinAnnouncement { announcement_id }
+            | ConditionInfo::AssertPuzzleAnnouncement { announcement_id } => Self {
+                announcement_id: Some(hex::encode(announcement_id)),
+                ..base
+            },
+            ConditionInfo::AssertConcurrentSpend { coin_id }
+            | ConditionInfo::AssertMyCoinId { coin_id } => Self {
+                coin_id: Some(hex::encode(coin_id)),
+                ..base
+            },
+            ConditionInfo::AssertMyParentId { parent_id } => Self {
+                parent_id: Some(hex::encode(parent_id)),
+                ..base
+            },
+            ConditionInfo::AssertConcurrentPuzzle { puzzle_hash }
+            | ConditionInfo::AssertMyPuzzleHash { puzzle_hash } => Self {
+                puzzle_hash: Some(hex::encode(puzzle_hash)),
+                ..base
+            },
+            ConditionInfo::SendMessage {
+                mode,
+                message,
+                counterparty,
+            }
+            | ConditionInfo::ReceiveMessage {
+                mode,
+                message,
+                counterparty,
+            } => Self {
+                mode: Some((*mode).into()),
+                message: Some(hex::encode(message)),
+                counterparty: Some(counterparty.iter().map(hex::encode).collect()),
+                ..base
+            },
+            ConditionInfo::AssertSecondsRelative { seconds }
+            | ConditionInfo::AssertSecondsAbsolute { seconds }
+            | ConditionInfo::AssertBeforeSecondsRelative { seconds }
+            | ConditionInfo::AssertBeforeSecondsAbsolute { seconds }
+            | ConditionInfo::AssertMyBirthSeconds { seconds } => Self {
+                seconds: Some(seconds.to_string()),
+                ..base
+            },
+            ConditionInfo::AssertHeightRelative { height }
+            | ConditionInfo::AssertHeightAbsolute { height }
+            | ConditionInfo::AssertBeforeHeightRelative { height }
+            | ConditionInfo::AssertBeforeHeightAbsolute { height }
+            | ConditionInfo::AssertMyBirthHeight { height } => Self {
+                height: Some(*height),
+                ..base
+            },
+            ConditionInfo::Remark { args } => Self {
+                args: Some(hex::encode(args)),
+                ..base
+            },
+            ConditionInfo::AssertEphemeral => base,
+        }
+    }
+}
+
+// Export one layer of a PuzzleKind for TypeScript; only the fields of the layer's kind are set
+#[napi(object)]
+#[derive(Clone, Default)]
+pub struct PuzzleLayerJS {
+    pub kind: String,
+    #[napi(js_name = "syntheticKey")]
+    pub synthetic_key: Option<String>,
+    #[napi(js_name = "assetId")]
+    pub asset_id: Option<String>,
+    #[napi(js_name = "launcherId")]
+    pub launcher_id: Option<String>,
+    #[napi(js_name = "innerPuzzleHash")]
+    pub inner_puzzle_hash: Option<String>,
+    pub metadata: Option<String>,
+    #[napi(js_name = "metadataUpdaterPuzzleHash")]
+    pub metadata_updater_puzzle_hash: Option<String>,
+    #[napi(js_name = "currentOwner")]
+    pub current_owner: Option<String>,
+    #[napi(js_name = "transferProgramHash")]
+    pub transfer_program_hash: Option<String>,
+    #[napi(js_name = "recoveryListHash")]
+    pub recovery_list_hash: Option<String>,
+    #[napi(js_name = "numVerificationsRequired")]
+    pub num_verifications_required: Option<String>,
+    #[napi(js_name = "rootHash")]
+    pub root_hash: Option<String>,
+    #[napi(js_name = "merkleRoot")]
+    pub merkle_root: Option<String>,
+    #[napi(js_name = "modHash")]
+    pub mod_hash: Option<String>,
+}
+
+impl From<&PuzzleKind> for PuzzleLayerJS {
+    fn from(kind: &PuzzleKind) -> Self {
+        let base = Self {
+            kind: kind.name().to_string(),
+            ..Default::default()
+        };
+
+        match kind {
+            PuzzleKind::StandardTransaction { synthetic_key } => Self {
+                synthetic_key: Some(hex::encode(synthetic_key.to_bytes())),
+                ..base
+            },
+            PuzzleKind::Cat {
+                asset_id,
+                inner_puzzle_hash,
+                ..
+            } => Self {
+                asset_id: Some(hex::encode(asset_id)),
+                inner_puzzle_hash: Some(hex::encode(inner_puzzle_hash)),
+                ..base
+            },
+            PuzzleKind::Singleton {
+                launcher_id,
+                inner_puzzle_hash,
+                ..
+            } => Self {
+                launcher_id: Some(hex::encode(launcher_id)),
+                inner_puzzle_hash: Some(hex::encode(inner_puzzle_hash)),
+                ..base
+            },
+            PuzzleKind::NftState {
+                metadata,
+                metadata_updater_puzzle_hash,
+                inner_puzzle_hash,
+                ..
+            } => Self {
+                metadata: Some(hex::encode(metadata)),
+                metadata_updater_puzzle_hash: Some(hex::encode(metadata_updater_puzzle_hash)),
+                inner_puzzle_hash: Some(hex::encode(inner_puzzle_hash)),
+                ..base
+            },
+            PuzzleKind::NftOwnership {
+                current_owner,
+                transfer_program_hash,
+                inner_puzzle_hash,
+                ..
+            } => Self {
+                current_owner: current_owner.map(hex::encode),
+                transfer_program_hash: Some(hex::encode(transfer_program_hash)),
+                inner_puzzle_hash: Some(hex::encode(inner_puzzle_hash)),
+                ..base
+            },
+            PuzzleKind::Did {
+                launcher_id,
+                recovery_list_hash,
+                num_verifications_required,
+                metadata,
+                inner_puzzle_hash,
+                ..
+            } => Self {
+                launcher_id: Some(hex::encode(launcher_id)),
+                recovery_list_hash: recovery_list_hash.map(hex::encode),
+                num_verifications_required: Some(num_verifications_required.to_string()),
+                metadata: Some(hex::encode(metadata)),
+                inner_puzzle_hash: Some(hex::encode(inner_puzzle_hash)),
+                ..base
+            },
+            PuzzleKind::DataLayer {
+                root_hash,
+                metadata,
+                inner_puzzle_hash,
+                ..
+            } => Self {
+                root_hash: root_hash.map(hex::encode),
+                metadata: Some(hex::encode(metadata)),
+                inner_puzzle_hash: Some(hex::encode(inner_puzzle_hash)),
+                ..base
+            },
+            PuzzleKind::Clawback { merkle_root } => Self {
+                merkle_root: Some(hex::encode(merkle_root)),
+                ..base
+            },
+            PuzzleKind::Unknown { mod_hash } => Self {
+                mod_hash: Some(hex::encode(mod_hash)),
+                ..base
+            },
+            PuzzleKind::SingletonLauncher | PuzzleKind::SettlementPayments => base,
+        }
+    }
+}
+
+/// Flatten a puzzle kind into its layers, outermost first
+pub fn puzzle_layers(kind: &PuzzleKind) -> Vec<PuzzleLayerJS> {
+    kind.layers().map(|layer| layer.into()).collect()
+}
+
 // Export CoinSpendInfo for TypeScript
 #[napi(object)]
 #[derive(Clone)]
@@ -44,18 +327,155 @@ pub struct CoinSpendInfoJS {
     pub offset: u32,
     #[napi(js_name = "createdCoins")]
     pub created_coins: Vec<CoinInfoJS>,
+    pub conditions: Vec<ConditionJS>,
+    #[napi(js_name = "executionCost")]
+    pub execution_cost: String,
+    #[napi(js_name = "conditionCost")]
+    pub condition_cost: String,
+    #[napi(js_name = "puzzleLayers")]
+    pub puzzle_layers: Vec<PuzzleLayerJS>,
+    #[napi(js_name = "puzzleDisassembly")]
+    pub puzzle_disassembly: Option<String>,
+    #[napi(js_name = "solutionDisassembly")]
+    pub solution_disassembly: Option<String>,
 }
 
 impl From<&CoinSpendInfo> for CoinSpendInfoJS {
     fn from(spend: &CoinSpendInfo) -> Self {
         Self {
             coin: (&spend.coin).into(),
-            puzzle_reveal: spend.puzzle_reveal.clone(),
-            solution: spend.solution.clone(),
+            puzzle_reveal: hex::encode(&spend.puzzle_reveal),
+            solution: hex::encode(&spend.solution),
             real_data: spend.real_data,
             parsing_method: spend.parsing_method.clone(),
             offset: spend.offset,
             created_coins: spend.created_coins.iter().map(|c| c.into()).collect(),
+            conditions: spend.conditions.iter().map(|c| c.into()).collect(),
+            execution_cost: spend.execution_cost.to_string(),
+            condition_cost: spend.condition_cost.to_string(),
+            puzzle_layers: puzzle_layers(&spend.puzzle_kind),
+            puzzle_disassembly: spend.puzzle_disassembly.clone(),
+            solution_disassembly: spend.solution_disassembly.clone(),
+        }
+    }
+}
+
+// Export DataStoreUpdate for TypeScript
+#[napi(object)]
+#[derive(Clone)]
+pub struct DataStoreUpdateJS {
+    #[napi(js_name = "launcherId")]
+    pub launcher_id: String,
+    #[napi(js_name = "coinId")]
+    pub coin_id: String,
+    #[napi(js_name = "newRootHash")]
+    pub new_root_hash: Option<String>,
+    #[napi(js_name = "previousRootHash")]
+    pub previous_root_hash: Option<String>, // Unset when the store is launched
+    pub metadata: String,
+    #[napi(js_name = "ownerPuzzleHash")]
+    pub owner_puzzle_hash: Option<String>, // Unset when the store is melted
+}
+
+impl From<&DataStoreUpdate> for DataStoreUpdateJS {
+    fn from(update: &DataStoreUpdate) -> Self {
+        Self {
+            launcher_id: hex::encode(update.launcher_id),
+            coin_id: hex::encode(update.coin_id),
+            new_root_hash: update.new_root_hash.map(hex::encode),
+            previous_root_hash: update.previous_root_hash.map(hex::encode),
+            metadata: hex::encode(&update.metadata),
+            owner_puzzle_hash: update.owner_puzzle_hash.map(hex::encode),
+        }
+    }
+}
+
+// Export DataStoreMirror for TypeScript
+#[napi(object)]
+#[derive(Clone)]
+pub struct DataStoreMirrorJS {
+    #[napi(js_name = "coinId")]
+    pub coin_id: String,
+    #[napi(js_name = "launcherId")]
+    pub launcher_id: String,
+    pub amount: String, // Use string for u64 to avoid JS precision issues
+    pub urls: Vec<String>,
+}
+
+impl From<&DataStoreMirror> for DataStoreMirrorJS {
+    fn from(mirror: &DataStoreMirror) -> Self {
+        Self {
+            coin_id: hex::encode(mirror.coin_id),
+            launcher_id: hex::encode(mirror.launcher_id),
+            amount: mirror.amount.to_string(),
+            urls: mirror.urls.clone(),
+        }
+    }
+}
+
+// Export ParseFailure for TypeScript
+#[napi(object)]
+#[derive(Clone)]
+pub struct ParseFailureJS {
+    pub stage: String,
+    #[napi(js_name = "errorCode")]
+    pub error_code: Option<u32>,
+    pub message: String,
+    #[napi(js_name = "spendIndex")]
+    pub spend_index: Option<u32>,
+}
+
+impl From<&ParseFailure> for ParseFailureJS {
+    fn from(failure: &ParseFailure) -> Self {
+        Self {
+            stage: failure.stage.name().to_string(),
+            error_code: failure.error_code,
+            message: failure.message.clone(),
+            spend_index: failure.spend_index,
+        }
+    }
+}
+
+// Export FarmingInfo for TypeScript
+#[napi(object)]
+#[derive(Clone)]
+pub struct FarmingInfoJS {
+    #[napi(js_name = "plotPublicKey")]
+    pub plot_public_key: String,
+    #[napi(js_name = "poolPublicKey")]
+    pub pool_public_key: Option<String>, // OG plots only
+    #[napi(js_name = "poolContractPuzzleHash")]
+    pub pool_contract_puzzle_hash: Option<String>, // Portable (plot NFT) plots only
+    #[napi(js_name = "farmerRewardPuzzleHash")]
+    pub farmer_reward_puzzle_hash: String,
+    #[napi(js_name = "poolTargetPuzzleHash")]
+    pub pool_target_puzzle_hash: String,
+    #[napi(js_name = "poolTargetMaxHeight")]
+    pub pool_target_max_height: u32, // 0 when the target doesn't expire
+    #[napi(js_name = "signagePointIndex")]
+    pub signage_point_index: u32,
+    #[napi(js_name = "plotSize")]
+    pub plot_size: u32, // k
+    #[napi(js_name = "plotVersion")]
+    pub plot_version: u32,
+    pub overflow: bool,
+}
+
+impl From<&FarmingInfo> for FarmingInfoJS {
+    fn from(farming: &FarmingInfo) -> Self {
+        Self {
+            plot_public_key: hex::encode(farming.plot_public_key.to_bytes()),
+            pool_public_key: farming
+                .pool_public_key
+                .map(|public_key| hex::encode(public_key.to_bytes())),
+            pool_contract_puzzle_hash: farming.pool_contract_puzzle_hash.map(hex::encode),
+            farmer_reward_puzzle_hash: hex::encode(farming.farmer_reward_puzzle_hash),
+            pool_target_puzzle_hash: hex::encode(farming.pool_target.puzzle_hash),
+            pool_target_max_height: farming.pool_target.max_height,
+            signage_point_index: farming.signage_point_index.into(),
+            plot_size: farming.plot_size.into(),
+            plot_version: farming.plot_version.into(),
+            overflow: farming.overflow,
         }
     }
 }
@@ -69,6 +489,7 @@ pub struct ParsedBlockJS {
     #[napi(js_name = "headerHash")]
     pub header_hash: String,
     pub timestamp: Option<u32>,
+    pub farming: FarmingInfoJS,
     #[napi(js_name = "coinAdditions")]
     pub coin_additions: Vec<CoinInfoJS>,
     #[napi(js_name = "coinRemovals")]
@@ -77,6 +498,23 @@ pub struct ParsedBlockJS {
     pub coin_spends: Vec<CoinSpendInfoJS>,
     #[napi(js_name = "coinCreations")]
     pub coin_creations: Vec<CoinInfoJS>,
+    #[napi(js_name = "hintIndex")]
+    pub hint_index: Vec<HintEntryJS>,
+    pub cost: String, // Use strings for u64 costs and fees to avoid JS precision issues
+    #[napi(js_name = "executionCost")]
+    pub execution_cost: String,
+    #[napi(js_name = "conditionCost")]
+    pub condition_cost: String,
+    pub fees: String,
+    #[napi(js_name = "reserveFee")]
+    pub reserve_fee: String,
+    #[napi(js_name = "signatureValidation")]
+    pub signature_validation: String, // "not_validated", "valid" or "invalid"
+    pub diagnostics: Vec<ParseFailureJS>,
+    #[napi(js_name = "dataStoreUpdates")]
+    pub data_store_updates: Vec<DataStoreUpdateJS>,
+    #[napi(js_name = "dataStoreMirrors")]
+    pub data_store_mirrors: Vec<DataStoreMirrorJS>,
     #[napi(js_name = "hasTransactionsGenerator")]
     pub has_transactions_generator: bool,
     #[napi(js_name = "generatorSize")]
@@ -87,19 +525,169 @@ impl From<&ParsedBlock> for ParsedBlockJS {
     fn from(block: &ParsedBlock) -> Self {
         Self {
             height: block.height,
-            weight: block.weight.clone(),
-            header_hash: block.header_hash.clone(),
+            weight: block.weight.to_string(),
+            header_hash: hex::encode(block.header_hash),
             timestamp: block.timestamp,
+            farming: (&block.farming).into(),
             coin_additions: block.coin_additions.iter().map(|c| c.into()).collect(),
             coin_removals: block.coin_removals.iter().map(|c| c.into()).collect(),
             coin_spends: block.coin_spends.iter().map(|s| s.into()).collect(),
             coin_creations: block.coin_creations.iter().map(|c| c.into()).collect(),
+            hint_index: hint_entries(&block.hint_index),
+            cost: block.cost.to_string(),
+            execution_cost: block.execution_cost.to_string(),
+            condition_cost: block.condition_cost.to_string(),
+            fees: block.fees.to_string(),
+            reserve_fee: block.reserve_fee.to_string(),
+            signature_validation: block.signature_validation.name().to_string(),
+            diagnostics: block
+                .diagnostics
+                .failures
+                .iter()
+                .map(|f| f.into())
+                .collect(),
+            data_store_updates: block.data_store_updates.iter().map(|u| u.into()).collect(),
+            data_store_mirrors: block.data_store_mirrors.iter().map(|m| m.into()).collect(),
             has_transactions_generator: block.has_transactions_generator,
             generator_size: block.generator_size,
         }
     }
 }
 
+// Export ParsedSpendBundle for TypeScript
+#[napi(object)]
+#[derive(Clone)]
+pub struct ParsedSpendBundleJS {
+    #[napi(js_name = "coinRemovals")]
+    pub coin_removals: Vec<CoinInfoJS>,
+    #[napi(js_name = "coinSpends")]
+    pub coin_spends: Vec<CoinSpendInfoJS>,
+    #[napi(js_name = "coinCreations")]
+    pub coin_creations: Vec<CoinInfoJS>,
+    #[napi(js_name = "hintIndex")]
+    pub hint_index: Vec<HintEntryJS>,
+    pub cost: String,
+    #[napi(js_name = "executionCost")]
+    pub execution_cost: String,
+    #[napi(js_name = "conditionCost")]
+    pub condition_cost: String,
+    pub fees: String,
+    #[napi(js_name = "reserveFee")]
+    pub reserve_fee: String,
+    #[napi(js_name = "signatureValidation")]
+    pub signature_validation: String, // "not_validated", "valid" or "invalid"
+    pub diagnostics: Vec<ParseFailureJS>,
+}
+
+impl From<&ParsedSpendBundle> for ParsedSpendBundleJS {
+    fn from(bundle: &ParsedSpendBundle) -> Self {
+        Self {
+            coin_removals: bundle.coin_removals.iter().map(|c| c.into()).collect(),
+            coin_spends: bundle.coin_spends.iter().map(|s| s.into()).collect(),
+            coin_creations: bundle.coin_creations.iter().map(|c| c.into()).collect(),
+            hint_index: hint_entries(&bundle.hint_index),
+            cost: bundle.cost.to_string(),
+            execution_cost: bundle.execution_cost.to_string(),
+            condition_cost: bundle.condition_cost.to_string(),
+            fees: bundle.fees.to_string(),
+            reserve_fee: bundle.reserve_fee.to_string(),
+            signature_validation: bundle.signature_validation.name().to_string(),
+            diagnostics: bundle
+                .diagnostics
+                .failures
+                .iter()
+                .map(|f| f.into())
+                .collect(),
+        }
+    }
+}
+
+// Export ParsedHeaderBlock for TypeScript
+#[napi(object)]
+#[derive(Clone)]
+pub struct ParsedHeaderBlockJS {
+    pub height: u32,
+    pub weight: String,
+    #[napi(js_name = "headerHash")]
+    pub header_hash: String,
+    pub timestamp: Option<u32>,
+    pub farming: FarmingInfoJS,
+    #[napi(js_name = "rewardClaims")]
+    pub reward_claims: Vec<CoinInfoJS>,
+    #[napi(js_name = "transactionsFilter")]
+    pub transactions_filter: String, // Hex-encoded bytes
+}
+
+impl From<&ParsedHeaderBlock> for ParsedHeaderBlockJS {
+    fn from(block: &ParsedHeaderBlock) -> Self {
+        Self {
+            height: block.height,
+            weight: block.weight.to_string(),
+            header_hash: hex::encode(block.header_hash),
+            timestamp: block.timestamp,
+            farming: (&block.farming).into(),
+            reward_claims: block.reward_claims.iter().map(|c| c.into()).collect(),
+            transactions_filter: hex::encode(&block.transactions_filter),
+        }
+    }
+}
+
+// Export one commitment check of a BlockVerification for TypeScript
+#[napi(object)]
+#[derive(Clone)]
+pub struct CommitmentCheckJS {
+    pub name: String,   // e.g. "additions_root"
+    pub status: String, // "not_checked", "unverifiable", "valid" or "invalid"
+    pub committed: Option<String>,
+    pub computed: Option<String>,
+}
+
+// Export BlockVerification for TypeScript
+#[napi(object)]
+#[derive(Clone)]
+pub struct BlockVerificationJS {
+    #[napi(js_name = "headerHash")]
+    pub header_hash: String,
+    pub valid: bool,
+    pub checks: Vec<CommitmentCheckJS>,
+}
+
+impl From<&BlockVerification> for BlockVerificationJS {
+    fn from(verification: &BlockVerification) -> Self {
+        Self {
+            header_hash: hex::encode(verification.header_hash),
+            valid: verification.is_valid(),
+            checks: verification
+                .checks()
+                .into_iter()
+                .map(|(name, check)| {
+                    let (committed, computed) = match check {
+                        CommitmentCheck::Invalid {
+                            committed,
+                            computed,
+                        } => (committed.map(hex::encode), computed.map(hex::encode)),
+                        _ => (None, None),
+                    };
+                    CommitmentCheckJS {
+                        name: name.to_string(),
+                        status: check.name().to_string(),
+                        committed,
+                        computed,
+                    }
+                })
+                .collect(),
+        }
+    }
+}
+
+// Export one result of a batch parse for TypeScript; exactly one of block and error is set
+#[napi(object)]
+#[derive(Clone)]
+pub struct BlockParseResultJS {
+    pub block: Option<ParsedBlockJS>,
+    pub error: Option<String>,
+}
+
 // Export GeneratorBlockInfo for TypeScript
 #[napi(object)]
 #[derive(Clone)]
@@ -122,6 +710,114 @@ impl From<&GeneratorBlockInfo> for GeneratorBlockInfoJS {
     }
 }
 
+// Export PuzzleRevealSize for TypeScript
+#[napi(object)]
+#[derive(Clone)]
+pub struct PuzzleRevealSizeJS {
+    #[napi(js_name = "coinId")]
+    pub coin_id: String,
+    #[napi(js_name = "puzzleHash")]
+    pub puzzle_hash: String,
+    #[napi(js_name = "sizeBytes")]
+    pub size_bytes: u32,
+}
+
+impl From<&PuzzleRevealSize> for PuzzleRevealSizeJS {
+    fn from(reveal: &PuzzleRevealSize) -> Self {
+        Self {
+            coin_id: hex::encode(reveal.coin_id),
+            puzzle_hash: hex::encode(reveal.puzzle_hash),
+            size_bytes: reveal.size_bytes,
+        }
+    }
+}
+
+// Export PuzzleModUsage for TypeScript
+#[napi(object)]
+#[derive(Clone)]
+pub struct PuzzleModUsageJS {
+    #[napi(js_name = "modHash")]
+    pub mod_hash: String,
+    pub kind: String,
+    #[napi(js_name = "spendCount")]
+    pub spend_count: u32,
+    #[napi(js_name = "revealBytes")]
+    pub reveal_bytes: String, // u64 as string
+}
+
+impl From<&PuzzleModUsage> for PuzzleModUsageJS {
+    fn from(usage: &PuzzleModUsage) -> Self {
+        Self {
+            mod_hash: hex::encode(usage.mod_hash),
+            kind: usage.kind.clone(),
+            spend_count: usage.spend_count,
+            reveal_bytes: usage.reveal_bytes.to_string(),
+        }
+    }
+}
+
+// Export GeneratorAnalysis for TypeScript
+#[napi(object)]
+#[derive(Clone)]
+pub struct GeneratorAnalysisJS {
+    #[napi(js_name = "sizeBytes")]
+    pub size_bytes: u32,
+    #[napi(js_name = "expandedSizeBytes")]
+    pub expanded_size_bytes: String, // u64 as string
+    #[napi(js_name = "compressionRatio")]
+    pub compression_ratio: f64,
+    #[napi(js_name = "usesBackReferences")]
+    pub uses_back_references: bool,
+    #[napi(js_name = "generatorRefList")]
+    pub generator_ref_list: Vec<u32>,
+    pub entropy: f64,
+    #[napi(js_name = "spendCount")]
+    pub spend_count: u32,
+    pub cost: String, // u64 as string
+    #[napi(js_name = "executionCost")]
+    pub execution_cost: String, // u64 as string
+    #[napi(js_name = "conditionCost")]
+    pub condition_cost: String, // u64 as string
+    #[napi(js_name = "largestPuzzleReveals")]
+    pub largest_puzzle_reveals: Vec<PuzzleRevealSizeJS>,
+    #[napi(js_name = "repeatedPuzzleMods")]
+    pub repeated_puzzle_mods: Vec<PuzzleModUsageJS>,
+    pub diagnostics: Vec<ParseFailureJS>,
+}
+
+impl From<&GeneratorAnalysis> for GeneratorAnalysisJS {
+    fn from(analysis: &GeneratorAnalysis) -> Self {
+        Self {
+            size_bytes: analysis.size_bytes,
+            expanded_size_bytes: analysis.expanded_size_bytes.to_string(),
+            compression_ratio: analysis.compression_ratio(),
+            uses_back_references: analysis.uses_back_references,
+            generator_ref_list: analysis.generator_ref_list.clone(),
+            entropy: analysis.entropy,
+            spend_count: analysis.spend_count,
+            cost: analysis.cost.to_string(),
+            execution_cost: analysis.execution_cost.to_string(),
+            condition_cost: analysis.condition_cost.to_string(),
+            largest_puzzle_reveals: analysis
+                .largest_puzzle_reveals
+                .iter()
+                .map(|reveal| reveal.into())
+                .collect(),
+            repeated_puzzle_mods: analysis
+                .repeated_puzzle_mods
+                .iter()
+                .map(|usage| usage.into())
+                .collect(),
+            diagnostics: analysis
+                .diagnostics
+                .failures
+                .iter()
+                .map(|failure| failure.into())
+                .collect(),
+        }
+    }
+}
+
 // Export BlockHeightInfo for TypeScript
 #[napi(object)]
 #[derive(Clone)]
@@ -140,26 +836,139 @@ impl From<&BlockHeightInfo> for BlockHeightInfoJS {
     }
 }
 
+/// Batch of serialized blocks parsed on the libuv thread pool for `parseBlocksBatch`
+pub struct ParseBlocksBatchTask {
+    parser: RustBlockParser,
+    blocks: Vec<Buffer>,
+}
+
+impl Task for ParseBlocksBatchTask {
+    type Output = Vec<BlockParseResultJS>;
+    type JsValue = Vec<BlockParseResultJS>;
+
+    fn compute(&mut self) -> Result<Self::Output> {
+        let block_bytes: Vec<&[u8]> = self.blocks.iter().map(|block| block.as_ref()).collect();
+        Ok(self
+            .parser
+            .parse_blocks_batch_from_bytes(&block_bytes)
+            .iter()
+            .map(|result| match result {
+                Ok(parsed_block) => BlockParseResultJS {
+                    block: Some(parsed_block.into()),
+                    error: None,
+                },
+                Err(e) => BlockParseResultJS {
+                    block: None,
+                    error: Some(format!("Parse error: {e}")),
+                },
+            })
+            .collect())
+    }
+
+    fn resolve(&mut self, _env: Env, output: Self::Output) -> Result<Self::JsValue> {
+        Ok(output)
+    }
+}
+
+// Settings for a ChiaBlockParser, all optional
+#[napi(object)]
+#[derive(Clone, Default)]
+pub struct ChiaBlockParserOptions {
+    pub network: Option<String>,
+    #[napi(js_name = "validateSignatures")]
+    pub validate_signatures: Option<bool>,
+    pub strict: Option<bool>,
+    #[napi(js_name = "disassemblyLimit")]
+    pub disassembly_limit: Option<u32>,
+}
+
 #[napi]
 pub struct ChiaBlockParser {
     parser: RustBlockParser,
+    generator_cache: Arc<InMemoryGeneratorCache>,
+}
+
+impl ChiaBlockParser {
+    /// A parser for `network` with the remaining `options`
+    fn with_network(network: Network, options: ChiaBlockParserOptions) -> Self {
+        let generator_cache = Arc::new(InMemoryGeneratorCache::new());
+        let mut parser = RustBlockParser::new(network)
+            .with_generator_lookup(generator_cache.clone())
+            .with_strict_mode(options.strict.unwrap_or(false));
+        if options.validate_signatures.unwrap_or(false) {
+            parser = parser.with_signature_validation(Arc::new(BlsCache::default()));
+        }
+        if let Some(max_len) = options.disassembly_limit {
+            parser = parser.with_disassembly(max_len as usize);
+        }
+
+        Self {
+            parser,
+            generator_cache,
+        }
+    }
 }
 
 impl Default for ChiaBlockParser {
     fn default() -> Self {
-        Self::new()
+        Self::with_network(Network::default(), ChiaBlockParserOptions::default())
     }
 }
 
 #[napi]
 impl ChiaBlockParser {
     /// Create a new block parser
+    ///
+    /// `network` is a network ID ("mainnet", "testnet11") or genesis challenge hex.
+    /// Defaults to mainnet; any other network throws.
+    ///
+    /// With `validateSignatures`, each block's aggregate signature is checked and
+    /// reported in `signatureValidation`.
+    ///
+    /// Generator failures are reported in `diagnostics`; with `strict`, parsing throws
+    /// instead.
+    ///
+    /// With `disassemblyLimit`, each spend's puzzle reveal and solution are disassembled
+    /// into chialisp-style text of at most that many characters.
     #[napi(constructor)]
-    pub fn new() -> Self {
+    pub fn new(options: Option<ChiaBlockParserOptions>) -> Result<Self> {
         info!("Creating new ChiaBlockParser");
-        Self {
-            parser: RustBlockParser::new(),
-        }
+        let options = options.unwrap_or_default();
+        let network = match options.network.as_deref() {
+            Some(network_id) => protocol::network_for_id(network_id).ok_or_else(|| {
+                Error::new(Status::InvalidArg, format!("Unknown network: {network_id}"))
+            })?,
+            None => Network::default(),
+        };
+
+        Ok(Self::with_network(network, options))
+    }
+
+    /// Register the generator of a block at the given height, so compressed blocks
+    /// that reference it can be parsed
+    #[napi]
+    pub fn cache_generator(&self, height: u32, generator: Buffer) {
+        debug!(
+            "Caching {} byte generator for height {}",
+            generator.len(),
+            height
+        );
+        self.generator_cache.insert(height, generator.to_vec());
+    }
+
+    /// Register the generator of a serialized FullBlock, so compressed blocks
+    /// that reference it can be parsed
+    #[napi]
+    pub fn cache_block_generator(&self, block_bytes: Buffer) -> Result<()> {
+        let block = FullBlock::from_bytes(&block_bytes).map_err(|e| {
+            Error::new(
+                Status::InvalidArg,
+                format!("Failed to deserialize FullBlock: {e}"),
+            )
+        })?;
+
+        self.generator_cache.insert_block(&block);
+        Ok(())
     }
 
     /// Parse a FullBlock from bytes
@@ -194,6 +1003,162 @@ impl ChiaBlockParser {
         Ok((&parsed_block).into())
     }
 
+    /// Parse a FullBlock from bytes and encode it as versioned JSON, for archiving
+    ///
+    /// The JSON follows the Rust `ParsedBlock` layout rather than `ParsedBlockJS`, wrapped
+    /// as `{"format_version": 1, "block": {...}}`.
+    #[napi]
+    pub fn to_json(&self, block_bytes: Buffer) -> Result<String> {
+        let parsed_block = self
+            .parser
+            .parse_full_block_from_bytes(&block_bytes)
+            .map_err(|e| Error::new(Status::GenericFailure, format!("Parse error: {e}")))?;
+
+        parsed_block
+            .to_json()
+            .map_err(|e| Error::new(Status::GenericFailure, format!("Encode error: {e}")))
+    }
+
+    /// Parse a FullBlock from bytes and encode it as versioned CBOR, the compact form of
+    /// `toJson`
+    #[napi]
+    pub fn to_binary(&self, block_bytes: Buffer) -> Result<Buffer> {
+        let parsed_block = self
+            .parser
+            .parse_full_block_from_bytes(&block_bytes)
+            .map_err(|e| Error::new(Status::GenericFailure, format!("Parse error: {e}")))?;
+
+        let bytes = parsed_block
+            .to_binary()
+            .map_err(|e| Error::new(Status::GenericFailure, format!("Encode error: {e}")))?;
+        Ok(bytes.into())
+    }
+
+    /// Decode a parsed block archived with `toJson`
+    #[napi]
+    pub fn from_json(&self, json: String) -> Result<ParsedBlockJS> {
+        let parsed_block = ParsedBlock::from_json(&json)
+            .map_err(|e| Error::new(Status::InvalidArg, format!("Decode error: {e}")))?;
+
+        Ok((&parsed_block).into())
+    }
+
+    /// Decode a parsed block archived with `toBinary`
+    #[napi]
+    pub fn from_binary(&self, data: Buffer) -> Result<ParsedBlockJS> {
+        let parsed_block = ParsedBlock::from_binary(&data)
+            .map_err(|e| Error::new(Status::InvalidArg, format!("Decode error: {e}")))?;
+
+        Ok((&parsed_block).into())
+    }
+
+    /// Parse a HeaderBlock from bytes, as returned by the wallet protocol
+    #[napi]
+    pub fn parse_header_block_from_bytes(
+        &self,
+        block_bytes: Buffer,
+    ) -> Result<ParsedHeaderBlockJS> {
+        debug!("Parsing HeaderBlock from {} bytes", block_bytes.len());
+
+        let parsed_block = self
+            .parser
+            .parse_header_block_from_bytes(&block_bytes)
+            .map_err(|e| Error::new(Status::GenericFailure, format!("Parse error: {e}")))?;
+
+        Ok((&parsed_block).into())
+    }
+
+    /// Parse a HeaderBlock from hex string
+    #[napi]
+    pub fn parse_header_block_from_hex(&self, block_hex: String) -> Result<ParsedHeaderBlockJS> {
+        debug!(
+            "Parsing HeaderBlock from hex string of length {}",
+            block_hex.len()
+        );
+
+        let block_bytes = hex::decode(&block_hex)
+            .map_err(|e| Error::new(Status::InvalidArg, format!("Hex decode error: {e}")))?;
+
+        let parsed_block = self
+            .parser
+            .parse_header_block_from_bytes(&block_bytes)
+            .map_err(|e| Error::new(Status::GenericFailure, format!("Parse error: {e}")))?;
+
+        Ok((&parsed_block).into())
+    }
+
+    /// Parse a SpendBundle from bytes, such as a mempool item or an offer, into the same
+    /// coin data as a block
+    #[napi]
+    pub fn parse_spend_bundle_from_bytes(
+        &self,
+        bundle_bytes: Buffer,
+    ) -> Result<ParsedSpendBundleJS> {
+        debug!("Parsing SpendBundle from {} bytes", bundle_bytes.len());
+
+        let parsed_bundle = self
+            .parser
+            .parse_spend_bundle_from_bytes(&bundle_bytes)
+            .map_err(|e| Error::new(Status::GenericFailure, format!("Parse error: {e}")))?;
+
+        Ok((&parsed_bundle).into())
+    }
+
+    /// Parse a SpendBundle from hex string
+    #[napi]
+    pub fn parse_spend_bundle_from_hex(&self, bundle_hex: String) -> Result<ParsedSpendBundleJS> {
+        debug!(
+            "Parsing SpendBundle from hex string of length {}",
+            bundle_hex.len()
+        );
+
+        let bundle_bytes = hex::decode(&bundle_hex)
+            .map_err(|e| Error::new(Status::InvalidArg, format!("Hex decode error: {e}")))?;
+
+        let parsed_bundle = self
+            .parser
+            .parse_spend_bundle_from_bytes(&bundle_bytes)
+            .map_err(|e| Error::new(Status::GenericFailure, format!("Parse error: {e}")))?;
+
+        Ok((&parsed_bundle).into())
+    }
+
+    /// Parse a FullBlock from bytes and check it against the hashes its foliage commits to
+    #[napi]
+    pub fn verify_block_from_bytes(&self, block_bytes: Buffer) -> Result<BlockVerificationJS> {
+        debug!("Verifying FullBlock from {} bytes", block_bytes.len());
+
+        let block = FullBlock::from_bytes(&block_bytes).map_err(|e| {
+            Error::new(
+                Status::InvalidArg,
+                format!("Failed to deserialize FullBlock: {e}"),
+            )
+        })?;
+
+        let verification = self
+            .parser
+            .parse_full_block(&block)
+            .and_then(|parsed_block| self.parser.verify_block(&block, &parsed_block))
+            .map_err(|e| Error::new(Status::GenericFailure, format!("Verification error: {e}")))?;
+
+        Ok((&verification).into())
+    }
+
+    /// Parse many FullBlocks from bytes across a CPU thread pool
+    ///
+    /// Parsing runs off the event loop, resolving to results in input order. A block that
+    /// fails to parse sets `error` on its own result instead of failing the batch.
+    /// Generator refs to other blocks of the batch are resolved from the batch itself.
+    #[napi(ts_return_type = "Promise<Array<BlockParseResultJs>>")]
+    pub fn parse_blocks_batch(&self, blocks: Vec<Buffer>) -> AsyncTask<ParseBlocksBatchTask> {
+        debug!("Parsing batch of {} blocks", blocks.len());
+
+        AsyncTask::new(ParseBlocksBatchTask {
+            parser: self.parser.clone(),
+            blocks,
+        })
+    }
+
     /// Extract generator from block bytes
     #[napi]
     pub fn extract_generator_from_block_bytes(
@@ -262,4 +1227,58 @@ impl ChiaBlockParser {
 
         Ok((&block_info).into())
     }
+
+    /// Run a serialized generator on its own and analyze its composition
+    ///
+    /// The generator runs under the network's latest consensus rules, without generator
+    /// refs.
+    #[napi]
+    pub fn analyze_generator(&self, generator: Buffer) -> Result<GeneratorAnalysisJS> {
+        debug!("Analyzing generator of {} bytes", generator.len());
+
+        let analysis = self
+            .parser
+            .analyze_generator(&generator)
+            .map_err(|e| Error::new(Status::GenericFailure, format!("Analysis error: {e}")))?;
+
+        Ok((&analysis).into())
+    }
+
+    /// Analyze the generator of a FullBlock from bytes, or null when it has none
+    ///
+    /// Generator refs are resolved from the generators registered with
+    /// `cacheGenerator` and `cacheBlockGenerator`.
+    #[napi]
+    pub fn analyze_block_generator_from_bytes(
+        &self,
+        block_bytes: Buffer,
+    ) -> Result<Option<GeneratorAnalysisJS>> {
+        debug!("Analyzing block generator from {} bytes", block_bytes.len());
+
+        let block = FullBlock::from_bytes(&block_bytes).map_err(|e| {
+            Error::new(
+                Status::InvalidArg,
+                format!("Failed to deserialize FullBlock: {e}"),
+            )
+        })?;
+
+        let analysis = self
+            .parser
+            .analyze_block_generator(&block)
+            .map_err(|e| Error::new(Status::GenericFailure, format!("Analysis error: {e}")))?;
+
+        Ok(analysis.as_ref().map(|analysis| analysis.into()))
+    }
+
+    /// Disassemble serialized CLVM hex, such as a puzzle reveal or solution, into
+    /// chialisp-style text of at most `maxLength` characters (16384 by default)
+    #[napi]
+    pub fn disassemble(&self, program_hex: String, max_length: Option<u32>) -> Result<String> {
+        let program = hex::decode(&program_hex)
+            .map_err(|e| Error::new(Status::InvalidArg, format!("Hex decode error: {e}")))?;
+
+        let max_len = max_length.map_or(DEFAULT_DISASSEMBLY_LIMIT, |max_len| max_len as usize);
+        disassemble_bytes(&program, max_len)
+            .map_err(|e| Error::new(Status::InvalidArg, format!("Disassembly error: {e}")))
+    }
 }
@@ -1,11 +1,20 @@
+use crate::block_parser_napi::{
+    hint_entries, puzzle_layers, ConditionJS, DataStoreMirrorJS, DataStoreUpdateJS, FarmingInfoJS,
+    HintEntryJS, PuzzleLayerJS,
+};
 use crate::error::ChiaError;
-use crate::peer::PeerConnection;
-use chia_generator_parser::{types::ParsedBlock, BlockParser};
+use crate::peer::{PeerConnection, SharedCoinFilter};
+use crate::timestamps::{BlockHeader, BlockTimestamps, ResolvedTimestamp};
+use chia_generator_parser::{
+    types::{CoinInfo, ParsedBlock},
+    BlockParser, CoinFilter, InMemoryGeneratorCache,
+};
+use chia_protocol::Bytes32;
 
 use napi::{
     bindgen_prelude::*,
     threadsafe_function::{ErrorStrategy, ThreadsafeFunction, ThreadsafeFunctionCallMode},
-    JsFunction,
+    Env, JsFunction, JsObject,
 };
 use napi_derive::napi;
 use std::collections::HashMap;
@@ -19,6 +28,11 @@ pub const EVENT_BLOCK_RECEIVED: &str = "blockReceived";
 pub const EVENT_PEER_CONNECTED: &str = "peerConnected";
 #[allow(dead_code)]
 pub const EVENT_PEER_DISCONNECTED: &str = "peerDisconnected";
+#[allow(dead_code)]
+pub const EVENT_DATA_STORE_UPDATED: &str = "dataStoreUpdated";
+
+const GENERATOR_CACHE_SIZE: usize = 256; // Generators kept for resolving compressed blocks
+const TIMESTAMP_CACHE_SIZE: usize = 1024; // Block headers kept for dating non-transaction blocks
 
 // Export event types for TypeScript
 #[napi(object)]
@@ -26,6 +40,7 @@ pub struct EventTypes {
     pub block_received: String,
     pub peer_connected: String,
     pub peer_disconnected: String,
+    pub data_store_updated: String,
 }
 
 #[napi]
@@ -35,12 +50,16 @@ pub fn get_event_types() -> EventTypes {
         block_received: EVENT_BLOCK_RECEIVED.to_string(),
         peer_connected: EVENT_PEER_CONNECTED.to_string(),
         peer_disconnected: EVENT_PEER_DISCONNECTED.to_string(),
+        data_store_updated: EVENT_DATA_STORE_UPDATED.to_string(),
     }
 }
 
 #[napi]
 pub struct ChiaBlockListener {
     inner: Arc<RwLock<ChiaBlockListenerInner>>,
+    generator_cache: Arc<InMemoryGeneratorCache>,
+    timestamps: Arc<BlockTimestamps>,
+    coin_filter: SharedCoinFilter,
 }
 
 struct ChiaBlockListenerInner {
@@ -49,6 +68,8 @@ struct ChiaBlockListenerInner {
     peer_connected_listeners: Vec<ThreadsafeFunction<PeerConnectedEvent, ErrorStrategy::Fatal>>,
     peer_disconnected_listeners:
         Vec<ThreadsafeFunction<PeerDisconnectedEvent, ErrorStrategy::Fatal>>,
+    data_store_updated_listeners:
+        Vec<ThreadsafeFunction<DataStoreUpdatedEvent, ErrorStrategy::Fatal>>,
     block_sender: mpsc::Sender<ParsedBlockEvent>,
     event_sender: mpsc::Sender<PeerEvent>,
 }
@@ -62,6 +83,7 @@ struct PeerConnectionInfo {
 #[derive(Clone)]
 struct ParsedBlockEvent {
     peer_id: String,
+    header: BlockHeader,
     block: ParsedBlock,
 }
 
@@ -102,6 +124,73 @@ pub struct PeerDisconnectedEvent {
     pub message: Option<String>,
 }
 
+// Event struct for DataLayer store launches and updates
+#[napi(object)]
+#[derive(Clone)]
+pub struct DataStoreUpdatedEvent {
+    #[napi(js_name = "peerId")]
+    pub peer_id: String,
+    pub height: u32,
+    #[napi(js_name = "headerHash")]
+    pub header_hash: String,
+    #[napi(js_name = "launcherId")]
+    pub launcher_id: String,
+    #[napi(js_name = "coinId")]
+    pub coin_id: String,
+    #[napi(js_name = "newRootHash")]
+    pub new_root_hash: Option<String>,
+    #[napi(js_name = "previousRootHash")]
+    pub previous_root_hash: Option<String>, // Unset when the store is launched
+    pub metadata: String,
+    #[napi(js_name = "ownerPuzzleHash")]
+    pub owner_puzzle_hash: Option<String>, // Unset when the store is melted
+}
+
+impl DataStoreUpdatedEvent {
+    /// One event per DataLayer store launched or updated in the block
+    pub fn from_block(block: &ParsedBlock, peer_id: &str) -> Vec<Self> {
+        block
+            .data_store_updates
+            .iter()
+            .map(|update| Self {
+                peer_id: peer_id.to_string(),
+                height: block.height,
+                header_hash: hex::encode(block.header_hash),
+                launcher_id: hex::encode(update.launcher_id),
+                coin_id: hex::encode(update.coin_id),
+                new_root_hash: update.new_root_hash.map(hex::encode),
+                previous_root_hash: update.previous_root_hash.map(hex::encode),
+                metadata: hex::encode(&update.metadata),
+                owner_puzzle_hash: update.owner_puzzle_hash.map(hex::encode),
+            })
+            .collect()
+    }
+}
+
+/// Build the JS object for a dataStoreUpdated event
+pub fn create_data_store_updated_object(
+    env: &Env,
+    event: &DataStoreUpdatedEvent,
+) -> Result<JsObject> {
+    let mut obj = env.create_object()?;
+    obj.set_named_property("peerId", env.create_string(&event.peer_id)?)?;
+    obj.set_named_property("height", env.create_uint32(event.height)?)?;
+    obj.set_named_property("headerHash", env.create_string(&event.header_hash)?)?;
+    obj.set_named_property("launcherId", env.create_string(&event.launcher_id)?)?;
+    obj.set_named_property("coinId", env.create_string(&event.coin_id)?)?;
+    if let Some(root_hash) = &event.new_root_hash {
+        obj.set_named_property("newRootHash", env.create_string(root_hash)?)?;
+    }
+    if let Some(root_hash) = &event.previous_root_hash {
+        obj.set_named_property("previousRootHash", env.create_string(root_hash)?)?;
+    }
+    obj.set_named_property("metadata", env.create_string(&event.metadata)?)?;
+    if let Some(puzzle_hash) = &event.owner_puzzle_hash {
+        obj.set_named_property("ownerPuzzleHash", env.create_string(puzzle_hash)?)?;
+    }
+    Ok(obj)
+}
+
 // Event struct for block received callbacks
 #[napi(object)]
 #[derive(Clone)]
@@ -113,6 +202,9 @@ pub struct BlockReceivedEvent {
     #[napi(js_name = "headerHash")]
     pub header_hash: String,
     pub timestamp: u32,
+    #[napi(js_name = "timestampExact")]
+    pub timestamp_exact: bool, // False when inferred from an earlier transaction block
+    pub farming: FarmingInfoJS,
     #[napi(js_name = "coinAdditions")]
     pub coin_additions: Vec<CoinRecord>,
     #[napi(js_name = "coinRemovals")]
@@ -121,20 +213,160 @@ pub struct BlockReceivedEvent {
     pub coin_spends: Vec<CoinSpend>,
     #[napi(js_name = "coinCreations")]
     pub coin_creations: Vec<CoinRecord>,
+    #[napi(js_name = "hintIndex")]
+    pub hint_index: Vec<HintEntryJS>,
+    pub cost: String,
+    #[napi(js_name = "executionCost")]
+    pub execution_cost: String,
+    #[napi(js_name = "conditionCost")]
+    pub condition_cost: String,
+    pub fees: String,
+    #[napi(js_name = "reserveFee")]
+    pub reserve_fee: String,
+    #[napi(js_name = "dataStoreUpdates")]
+    pub data_store_updates: Vec<DataStoreUpdateJS>,
+    #[napi(js_name = "dataStoreMirrors")]
+    pub data_store_mirrors: Vec<DataStoreMirrorJS>,
     #[napi(js_name = "hasTransactionsGenerator")]
     pub has_transactions_generator: bool,
     #[napi(js_name = "generatorSize")]
     pub generator_size: u32,
 }
 
+/// Convert a parsed block to the event handed to JS, dated by `timestamp`
+pub(crate) fn convert_parsed_block_to_external(
+    parsed_block: &ParsedBlock,
+    peer_id: String,
+    timestamp: ResolvedTimestamp,
+) -> BlockReceivedEvent {
+    BlockReceivedEvent {
+        peer_id,
+        height: parsed_block.height,
+        weight: parsed_block.weight.to_string(),
+        header_hash: hex::encode(parsed_block.header_hash),
+        timestamp: timestamp.timestamp,
+        timestamp_exact: timestamp.exact,
+        farming: (&parsed_block.farming).into(),
+        coin_additions: parsed_block
+            .coin_additions
+            .iter()
+            .map(|coin| coin.into())
+            .collect(),
+        coin_removals: parsed_block
+            .coin_removals
+            .iter()
+            .map(|coin| coin.into())
+            .collect(),
+        coin_spends: parsed_block
+            .coin_spends
+            .iter()
+            .map(|spend| CoinSpend {
+                coin: (&spend.coin).into(),
+                puzzle_reveal: hex::encode(&spend.puzzle_reveal),
+                solution: hex::encode(&spend.solution),
+                offset: spend.offset,
+                conditions: spend.conditions.iter().map(|c| c.into()).collect(),
+                execution_cost: spend.execution_cost.to_string(),
+                condition_cost: spend.condition_cost.to_string(),
+                puzzle_layers: puzzle_layers(&spend.puzzle_kind),
+            })
+            .collect(),
+        coin_creations: parsed_block
+            .coin_creations
+            .iter()
+            .map(|coin| coin.into())
+            .collect(),
+        hint_index: hint_entries(&parsed_block.hint_index),
+        cost: parsed_block.cost.to_string(),
+        execution_cost: parsed_block.execution_cost.to_string(),
+        condition_cost: parsed_block.condition_cost.to_string(),
+        fees: parsed_block.fees.to_string(),
+        reserve_fee: parsed_block.reserve_fee.to_string(),
+        data_store_updates: parsed_block
+            .data_store_updates
+            .iter()
+            .map(|update| update.into())
+            .collect(),
+        data_store_mirrors: parsed_block
+            .data_store_mirrors
+            .iter()
+            .map(|mirror| mirror.into())
+            .collect(),
+        has_transactions_generator: parsed_block.has_transactions_generator,
+        generator_size: parsed_block.generator_size.unwrap_or(0),
+    }
+}
+
+// Coins to watch, as hex strings, for ChiaBlockListener.setCoinFilter
+#[napi(object)]
+#[derive(Clone)]
+pub struct CoinFilterJS {
+    #[napi(js_name = "puzzleHashes")]
+    pub puzzle_hashes: Option<Vec<String>>,
+    #[napi(js_name = "coinIds")]
+    pub coin_ids: Option<Vec<String>>,
+    pub hints: Option<Vec<String>>,
+}
+
+impl TryFrom<CoinFilterJS> for CoinFilter {
+    type Error = Error;
+
+    fn try_from(filter: CoinFilterJS) -> Result<Self> {
+        Ok(CoinFilter::new()
+            .with_puzzle_hashes(parse_hashes(filter.puzzle_hashes)?)
+            .with_coin_ids(parse_hashes(filter.coin_ids)?)
+            .with_hints(parse_hashes(filter.hints)?))
+    }
+}
+
+fn parse_hashes(hashes: Option<Vec<String>>) -> Result<Vec<Bytes32>> {
+    hashes
+        .unwrap_or_default()
+        .iter()
+        .map(|hash| {
+            hex::decode(hash.strip_prefix("0x").unwrap_or(hash))
+                .ok()
+                .and_then(|bytes| Bytes32::try_from(bytes).ok())
+                .ok_or_else(|| {
+                    Error::new(
+                        Status::InvalidArg,
+                        format!("Invalid 32-byte hex hash: {hash}"),
+                    )
+                })
+        })
+        .collect()
+}
+
 #[napi(object)]
 #[derive(Clone)]
 pub struct CoinRecord {
+    #[napi(js_name = "coinId")]
+    pub coin_id: String,
     #[napi(js_name = "parentCoinInfo")]
     pub parent_coin_info: String,
     #[napi(js_name = "puzzleHash")]
     pub puzzle_hash: String,
     pub amount: String,
+    #[napi(js_name = "createdBySpend")]
+    pub created_by_spend: Option<u32>, // Index into coinSpends
+    pub ephemeral: bool,
+    pub memos: Vec<String>,
+    pub hint: Option<String>,
+}
+
+impl From<&CoinInfo> for CoinRecord {
+    fn from(coin: &CoinInfo) -> Self {
+        Self {
+            coin_id: hex::encode(coin.coin_id),
+            parent_coin_info: hex::encode(coin.parent_coin_info),
+            puzzle_hash: hex::encode(coin.puzzle_hash),
+            amount: coin.amount.to_string(),
+            created_by_spend: coin.created_by_spend,
+            ephemeral: coin.ephemeral,
+            memos: coin.memos.iter().map(hex::encode).collect(),
+            hint: coin.hint.map(hex::encode),
+        }
+    }
 }
 
 #[napi(object)]
@@ -145,6 +377,31 @@ pub struct CoinSpend {
     pub puzzle_reveal: String,
     pub solution: String,
     pub offset: u32,
+    pub conditions: Vec<ConditionJS>,
+    #[napi(js_name = "executionCost")]
+    pub execution_cost: String,
+    #[napi(js_name = "conditionCost")]
+    pub condition_cost: String,
+    #[napi(js_name = "puzzleLayers")]
+    pub puzzle_layers: Vec<PuzzleLayerJS>,
+}
+
+/// Build the JS object for a coin record in a blockReceived event
+fn create_coin_object(env: &Env, coin: &CoinRecord) -> Result<JsObject> {
+    let mut coin_obj = env.create_object()?;
+    coin_obj.set_named_property("coinId", env.create_string(&coin.coin_id)?)?;
+    coin_obj.set_named_property("parentCoinInfo", env.create_string(&coin.parent_coin_info)?)?;
+    coin_obj.set_named_property("puzzleHash", env.create_string(&coin.puzzle_hash)?)?;
+    coin_obj.set_named_property("amount", env.create_string(&coin.amount)?)?;
+    if let Some(spend_index) = coin.created_by_spend {
+        coin_obj.set_named_property("createdBySpend", env.create_uint32(spend_index)?)?;
+    }
+    coin_obj.set_named_property("ephemeral", env.get_boolean(coin.ephemeral)?)?;
+    coin_obj.set_named_property("memos", coin.memos.clone())?;
+    if let Some(hint) = &coin.hint {
+        coin_obj.set_named_property("hint", env.create_string(hint)?)?;
+    }
+    Ok(coin_obj)
 }
 
 #[napi]
@@ -159,20 +416,38 @@ impl ChiaBlockListener {
             block_listeners: Vec::new(),
             peer_connected_listeners: Vec::new(),
             peer_disconnected_listeners: Vec::new(),
+            data_store_updated_listeners: Vec::new(),
             block_sender,
             event_sender,
         }));
 
+        let timestamps = Arc::new(BlockTimestamps::with_max_entries(TIMESTAMP_CACHE_SIZE));
+
         let inner_clone = inner.clone();
+        let timestamps_clone = timestamps.clone();
         tokio::spawn(async move {
-            Self::event_loop(inner_clone, block_receiver, event_receiver).await;
+            Self::event_loop(
+                inner_clone,
+                timestamps_clone,
+                block_receiver,
+                event_receiver,
+            )
+            .await;
         });
 
-        Self { inner }
+        Self {
+            inner,
+            generator_cache: Arc::new(InMemoryGeneratorCache::with_max_entries(
+                GENERATOR_CACHE_SIZE,
+            )),
+            timestamps,
+            coin_filter: Arc::new(std::sync::RwLock::new(None)),
+        }
     }
 
     async fn event_loop(
         inner: Arc<RwLock<ChiaBlockListenerInner>>,
+        timestamps: Arc<BlockTimestamps>,
         mut block_receiver: mpsc::Receiver<ParsedBlockEvent>,
         mut event_receiver: mpsc::Receiver<PeerEvent>,
     ) {
@@ -180,7 +455,8 @@ impl ChiaBlockListener {
             tokio::select! {
                 Some(block_event) = block_receiver.recv() => {
                     // Convert ParsedBlock to external Block format
-                    let block_received_event = ChiaBlockListener::convert_parsed_block_to_external(&block_event.block, block_event.peer_id);
+                    let timestamp = timestamps.resolve(&block_event.header);
+                    let block_received_event = convert_parsed_block_to_external(&block_event.block, block_event.peer_id, timestamp);
 
                     let listeners = {
                         let guard = inner.read().await;
@@ -189,6 +465,19 @@ impl ChiaBlockListener {
                     for listener in listeners {
                         listener.call(block_received_event.clone(), ThreadsafeFunctionCallMode::NonBlocking);
                     }
+
+                    let data_store_events = DataStoreUpdatedEvent::from_block(&block_event.block, &block_received_event.peer_id);
+                    if !data_store_events.is_empty() {
+                        let listeners = {
+                            let guard = inner.read().await;
+                            guard.data_store_updated_listeners.clone()
+                        };
+                        for event in data_store_events {
+                            for listener in &listeners {
+                                listener.call(event.clone(), ThreadsafeFunctionCallMode::NonBlocking);
+                            }
+                        }
+                    }
                 }
                 Some(peer_event) = event_receiver.recv() => {
                     match peer_event.event_type {
@@ -246,7 +535,8 @@ impl ChiaBlockListener {
 
     #[napi]
     pub fn add_peer(&self, host: String, port: u16, network_id: String) -> Result<String> {
-        let peer = PeerConnection::new(host.clone(), port, network_id);
+        let peer = PeerConnection::new(host.clone(), port, network_id)
+            .map_err(|e| Error::new(Status::InvalidArg, e.to_string()))?;
 
         let rt = tokio::runtime::Handle::current();
         let inner = self.inner.clone();
@@ -347,25 +637,18 @@ impl ChiaBlockListener {
                         ctx.env.create_string(&event.header_hash)?,
                     )?;
                     obj.set_named_property("timestamp", ctx.env.create_uint32(event.timestamp)?)?;
+                    obj.set_named_property(
+                        "timestampExact",
+                        ctx.env.get_boolean(event.timestamp_exact)?,
+                    )?;
+                    obj.set_named_property("farming", event.farming.clone())?;
 
                     // Coin additions array
                     let mut additions_array = ctx
                         .env
                         .create_array_with_length(event.coin_additions.len())?;
                     for (i, coin) in event.coin_additions.iter().enumerate() {
-                        let mut coin_obj = ctx.env.create_object()?;
-                        coin_obj.set_named_property(
-                            "parentCoinInfo",
-                            ctx.env.create_string(&coin.parent_coin_info)?,
-                        )?;
-                        coin_obj.set_named_property(
-                            "puzzleHash",
-                            ctx.env.create_string(&coin.puzzle_hash)?,
-                        )?;
-                        coin_obj.set_named_property(
-                            "amount",
-                            ctx.env.create_string(&coin.amount.to_string())?,
-                        )?;
+                        let coin_obj = create_coin_object(&ctx.env, coin)?;
                         additions_array.set_element(i as u32, coin_obj)?;
                     }
                     obj.set_named_property("coinAdditions", additions_array)?;
@@ -375,19 +658,7 @@ impl ChiaBlockListener {
                         .env
                         .create_array_with_length(event.coin_removals.len())?;
                     for (i, coin) in event.coin_removals.iter().enumerate() {
-                        let mut coin_obj = ctx.env.create_object()?;
-                        coin_obj.set_named_property(
-                            "parentCoinInfo",
-                            ctx.env.create_string(&coin.parent_coin_info)?,
-                        )?;
-                        coin_obj.set_named_property(
-                            "puzzleHash",
-                            ctx.env.create_string(&coin.puzzle_hash)?,
-                        )?;
-                        coin_obj.set_named_property(
-                            "amount",
-                            ctx.env.create_string(&coin.amount.to_string())?,
-                        )?;
+                        let coin_obj = create_coin_object(&ctx.env, coin)?;
                         removals_array.set_element(i as u32, coin_obj)?;
                     }
                     obj.set_named_property("coinRemovals", removals_array)?;
@@ -398,20 +669,7 @@ impl ChiaBlockListener {
                     for (i, spend) in event.coin_spends.iter().enumerate() {
                         let mut spend_obj = ctx.env.create_object()?;
 
-                        // Create coin object
-                        let mut coin_obj = ctx.env.create_object()?;
-                        coin_obj.set_named_property(
-                            "parentCoinInfo",
-                            ctx.env.create_string(&spend.coin.parent_coin_info)?,
-                        )?;
-                        coin_obj.set_named_property(
-                            "puzzleHash",
-                            ctx.env.create_string(&spend.coin.puzzle_hash)?,
-                        )?;
-                        coin_obj.set_named_property(
-                            "amount",
-                            ctx.env.create_string(&spend.coin.amount)?,
-                        )?;
+                        let coin_obj = create_coin_object(&ctx.env, &spend.coin)?;
                         spend_obj.set_named_property("coin", coin_obj)?;
 
                         spend_obj.set_named_property(
@@ -425,6 +683,17 @@ impl ChiaBlockListener {
 
                         spend_obj
                             .set_named_property("offset", ctx.env.create_uint32(spend.offset)?)?;
+                        spend_obj.set_named_property("conditions", spend.conditions.clone())?;
+                        spend_obj.set_named_property(
+                            "executionCost",
+                            ctx.env.create_string(&spend.execution_cost)?,
+                        )?;
+                        spend_obj.set_named_property(
+                            "conditionCost",
+                            ctx.env.create_string(&spend.condition_cost)?,
+                        )?;
+                        spend_obj
+                            .set_named_property("puzzleLayers", spend.puzzle_layers.clone())?;
 
                         spends_array.set_element(i as u32, spend_obj)?;
                     }
@@ -435,20 +704,27 @@ impl ChiaBlockListener {
                         .env
                         .create_array_with_length(event.coin_creations.len())?;
                     for (i, coin) in event.coin_creations.iter().enumerate() {
-                        let mut coin_obj = ctx.env.create_object()?;
-                        coin_obj.set_named_property(
-                            "parentCoinInfo",
-                            ctx.env.create_string(&coin.parent_coin_info)?,
-                        )?;
-                        coin_obj.set_named_property(
-                            "puzzleHash",
-                            ctx.env.create_string(&coin.puzzle_hash)?,
-                        )?;
-                        coin_obj
-                            .set_named_property("amount", ctx.env.create_string(&coin.amount)?)?;
+                        let coin_obj = create_coin_object(&ctx.env, coin)?;
                         creations_array.set_element(i as u32, coin_obj)?;
                     }
                     obj.set_named_property("coinCreations", creations_array)?;
+                    obj.set_named_property("hintIndex", event.hint_index.clone())?;
+                    obj.set_named_property("cost", ctx.env.create_string(&event.cost)?)?;
+                    obj.set_named_property(
+                        "executionCost",
+                        ctx.env.create_string(&event.execution_cost)?,
+                    )?;
+                    obj.set_named_property(
+                        "conditionCost",
+                        ctx.env.create_string(&event.condition_cost)?,
+                    )?;
+                    obj.set_named_property("fees", ctx.env.create_string(&event.fees)?)?;
+                    obj.set_named_property(
+                        "reserveFee",
+                        ctx.env.create_string(&event.reserve_fee)?,
+                    )?;
+                    obj.set_named_property("dataStoreUpdates", event.data_store_updates.clone())?;
+                    obj.set_named_property("dataStoreMirrors", event.data_store_mirrors.clone())?;
 
                     obj.set_named_property(
                         "hasTransactionsGenerator",
@@ -500,6 +776,17 @@ impl ChiaBlockListener {
                     guard.peer_disconnected_listeners.push(tsfn);
                 });
             }
+            "dataStoreUpdated" => {
+                let tsfn = callback.create_threadsafe_function(0, |ctx| {
+                    let event: &DataStoreUpdatedEvent = &ctx.value;
+                    Ok(vec![create_data_store_updated_object(&ctx.env, event)?])
+                })?;
+
+                rt.block_on(async {
+                    let mut guard = inner.write().await;
+                    guard.data_store_updated_listeners.push(tsfn);
+                });
+            }
             _ => {
                 return Err(Error::new(
                     Status::InvalidArg,
@@ -525,6 +812,7 @@ impl ChiaBlockListener {
                 "blockReceived" => guard.block_listeners.clear(),
                 "peerConnected" => guard.peer_connected_listeners.clear(),
                 "peerDisconnected" => guard.peer_disconnected_listeners.clear(),
+                "dataStoreUpdated" => guard.data_store_updated_listeners.clear(),
                 _ => {
                     return Err(Error::new(
                         Status::InvalidArg,
@@ -539,6 +827,8 @@ impl ChiaBlockListener {
 
     fn start_peer_connection(&self, peer_id: String, peer: PeerConnection) {
         let inner = self.inner.clone();
+        let generator_cache = self.generator_cache.clone();
+        let coin_filter = self.coin_filter.clone();
 
         tokio::spawn(async move {
             let (disconnect_tx, disconnect_rx) = oneshot::channel();
@@ -615,9 +905,10 @@ impl ChiaBlockListener {
                     // Spawn block listener
                     let inner_for_listener = inner.clone();
                     let host_for_listener = host.clone();
+                    let peer_for_listener = peer.clone();
                     tokio::spawn(async move {
                         tokio::select! {
-                            result = PeerConnection::listen_for_blocks(ws_stream, block_tx) => {
+                            result = peer_for_listener.listen_for_blocks(ws_stream, block_tx, generator_cache, coin_filter) => {
                                 match result {
                                     Ok(_) => info!("Peer {} (ID: {}) disconnected normally", host_for_listener, &peer_id_for_listener),
                                     Err(e) => {
@@ -660,7 +951,7 @@ impl ChiaBlockListener {
                     });
 
                     // Forward parsed blocks with peer ID
-                    while let Some(parsed_block) = block_rx.recv().await {
+                    while let Some((header, parsed_block)) = block_rx.recv().await {
                         info!(
                             "Received parsed block {} with {} coin additions, {} coin removals, {} coin spends, {} coin creations",
                             parsed_block.height,
@@ -673,6 +964,7 @@ impl ChiaBlockListener {
                         let _ = block_sender
                             .send(ParsedBlockEvent {
                                 peer_id: peer_id_for_blocks.clone(),
+                                header,
                                 block: parsed_block,
                             })
                             .await;
@@ -699,67 +991,12 @@ impl ChiaBlockListener {
         });
     }
 
-    // Helper function to convert internal types to external types
-    pub fn convert_parsed_block_to_external(
-        parsed_block: &ParsedBlock,
-        peer_id: String,
-    ) -> BlockReceivedEvent {
-        BlockReceivedEvent {
-            peer_id,
-            height: parsed_block.height,
-            weight: parsed_block.weight.clone(),
-            header_hash: parsed_block.header_hash.clone(),
-            timestamp: parsed_block.timestamp.unwrap_or(0),
-            coin_additions: parsed_block
-                .coin_additions
-                .iter()
-                .map(|coin| CoinRecord {
-                    parent_coin_info: coin.parent_coin_info.clone(),
-                    puzzle_hash: coin.puzzle_hash.clone(),
-                    amount: coin.amount.to_string(),
-                })
-                .collect(),
-            coin_removals: parsed_block
-                .coin_removals
-                .iter()
-                .map(|coin| CoinRecord {
-                    parent_coin_info: coin.parent_coin_info.clone(),
-                    puzzle_hash: coin.puzzle_hash.clone(),
-                    amount: coin.amount.to_string(),
-                })
-                .collect(),
-            coin_spends: parsed_block
-                .coin_spends
-                .iter()
-                .map(|spend| CoinSpend {
-                    coin: CoinRecord {
-                        parent_coin_info: spend.coin.parent_coin_info.clone(),
-                        puzzle_hash: spend.coin.puzzle_hash.clone(),
-                        amount: spend.coin.amount.to_string(),
-                    },
-                    puzzle_reveal: spend.puzzle_reveal.clone(),
-                    solution: spend.solution.clone(),
-                    offset: spend.offset,
-                })
-                .collect(),
-            coin_creations: parsed_block
-                .coin_creations
-                .iter()
-                .map(|coin| CoinRecord {
-                    parent_coin_info: coin.parent_coin_info.clone(),
-                    puzzle_hash: coin.puzzle_hash.clone(),
-                    amount: coin.amount.to_string(),
-                })
-                .collect(),
-            has_transactions_generator: parsed_block.has_transactions_generator,
-            generator_size: parsed_block.generator_size.unwrap_or(0),
-        }
-    }
-
     #[napi]
     pub fn get_block_by_height(&self, peer_id: String, height: u32) -> Result<BlockReceivedEvent> {
         let rt = tokio::runtime::Handle::current();
         let inner = self.inner.clone();
+        let generator_cache = self.generator_cache.clone();
+        let timestamps = self.timestamps.clone();
 
         let block_result = rt.block_on(async {
             let guard = inner.read().await;
@@ -767,6 +1004,7 @@ impl ChiaBlockListener {
             if let Some(peer_info) = guard.peers.get(&peer_id) {
                 let peer = peer_info.connection.clone();
                 drop(guard); // Release the lock before connecting
+                let network = peer.network();
 
                 // Create a new connection for this request
                 match peer.connect().await {
@@ -777,8 +1015,26 @@ impl ChiaBlockListener {
                         }
 
                         // Request the block
-                        peer.request_block_by_height(height as u64, &mut ws_stream)
-                            .await
+                        let block = peer
+                            .request_block_by_height(height as u64, &mut ws_stream)
+                            .await?;
+
+                        // Fetch generators referenced by compressed blocks
+                        peer.fetch_generator_refs(&block, &mut ws_stream, &generator_cache)
+                            .await?;
+
+                        // Date a non-transaction block from the blocks before it
+                        let timestamp = timestamps
+                            .resolve_or_fetch(BlockHeader::from(&block), async |height| {
+                                let prev = peer
+                                    .request_block_by_height(u64::from(height), &mut ws_stream)
+                                    .await
+                                    .ok()?;
+                                Some(BlockHeader::from(&prev))
+                            })
+                            .await;
+
+                        Ok((block, network, timestamp))
                     }
                     Err(e) => Err(e),
                 }
@@ -788,9 +1044,10 @@ impl ChiaBlockListener {
         });
 
         match block_result {
-            Ok(block) => {
+            Ok((block, network, timestamp)) => {
                 // Parse the block using chia-generator-parser
-                let parser = BlockParser::new();
+                let parser =
+                    BlockParser::new(network).with_generator_lookup(self.generator_cache.clone());
                 let parsed_block = parser.parse_full_block(&block).map_err(|e| {
                     Error::new(
                         Status::GenericFailure,
@@ -799,9 +1056,10 @@ impl ChiaBlockListener {
                 })?;
 
                 // Convert to Block type
-                Ok(Self::convert_parsed_block_to_external(
+                Ok(convert_parsed_block_to_external(
                     &parsed_block,
                     peer_id.clone(),
+                    timestamp,
                 ))
             }
             Err(e) => Err(Error::new(
@@ -839,6 +1097,22 @@ impl ChiaBlockListener {
 
         Ok(blocks)
     }
+
+    /// Only fully parse the spends of watched coins in blocks streamed from peers
+    ///
+    /// Removals and additions still list every coin, but `coinSpends` only holds spends
+    /// of or creating a coin with a listed ID, puzzle hash or hint. Pass `null` to parse
+    /// every spend again.
+    #[napi]
+    pub fn set_coin_filter(&self, filter: Option<CoinFilterJS>) -> Result<()> {
+        let filter = filter.map(CoinFilter::try_from).transpose()?;
+        let mut guard = self
+            .coin_filter
+            .write()
+            .map_err(|e| Error::new(Status::GenericFailure, format!("Lock error: {e}")))?;
+        *guard = filter.map(Arc::new);
+        Ok(())
+    }
 }
 
 impl Default for ChiaBlockListener {
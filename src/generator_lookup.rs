@@ -0,0 +1,69 @@
+use crate::peer_pool::ChiaPeerPool;
+use chia_generator_parser::{
+    generator_matches_root, GeneratorLookup, GeneratorParserError, InMemoryGeneratorCache,
+    Result as ParserResult,
+};
+use std::sync::Arc;
+use tokio::runtime::Handle;
+use tracing::debug;
+
+/// Generator lookup that fetches referenced blocks from a `ChiaPeerPool`
+///
+/// Fetched generators are kept in a shared cache, since many compressed blocks
+/// reference the same earlier generators. Lookups block on the pool, so the parser
+/// must run outside the async worker threads (e.g. in `spawn_blocking`).
+pub struct PeerPoolGeneratorLookup {
+    pool: ChiaPeerPool,
+    cache: Arc<InMemoryGeneratorCache>,
+    runtime: Handle,
+}
+
+impl PeerPoolGeneratorLookup {
+    pub fn new(pool: ChiaPeerPool, cache: Arc<InMemoryGeneratorCache>) -> Self {
+        Self {
+            pool,
+            cache,
+            runtime: Handle::current(),
+        }
+    }
+}
+
+impl GeneratorLookup for PeerPoolGeneratorLookup {
+    fn get_generator(&self, height: u32) -> ParserResult<Vec<u8>> {
+        if let Ok(generator) = self.cache.get_generator(height) {
+            return Ok(generator);
+        }
+
+        debug!(
+            "Fetching referenced generator at height {} from pool",
+            height
+        );
+
+        let fetched = self
+            .runtime
+            .block_on(self.pool.get_full_block_by_height(height as u64))
+            .map_err(|e| {
+                GeneratorParserError::GeneratorLookupError(format!(
+                    "Failed to fetch block {height}: {e}"
+                ))
+            })?;
+
+        let generator = fetched
+            .block
+            .transactions_generator
+            .as_ref()
+            .map(|g| g.to_vec())
+            .ok_or(GeneratorParserError::GeneratorRefNotFound(height))?;
+
+        // Every later block referencing this height would use a swapped generator
+        if !generator_matches_root(&fetched.block) {
+            return Err(GeneratorParserError::GeneratorLookupError(format!(
+                "Generator of block {height} from peer {} doesn't match its generator root",
+                fetched.peer_id
+            )));
+        }
+
+        self.cache.insert(height, generator.clone());
+        Ok(generator)
+    }
+}
@@ -6,10 +6,12 @@ mod block_parser_napi;
 mod dns_discovery_napi;
 mod error;
 mod event_emitter;
+mod generator_lookup;
 mod peer;
 mod peer_pool;
 mod peer_pool_napi;
 mod protocol;
+mod timestamps;
 mod tls;
 
 pub use block_parser_napi::ChiaBlockParser;
@@ -1,5 +1,9 @@
-use crate::{error::ChiaError, tls};
-use chia_generator_parser::{parser::BlockParser, types::ParsedBlock};
+use crate::timestamps::BlockHeader;
+use crate::{error::ChiaError, protocol, tls};
+use chia_generator_parser::{
+    generator_matches_root, parser::BlockParser, types::ParsedBlock, CoinFilter,
+    InMemoryGeneratorCache, Network,
+};
 use chia_protocol::{
     FullBlock, Handshake as ChiaHandshake, NewPeakWallet, NodeType, ProtocolMessageTypes,
     RequestBlock, RespondBlock,
@@ -7,6 +11,7 @@ use chia_protocol::{
 use chia_traits::Streamable;
 use futures_util::{SinkExt, StreamExt};
 use std::net::IpAddr;
+use std::sync::{Arc, RwLock};
 use tokio::net::TcpStream;
 use tokio::sync::mpsc;
 use tokio_tungstenite::{
@@ -17,20 +22,29 @@ use tracing::{debug, error, info, warn};
 
 type WebSocket = WebSocketStream<MaybeTlsStream<TcpStream>>;
 
+/// Coin filter applied to streamed blocks, replaceable while peers are connected
+pub type SharedCoinFilter = Arc<RwLock<Option<Arc<CoinFilter>>>>;
+
 #[derive(Clone)]
 pub struct PeerConnection {
     host: String,
     port: u16,
     network_id: String,
+    network: Network,
 }
 
 impl PeerConnection {
-    pub fn new(host: String, port: u16, network_id: String) -> Self {
-        Self {
+    /// Fails for a network ID without known consensus rules
+    pub fn new(host: String, port: u16, network_id: String) -> Result<Self, ChiaError> {
+        let network = protocol::network_for_id(&network_id)
+            .ok_or_else(|| ChiaError::Protocol(format!("Unknown network ID: {network_id}")))?;
+
+        Ok(Self {
             host,
             port,
             network_id,
-        }
+            network,
+        })
     }
 
     pub fn host(&self) -> &str {
@@ -41,6 +55,11 @@ impl PeerConnection {
         self.port
     }
 
+    /// Network whose consensus rules apply to this peer's blocks
+    pub fn network(&self) -> Network {
+        self.network.clone()
+    }
+
     pub async fn connect(&self) -> Result<WebSocket, ChiaError> {
         info!("Connecting to peer at {}:{}", self.host, self.port);
 
@@ -162,8 +181,11 @@ impl PeerConnection {
     }
 
     pub async fn listen_for_blocks(
+        &self,
         mut ws_stream: WebSocket,
-        block_sender: mpsc::Sender<ParsedBlock>,
+        block_sender: mpsc::Sender<(BlockHeader, ParsedBlock)>,
+        generator_cache: Arc<InMemoryGeneratorCache>,
+        coin_filter: SharedCoinFilter,
     ) -> Result<(), ChiaError> {
         info!("Listening for blocks and messages");
 
@@ -221,11 +243,41 @@ impl PeerConnection {
                                                 block.reward_chain_block.height
                                             );
 
+                                            // Keep generators around for later compressed blocks
+                                            generator_cache.insert_block(&block);
+
+                                            // Fetch generators referenced by compressed blocks
+                                            if let Err(e) = self
+                                                .fetch_generator_refs_on_new_connection(
+                                                    &block,
+                                                    &generator_cache,
+                                                )
+                                                .await
+                                            {
+                                                warn!(
+                                                    "Failed to fetch generator refs of block {}: {}",
+                                                    block.reward_chain_block.height, e
+                                                );
+                                            }
+
                                             // Parse the block using chia-generator-parser
-                                            match Self::parse_block(block).await {
+                                            let header = BlockHeader::from(&block);
+                                            let filter = coin_filter
+                                                .read()
+                                                .ok()
+                                                .and_then(|filter| filter.clone());
+                                            match Self::parse_block(
+                                                block,
+                                                generator_cache.clone(),
+                                                filter,
+                                                self.network(),
+                                            )
+                                            .await
+                                            {
                                                 Ok(parsed_block) => {
-                                                    if let Err(e) =
-                                                        block_sender.send(parsed_block).await
+                                                    if let Err(e) = block_sender
+                                                        .send((header, parsed_block))
+                                                        .await
                                                     {
                                                         error!(
                                                             "Failed to send parsed block through channel: {}",
@@ -284,17 +336,28 @@ impl PeerConnection {
     }
 
     /// Parse a FullBlock using chia-generator-parser
-    async fn parse_block(block: FullBlock) -> Result<ParsedBlock, ChiaError> {
+    async fn parse_block(
+        block: FullBlock,
+        generator_cache: Arc<InMemoryGeneratorCache>,
+        coin_filter: Option<Arc<CoinFilter>>,
+        network: Network,
+    ) -> Result<ParsedBlock, ChiaError> {
         info!(
             "Parsing block at height {}",
             block.reward_chain_block.height
         );
 
-        // Use chia-generator-parser to parse the block directly
-        let parser = BlockParser::new();
-        let parsed_block = parser
-            .parse_full_block(&block)
-            .map_err(|e| ChiaError::Protocol(e.to_string()))?;
+        // Large generators take seconds to run, so keep them off the async workers
+        let parsed_block = tokio::task::spawn_blocking(move || {
+            let mut parser = BlockParser::new(network).with_generator_lookup(generator_cache);
+            if let Some(coin_filter) = coin_filter {
+                parser = parser.with_coin_filter(coin_filter);
+            }
+            parser.parse_full_block(&block)
+        })
+        .await
+        .map_err(|e| ChiaError::Other(format!("Block parsing task failed: {e}")))?
+        .map_err(|e| ChiaError::Protocol(e.to_string()))?;
 
         info!(
             "Parsed block {}: {} coin additions, {} coin removals, {} coin spends, generator: {}",
@@ -308,6 +371,62 @@ impl PeerConnection {
         Ok(parsed_block)
     }
 
+    /// Fetch the generators referenced by `block` that are not cached yet
+    pub async fn fetch_generator_refs(
+        &self,
+        block: &FullBlock,
+        ws_stream: &mut WebSocket,
+        generator_cache: &InMemoryGeneratorCache,
+    ) -> Result<(), ChiaError> {
+        for &ref_height in &block.transactions_generator_ref_list {
+            if generator_cache.contains(ref_height) {
+                continue;
+            }
+
+            debug!(
+                "Fetching referenced generator at height {} for block {}",
+                ref_height, block.reward_chain_block.height
+            );
+            let ref_block = self
+                .request_block_by_height(ref_height as u64, ws_stream)
+                .await?;
+
+            // Every later block referencing this height would use a swapped generator
+            if !generator_matches_root(&ref_block) {
+                return Err(ChiaError::Protocol(format!(
+                    "Generator of block {ref_height} doesn't match its generator root"
+                )));
+            }
+            generator_cache.insert_block(&ref_block);
+        }
+
+        Ok(())
+    }
+
+    /// Fetch the generators referenced by `block` that are not cached yet, over a
+    /// connection of their own
+    ///
+    /// Used while listening, as waiting for the referenced blocks on the listening
+    /// connection would drop the peaks announced in the meantime.
+    pub async fn fetch_generator_refs_on_new_connection(
+        &self,
+        block: &FullBlock,
+        generator_cache: &InMemoryGeneratorCache,
+    ) -> Result<(), ChiaError> {
+        if block
+            .transactions_generator_ref_list
+            .iter()
+            .all(|&ref_height| generator_cache.contains(ref_height))
+        {
+            return Ok(());
+        }
+
+        let mut ws_stream = self.connect().await?;
+        self.handshake(&mut ws_stream).await?;
+        self.fetch_generator_refs(block, &mut ws_stream, generator_cache)
+            .await
+    }
+
     pub async fn request_block_by_height(
         &self,
         height: u64,
@@ -361,6 +480,15 @@ impl PeerConnection {
                                         match RespondBlock::from_bytes(&response.data) {
                                             Ok(respond_block) => {
                                                 let block = respond_block.block;
+                                                if u64::from(block.reward_chain_block.height)
+                                                    != height
+                                                {
+                                                    debug!(
+                                                        "Received block {} while waiting for block {}",
+                                                        block.reward_chain_block.height, height
+                                                    );
+                                                    continue;
+                                                }
                                                 info!(
                                                     "Received block at height {} - transactions_generator: {} bytes, has_foliage_transaction_block: {}",
                                                     block.reward_chain_block.height,
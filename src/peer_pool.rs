@@ -1,10 +1,14 @@
 use crate::error::ChiaError;
 use crate::event_emitter::{
-    BlockReceivedEvent, CoinRecord, CoinSpend, PeerConnectedEvent, PeerDisconnectedEvent,
+    convert_parsed_block_to_external, BlockReceivedEvent, DataStoreUpdatedEvent,
+    PeerConnectedEvent, PeerDisconnectedEvent,
 };
+use crate::generator_lookup::PeerPoolGeneratorLookup;
 use crate::peer::PeerConnection;
-use chia_generator_parser::{BlockParser, ParsedBlock};
-use chia_protocol::FullBlock;
+use crate::protocol;
+use crate::timestamps::{BlockHeader, BlockTimestamps};
+use chia_generator_parser::{BlockParser, BlockVerification, InMemoryGeneratorCache, ParsedBlock};
+use chia_protocol::{Bytes32, FullBlock};
 
 use napi_derive::napi;
 use std::collections::{HashMap, VecDeque};
@@ -19,10 +23,14 @@ use tracing::{debug, error, info, warn};
 const RATE_LIMIT_MS: u64 = 500; // 500ms cooldown between peer usage
 const REQUEST_TIMEOUT_MS: u64 = 5000; // 5 second timeout for block requests (reduced from 10s)
 const CONNECTION_TIMEOUT_MS: u64 = 3000; // 3 second timeout for connections (reduced from 5s)
+const GENERATOR_CACHE_SIZE: usize = 256; // Referenced generators kept for compressed blocks
+const TIMESTAMP_CACHE_SIZE: usize = 1024; // Block headers kept for dating non-transaction blocks
+const MAX_VERIFICATION_ATTEMPTS: usize = 3; // Peers asked for a block that fails verification
 
 pub type PeerConnectedCallback = Box<dyn Fn(PeerConnectedEvent) + Send + Sync + 'static>;
 pub type PeerDisconnectedCallback = Box<dyn Fn(PeerDisconnectedEvent) + Send + Sync + 'static>;
 pub type NewPeakHeightCallback = Box<dyn Fn(NewPeakHeightEvent) + Send + Sync + 'static>;
+pub type DataStoreUpdatedCallback = Box<dyn Fn(DataStoreUpdatedEvent) + Send + Sync + 'static>;
 
 #[derive(Debug, Clone)]
 #[napi(object)]
@@ -35,6 +43,13 @@ pub struct NewPeakHeightEvent {
     pub peer_id: String,
 }
 
+/// An unparsed FullBlock together with the peer that served it
+pub struct FetchedBlock {
+    pub peer_id: String,
+    pub network_id: String,
+    pub block: FullBlock,
+}
+
 struct PeerWorkerParams {
     peer_connection: PeerConnection,
     peer_id: String,
@@ -45,12 +60,16 @@ struct PeerWorkerParams {
     new_peak_callback: Arc<RwLock<Option<NewPeakHeightCallback>>>,
 }
 
+#[derive(Clone)]
 pub struct ChiaPeerPool {
     inner: Arc<RwLock<ChiaPeerPoolInner>>,
     request_sender: mpsc::Sender<PoolRequest>,
     connected_callback: Arc<RwLock<Option<PeerConnectedCallback>>>,
     disconnected_callback: Arc<RwLock<Option<PeerDisconnectedCallback>>>,
     new_peak_callback: Arc<RwLock<Option<NewPeakHeightCallback>>>,
+    data_store_updated_callback: Arc<RwLock<Option<DataStoreUpdatedCallback>>>,
+    generator_cache: Arc<InMemoryGeneratorCache>,
+    timestamps: Arc<BlockTimestamps>,
 }
 
 struct ChiaPeerPoolInner {
@@ -61,16 +80,32 @@ struct ChiaPeerPoolInner {
 }
 
 struct PeerInfo {
+    network_id: String,
     last_used: Instant,
     is_connected: bool,
     worker_tx: Option<mpsc::Sender<WorkerRequest>>,
     peak_height: Option<u32>,
 }
 
+impl ChiaPeerPoolInner {
+    /// Whether a connected peer other than those in `excluded_peers` is left to ask
+    fn has_connected_peer_outside(&self, excluded_peers: &[String]) -> bool {
+        self.peer_ids.iter().any(|peer_id| {
+            !excluded_peers.contains(peer_id)
+                && self
+                    .peers
+                    .get(peer_id)
+                    .is_some_and(|peer_info| peer_info.is_connected)
+        })
+    }
+}
+
 enum PoolRequest {
     GetBlockByHeight {
         height: u64,
-        response_tx: oneshot::Sender<Result<BlockReceivedEvent, ChiaError>>,
+        /// Peers not to ask, as their copy of the block failed verification
+        excluded_peers: Vec<String>,
+        response_tx: oneshot::Sender<Result<FetchedBlock, ChiaError>>,
     },
 }
 
@@ -104,6 +139,11 @@ impl ChiaPeerPool {
             connected_callback: Arc::new(RwLock::new(None)),
             disconnected_callback: Arc::new(RwLock::new(None)),
             new_peak_callback: Arc::new(RwLock::new(None)),
+            data_store_updated_callback: Arc::new(RwLock::new(None)),
+            generator_cache: Arc::new(InMemoryGeneratorCache::with_max_entries(
+                GENERATOR_CACHE_SIZE,
+            )),
+            timestamps: Arc::new(BlockTimestamps::with_max_entries(TIMESTAMP_CACHE_SIZE)),
         };
 
         // Start the request processor
@@ -117,12 +157,14 @@ impl ChiaPeerPool {
         connected_callback: PeerConnectedCallback,
         disconnected_callback: PeerDisconnectedCallback,
         new_peak_callback: NewPeakHeightCallback,
+        data_store_updated_callback: DataStoreUpdatedCallback,
     ) {
         let rt = tokio::runtime::Handle::current();
         rt.block_on(async {
             *self.connected_callback.write().await = Some(connected_callback);
             *self.disconnected_callback.write().await = Some(disconnected_callback);
             *self.new_peak_callback.write().await = Some(new_peak_callback);
+            *self.data_store_updated_callback.write().await = Some(data_store_updated_callback);
         });
     }
 
@@ -134,7 +176,7 @@ impl ChiaPeerPool {
     ) -> Result<String, ChiaError> {
         info!("Adding peer {}:{} to pool", host, port);
 
-        let peer_connection = PeerConnection::new(host.clone(), port, network_id);
+        let peer_connection = PeerConnection::new(host.clone(), port, network_id.clone())?;
         let peer_id = format!("{host}:{port}");
 
         // Establish connection upfront
@@ -205,6 +247,7 @@ impl ChiaPeerPool {
         guard.peers.insert(
             peer_id.clone(),
             PeerInfo {
+                network_id,
                 last_used: Instant::now()
                     .checked_sub(Duration::from_millis(RATE_LIMIT_MS))
                     .unwrap_or(Instant::now()),
@@ -229,14 +272,144 @@ impl ChiaPeerPool {
     }
 
     pub async fn get_block_by_height(&self, height: u64) -> Result<BlockReceivedEvent, ChiaError> {
-        self.get_block_by_height_with_failover(height, 3).await
+        // Peers whose block failed verification, with the header hash of that block
+        let mut rejected: Vec<(String, Bytes32)> = Vec::new();
+        let (parsed_block, peer_id, header) = loop {
+            let excluded_peers: Vec<String> = rejected
+                .iter()
+                .map(|(peer_id, _)| peer_id.clone())
+                .collect();
+            let fetched = self
+                .get_full_block_by_height_excluding(height, &excluded_peers)
+                .await?;
+            let header = BlockHeader::from(&fetched.block);
+            let (parsed_block, verification) =
+                self.parse_block(fetched.block, &fetched.network_id).await?;
+
+            if verification.is_valid() {
+                // A peer that served this very block with contents its foliage doesn't
+                // commit to altered it. One that served another block at this height may
+                // only be on another fork, and one whose block couldn't be verified may
+                // have been honest.
+                for (rejected_peer_id, header_hash) in rejected {
+                    if header_hash == verification.header_hash {
+                        warn!(
+                            "Peer {} served an altered block {}, removing peer from pool",
+                            rejected_peer_id, height
+                        );
+                        let _ = self.remove_peer(rejected_peer_id).await;
+                    }
+                }
+                break (parsed_block, fetched.peer_id, header);
+            }
+
+            let failed_checks = verification.failed_checks();
+            let reason = if failed_checks.is_empty() {
+                format!(
+                    "could not verify {}",
+                    verification.unverifiable_checks().join(", ")
+                )
+            } else {
+                failed_checks.join(", ")
+            };
+            warn!(
+                "Block {} from peer {} failed verification ({}), trying another peer",
+                height, fetched.peer_id, reason
+            );
+            rejected.push((fetched.peer_id, verification.header_hash));
+
+            let excluded_peers: Vec<String> = rejected
+                .iter()
+                .map(|(peer_id, _)| peer_id.clone())
+                .collect();
+            if rejected.len() >= MAX_VERIFICATION_ATTEMPTS
+                || !self.has_connected_peer_outside(&excluded_peers).await
+            {
+                return Err(ChiaError::Protocol(format!(
+                    "Block {height} failed verification: {reason}"
+                )));
+            }
+        };
+
+        if let Some(callback) = &*self.data_store_updated_callback.read().await {
+            for event in DataStoreUpdatedEvent::from_block(&parsed_block, &peer_id) {
+                callback(event);
+            }
+        }
+
+        // Owned by the closure, as a borrowed pool keeps the fetch future from being Send
+        let pool = self.clone();
+        let timestamp = self
+            .timestamps
+            .resolve_or_fetch(header, async move |height| {
+                let fetched = pool
+                    .get_full_block_by_height(u64::from(height))
+                    .await
+                    .ok()?;
+                Some(BlockHeader::from(&fetched.block))
+            })
+            .await;
+
+        Ok(convert_parsed_block_to_external(
+            &parsed_block,
+            peer_id,
+            timestamp,
+        ))
+    }
+
+    /// Fetch an unparsed FullBlock along with the peer that served it
+    pub async fn get_full_block_by_height(&self, height: u64) -> Result<FetchedBlock, ChiaError> {
+        self.get_block_by_height_with_failover(height, 3, &[]).await
+    }
+
+    /// Like `get_full_block_by_height`, without asking the peers in `excluded_peers`
+    async fn get_full_block_by_height_excluding(
+        &self,
+        height: u64,
+        excluded_peers: &[String],
+    ) -> Result<FetchedBlock, ChiaError> {
+        self.get_block_by_height_with_failover(height, 3, excluded_peers)
+            .await
+    }
+
+    /// Whether a connected peer other than those in `excluded_peers` is left to ask
+    async fn has_connected_peer_outside(&self, excluded_peers: &[String]) -> bool {
+        let guard = self.inner.read().await;
+        guard.has_connected_peer_outside(excluded_peers)
+    }
+
+    /// Parse a block, resolving any generator refs through the pool, and verify it against
+    /// its commitments
+    async fn parse_block(
+        &self,
+        full_block: FullBlock,
+        network_id: &str,
+    ) -> Result<(ParsedBlock, BlockVerification), ChiaError> {
+        let lookup = PeerPoolGeneratorLookup::new(self.clone(), self.generator_cache.clone());
+        let network = protocol::network_for_id(network_id)
+            .ok_or_else(|| ChiaError::Protocol(format!("Unknown network ID: {network_id}")))?;
+
+        // Ref lookups block on the pool, so keep parsing off the async workers
+        tokio::task::spawn_blocking(move || {
+            let parser = BlockParser::new(network).with_generator_lookup(Arc::new(lookup));
+            parser
+                .parse_full_block(&full_block)
+                .and_then(|parsed_block| {
+                    let verification = parser.verify_block(&full_block, &parsed_block)?;
+                    Ok((parsed_block, verification))
+                })
+        })
+        .await
+        .map_err(|e| ChiaError::Other(format!("Block parsing task failed: {e}")))?
+        .map_err(|e| ChiaError::Protocol(format!("Failed to parse block: {e}")))
     }
 
     async fn get_block_by_height_with_failover(
         &self,
         height: u64,
         max_retries: usize,
-    ) -> Result<BlockReceivedEvent, ChiaError> {
+        excluded_peers: &[String],
+    ) -> Result<FetchedBlock, ChiaError> {
         let mut attempted_peers = Vec::new();
         let mut last_error = ChiaError::Connection("No peers available".to_string());
 
@@ -248,7 +421,7 @@ impl ChiaPeerPool {
                 let now = Instant::now();
 
                 for peer_id in &guard.peer_ids {
-                    if !attempted_peers.contains(peer_id) {
+                    if !attempted_peers.contains(peer_id) && !excluded_peers.contains(peer_id) {
                         if let Some(peer_info) = guard.peers.get(peer_id) {
                             if peer_info.is_connected {
                                 let time_since_last_use = now.duration_since(peer_info.last_used);
@@ -280,6 +453,7 @@ impl ChiaPeerPool {
                     .request_sender
                     .send(PoolRequest::GetBlockByHeight {
                         height,
+                        excluded_peers: excluded_peers.to_vec(),
                         response_tx,
                     })
                     .await
@@ -290,14 +464,14 @@ impl ChiaPeerPool {
                 }
 
                 match response_rx.await {
-                    Ok(Ok(block_event)) => {
+                    Ok(Ok(block)) => {
                         debug!(
                             "Successfully got block {} from peer {} on attempt {}",
                             height,
                             peer_id,
                             attempt + 1
                         );
-                        return Ok(block_event);
+                        return Ok(block);
                     }
                     Ok(Err(e)) => {
                         warn!(
@@ -434,7 +608,19 @@ impl ChiaPeerPool {
 
                             if let Some(request) = request_queue.front() {
                                 match request {
-                                    PoolRequest::GetBlockByHeight { .. } => {
+                                    PoolRequest::GetBlockByHeight { excluded_peers, .. } => {
+                                        // A request no connected peer may serve would wait forever
+                                        if !guard.has_connected_peer_outside(excluded_peers) {
+                                            if let Some(PoolRequest::GetBlockByHeight { response_tx, .. }) =
+                                                request_queue.pop_front()
+                                            {
+                                                let _ = response_tx.send(Err(ChiaError::Connection(
+                                                    "No peers available".to_string(),
+                                                )));
+                                            }
+                                            continue;
+                                        }
+
                                         // Round-robin peer selection with 500ms cooldown
                                         let now = Instant::now();
                                         let mut selected_peer = None;
@@ -447,7 +633,7 @@ impl ChiaPeerPool {
                                                 let peer_id = &guard.peer_ids[guard.round_robin_index];
 
                                                 if let Some(peer_info) = guard.peers.get(peer_id) {
-                                                    if peer_info.is_connected {
+                                                    if peer_info.is_connected && !excluded_peers.contains(peer_id) {
                                                         let time_since_last_use = now.duration_since(peer_info.last_used);
 
                                                         // Check if this peer is available (past cooldown)
@@ -481,6 +667,7 @@ impl ChiaPeerPool {
                                                                 PoolRequest::GetBlockByHeight {
                                                                     height,
                                                                     response_tx,
+                                                                    ..
                                                                 } => {
                                                                     if let Some(worker_tx) = &peer_info.worker_tx {
                                                                         let (worker_response_tx, worker_response_rx) =
@@ -503,25 +690,15 @@ impl ChiaPeerPool {
 
                                                                         // Process response asynchronously for maximum throughput
                                                                         let peer_id_clone = peer_id.clone();
+                                                                        let network_id = peer_info.network_id.clone();
                                                                         tokio::spawn(async move {
                                                                             match worker_response_rx.await {
                                                                                 Ok(Ok(full_block)) => {
-                                                                                    // Parse the block
-                                                                                    let parser = BlockParser::new();
-                                                                                    match parser.parse_full_block(&full_block) {
-                                                                                        Ok(parsed_block) => {
-                                                                                            let block_event = Self::convert_parsed_block_to_external(
-                                                                                                &parsed_block,
-                                                                                                peer_id_clone,
-                                                                                            );
-                                                                                            let _ = response_tx.send(Ok(block_event));
-                                                                                        }
-                                                                                        Err(e) => {
-                                                                                            let _ = response_tx.send(Err(
-                                                                                                ChiaError::Protocol(format!("Failed to parse block: {e}")),
-                                                                                            ));
-                                                                                        }
-                                                                                    }
+                                                                                    let _ = response_tx.send(Ok(FetchedBlock {
+                                                                                        peer_id: peer_id_clone,
+                                                                                        network_id,
+                                                                                        block: full_block,
+                                                                                    }));
                                                                                 }
                                                                                 Ok(Err(e)) => {
                                                                                     let _ = response_tx.send(Err(e));
@@ -903,63 +1080,6 @@ impl ChiaPeerPool {
         }
     }
 
-    // Helper function to convert internal types to external types
-    fn convert_parsed_block_to_external(
-        parsed_block: &ParsedBlock,
-        peer_id: String,
-    ) -> BlockReceivedEvent {
-        BlockReceivedEvent {
-            peer_id,
-            height: parsed_block.height,
-            weight: parsed_block.weight.clone(),
-            header_hash: parsed_block.header_hash.clone(),
-            timestamp: parsed_block.timestamp.unwrap_or(0),
-            coin_additions: parsed_block
-                .coin_additions
-                .iter()
-                .map(|coin| CoinRecord {
-                    parent_coin_info: coin.parent_coin_info.clone(),
-                    puzzle_hash: coin.puzzle_hash.clone(),
-                    amount: coin.amount.to_string(),
-                })
-                .collect(),
-            coin_removals: parsed_block
-                .coin_removals
-                .iter()
-                .map(|coin| CoinRecord {
-                    parent_coin_info: coin.parent_coin_info.clone(),
-                    puzzle_hash: coin.puzzle_hash.clone(),
-                    amount: coin.amount.to_string(),
-                })
-                .collect(),
-            coin_spends: parsed_block
-                .coin_spends
-                .iter()
-                .map(|spend| CoinSpend {
-                    coin: CoinRecord {
-                        parent_coin_info: spend.coin.parent_coin_info.clone(),
-                        puzzle_hash: spend.coin.puzzle_hash.clone(),
-                        amount: spend.coin.amount.to_string(),
-                    },
-                    puzzle_reveal: spend.puzzle_reveal.clone(),
-                    solution: spend.solution.clone(),
-                    offset: spend.offset,
-                })
-                .collect(),
-            coin_creations: parsed_block
-                .coin_creations
-                .iter()
-                .map(|coin| CoinRecord {
-                    parent_coin_info: coin.parent_coin_info.clone(),
-                    puzzle_hash: coin.puzzle_hash.clone(),
-                    amount: coin.amount.to_string(),
-                })
-                .collect(),
-            has_transactions_generator: parsed_block.has_transactions_generator,
-            generator_size: parsed_block.generator_size.unwrap_or(0),
-        }
-    }
-
     async fn disconnect_peer_internal(
         inner: &Arc<RwLock<ChiaPeerPoolInner>>,
         params: &PeerWorkerParams,
@@ -1,4 +1,7 @@
-use crate::event_emitter::{BlockReceivedEvent, PeerConnectedEvent, PeerDisconnectedEvent};
+use crate::event_emitter::{
+    create_data_store_updated_object, BlockReceivedEvent, DataStoreUpdatedEvent,
+    PeerConnectedEvent, PeerDisconnectedEvent,
+};
 use crate::peer_pool::{ChiaPeerPool as InternalPeerPool, NewPeakHeightEvent};
 use napi::bindgen_prelude::*;
 use napi::{
@@ -21,6 +24,8 @@ struct EventListeners {
     peer_disconnected_listeners:
         Vec<ThreadsafeFunction<PeerDisconnectedEvent, ErrorStrategy::Fatal>>,
     new_peak_height_listeners: Vec<ThreadsafeFunction<NewPeakHeightEvent, ErrorStrategy::Fatal>>,
+    data_store_updated_listeners:
+        Vec<ThreadsafeFunction<DataStoreUpdatedEvent, ErrorStrategy::Fatal>>,
 }
 
 #[napi]
@@ -32,6 +37,7 @@ impl ChiaPeerPool {
             peer_connected_listeners: Vec::new(),
             peer_disconnected_listeners: Vec::new(),
             new_peak_height_listeners: Vec::new(),
+            data_store_updated_listeners: Vec::new(),
         }));
 
         let pool = Arc::new(InternalPeerPool::new());
@@ -40,6 +46,7 @@ impl ChiaPeerPool {
         let listeners_connected = listeners.clone();
         let listeners_disconnected = listeners.clone();
         let listeners_new_peak = listeners.clone();
+        let listeners_data_store = listeners.clone();
 
         pool.set_event_callbacks(
             Box::new(move |event| {
@@ -69,6 +76,15 @@ impl ChiaPeerPool {
                     }
                 });
             }),
+            Box::new(move |event| {
+                let listeners = listeners_data_store.clone();
+                tokio::spawn(async move {
+                    let guard = listeners.read().await;
+                    for listener in &guard.data_store_updated_listeners {
+                        listener.call(event.clone(), ThreadsafeFunctionCallMode::NonBlocking);
+                    }
+                });
+            }),
         );
 
         Self { pool, listeners }
@@ -177,6 +193,15 @@ impl ChiaPeerPool {
                 let mut guard = rt.block_on(self.listeners.write());
                 guard.new_peak_height_listeners.push(tsfn);
             }
+            "dataStoreUpdated" => {
+                let tsfn = callback.create_threadsafe_function(0, |ctx| {
+                    let event: &DataStoreUpdatedEvent = &ctx.value;
+                    Ok(vec![create_data_store_updated_object(&ctx.env, event)?])
+                })?;
+
+                let mut guard = rt.block_on(self.listeners.write());
+                guard.data_store_updated_listeners.push(tsfn);
+            }
             _ => {
                 return Err(Error::new(
                     Status::InvalidArg,
@@ -206,6 +231,9 @@ impl ChiaPeerPool {
                 "newPeakHeight" => {
                     guard.new_peak_height_listeners.clear();
                 }
+                "dataStoreUpdated" => {
+                    guard.data_store_updated_listeners.clear();
+                }
                 _ => {}
             }
         });
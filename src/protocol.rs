@@ -1,14 +1,41 @@
+use chia_generator_parser::Network;
 use chia_protocol::{Bytes32, ProtocolMessageTypes};
+use chia_traits::Streamable;
 use serde::{Deserialize, Serialize};
 use sha2::{Digest, Sha256};
 
-#[allow(dead_code)]
 pub const MAINNET_GENESIS_CHALLENGE: &str =
     "ccd5bb71183532bff220ba46c268991a3ff07eb358e8255a65c30a2dce0e5fbb";
-#[allow(dead_code)]
 pub const TESTNET11_GENESIS_CHALLENGE: &str =
     "37a90eb5185a9c4439a91ddc98bbadce7b4feba060d50116a067de66bf236615";
 
+/// Genesis challenge for a handshake network ID
+pub fn genesis_challenge_for_network_id(network_id: &str) -> Option<&'static str> {
+    match network_id {
+        "mainnet" => Some(MAINNET_GENESIS_CHALLENGE),
+        "testnet11" => Some(TESTNET11_GENESIS_CHALLENGE),
+        _ => None,
+    }
+}
+
+/// Network whose consensus rules apply to blocks with this genesis challenge
+pub fn network_for_genesis_challenge(genesis_challenge: &str) -> Option<Network> {
+    match genesis_challenge.to_ascii_lowercase().as_str() {
+        MAINNET_GENESIS_CHALLENGE => Some(Network::Mainnet),
+        TESTNET11_GENESIS_CHALLENGE => Some(Network::Testnet11),
+        _ => None,
+    }
+}
+
+/// Resolve a network ID (e.g. "mainnet") or genesis challenge hex to a parser network
+///
+/// Returns `None` for networks without known consensus rules, rather than parsing their
+/// blocks under another network's rules.
+pub fn network_for_id(network_id: &str) -> Option<Network> {
+    let genesis_challenge = genesis_challenge_for_network_id(network_id).unwrap_or(network_id);
+    network_for_genesis_challenge(genesis_challenge)
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Handshake {
     pub network_id: String,
@@ -78,7 +105,12 @@ impl Message {
             return Err(Error::new(ErrorKind::InvalidData, "Message too short"));
         }
 
-        let msg_type = bytes[0];
+        let msg_type = ProtocolMessageTypes::from_bytes(&bytes[..1]).map_err(|_| {
+            Error::new(
+                ErrorKind::InvalidData,
+                format!("Unknown message type {}", bytes[0]),
+            )
+        })?;
         let has_id = bytes[1] != 0;
 
         let (id, data_start) = if has_id {
@@ -104,11 +136,7 @@ impl Message {
             return Err(Error::new(ErrorKind::InvalidData, "Data length mismatch"));
         }
 
-        Ok(Self {
-            msg_type: unsafe { std::mem::transmute::<u8, ProtocolMessageTypes>(msg_type) },
-            id,
-            data,
-        })
+        Ok(Self { msg_type, id, data })
     }
 }
 
@@ -122,3 +150,52 @@ pub fn calculate_node_id(cert_der: &[u8]) -> Result<Bytes32, std::io::Error> {
     bytes.copy_from_slice(&result);
     Ok(Bytes32::new(bytes))
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_network_for_id() {
+        assert!(matches!(network_for_id("mainnet"), Some(Network::Mainnet)));
+        assert!(matches!(
+            network_for_id("testnet11"),
+            Some(Network::Testnet11)
+        ));
+        assert!(matches!(
+            network_for_id(&TESTNET11_GENESIS_CHALLENGE.to_uppercase()),
+            Some(Network::Testnet11)
+        ));
+        assert!(network_for_id("testnet").is_none());
+    }
+
+    #[test]
+    fn test_message_round_trip() {
+        for id in [None, Some(7)] {
+            let message = Message::new(ProtocolMessageTypes::RequestBlock, id, vec![1, 2, 3]);
+            let decoded = Message::from_bytes(&message.to_bytes().unwrap()).unwrap();
+            assert_eq!(decoded.msg_type, message.msg_type);
+            assert_eq!(decoded.id, message.id);
+            assert_eq!(decoded.data, message.data);
+        }
+    }
+
+    #[test]
+    fn test_message_rejects_unknown_type() {
+        // Type bytes without a variant used to be transmuted into an invalid
+        // ProtocolMessageTypes, which is undefined behavior
+        let bytes = [0xff, 0, 0, 0, 0, 0];
+        let err = Message::from_bytes(&bytes).unwrap_err();
+        assert_eq!(err.kind(), std::io::ErrorKind::InvalidData);
+        assert_eq!(err.to_string(), "Unknown message type 255");
+    }
+
+    #[test]
+    fn test_message_rejects_bad_lengths() {
+        assert!(Message::from_bytes(&[]).is_err());
+        // Has an ID, but is too short to hold it and the data length
+        assert!(Message::from_bytes(&[1, 1, 0, 0, 0, 0]).is_err());
+        // Claims one byte of data but carries two
+        assert!(Message::from_bytes(&[1, 0, 0, 0, 0, 1, 5, 6]).is_err());
+    }
+}
@@ -0,0 +1,379 @@
+use chia_protocol::{Bytes32, FullBlock};
+use chia_traits::Streamable;
+use std::collections::{BTreeSet, HashMap};
+use std::sync::RwLock;
+use tracing::warn;
+
+/// Furthest a block may be above the transaction block whose timestamp it is given
+///
+/// Transaction blocks are a few heights apart, so an older timestamp in the cache means
+/// the blocks in between weren't seen rather than that none of them had a timestamp.
+pub const MAX_INFERENCE_DISTANCE: u32 = 32;
+
+/// Headers of recently seen blocks, used to date the blocks between transaction blocks
+///
+/// Only transaction blocks carry a timestamp. A non-transaction block is given the
+/// timestamp of the nearest transaction block below it on its own chain, found by
+/// following `prev_header_hash` through the headers seen so far, as long as it is within
+/// `MAX_INFERENCE_DISTANCE` heights, which is how far behind the chain's clock it can be
+/// known to be. Headers are keyed by header hash, so blocks from another fork or network
+/// are never used.
+pub struct BlockTimestamps {
+    headers: RwLock<Headers>,
+    max_entries: usize,
+}
+
+#[derive(Default)]
+struct Headers {
+    by_hash: HashMap<Bytes32, BlockHeader>,
+    /// Eviction order, lowest heights first
+    by_height: BTreeSet<(u32, Bytes32)>,
+}
+
+/// A block's timestamp, and whether the block carried it or it was inferred
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ResolvedTimestamp {
+    pub timestamp: u32,
+    pub exact: bool,
+}
+
+impl ResolvedTimestamp {
+    fn inferred(timestamp: Option<u32>) -> Self {
+        Self {
+            timestamp: timestamp.unwrap_or(0),
+            exact: false,
+        }
+    }
+}
+
+/// What timestamp inference needs from a block
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct BlockHeader {
+    pub height: u32,
+    pub header_hash: Bytes32,
+    pub prev_header_hash: Bytes32,
+    /// Only set when the block's foliage commits to it
+    pub timestamp: Option<u32>,
+}
+
+impl From<&FullBlock> for BlockHeader {
+    fn from(block: &FullBlock) -> Self {
+        let timestamp = block
+            .foliage_transaction_block
+            .as_ref()
+            .filter(|ftb| {
+                block.foliage.foliage_transaction_block_hash == Some(Bytes32::new(ftb.hash()))
+            })
+            .map(|ftb| ftb.timestamp as u32);
+
+        Self {
+            height: block.height(),
+            header_hash: block.header_hash(),
+            prev_header_hash: block.prev_header_hash(),
+            timestamp,
+        }
+    }
+}
+
+impl BlockTimestamps {
+    /// Create a tracker that holds at most `max_entries` block headers
+    pub fn with_max_entries(max_entries: usize) -> Self {
+        Self {
+            headers: RwLock::new(Headers::default()),
+            max_entries,
+        }
+    }
+
+    /// Store a block's header, whether or not it has a timestamp
+    pub fn record(&self, block: &BlockHeader) {
+        let Ok(mut headers) = self.headers.write() else {
+            return;
+        };
+
+        if headers.by_hash.insert(block.header_hash, *block).is_none() {
+            headers.by_height.insert((block.height, block.header_hash));
+        }
+        while headers.by_hash.len() > self.max_entries {
+            let Some((_, header_hash)) = headers.by_height.pop_first() else {
+                break;
+            };
+            headers.by_hash.remove(&header_hash);
+        }
+    }
+
+    /// A recorded header, if the block has been seen
+    fn get(&self, header_hash: &Bytes32) -> Option<BlockHeader> {
+        self.headers.read().ok()?.by_hash.get(header_hash).copied()
+    }
+
+    /// Timestamp of the nearest transaction block below `block` on its chain, if the
+    /// blocks down to one within `MAX_INFERENCE_DISTANCE` heights have all been seen
+    pub fn infer(&self, block: &BlockHeader) -> Option<u32> {
+        let lowest = block.height.saturating_sub(MAX_INFERENCE_DISTANCE);
+        let mut height = block.height;
+        let mut prev_header_hash = block.prev_header_hash;
+        while let Some(prev) = self.get(&prev_header_hash) {
+            if prev.height >= height || prev.height < lowest {
+                return None;
+            }
+            if prev.timestamp.is_some() {
+                return prev.timestamp;
+            }
+            height = prev.height;
+            prev_header_hash = prev.prev_header_hash;
+        }
+        None
+    }
+
+    /// Timestamp to report for a block, recording the block for the ones after it
+    ///
+    /// Falls back to `0` when no earlier transaction block is known.
+    pub fn resolve(&self, block: &BlockHeader) -> ResolvedTimestamp {
+        self.record(block);
+        match block.timestamp {
+            Some(timestamp) => ResolvedTimestamp {
+                timestamp,
+                exact: true,
+            },
+            None => ResolvedTimestamp::inferred(self.infer(block)),
+        }
+    }
+
+    /// Like `resolve`, but `fetch` the blocks below this one that haven't been seen,
+    /// until one has a timestamp
+    ///
+    /// The walk follows `prev_header_hash`, so it stays on the block's own chain, and
+    /// stops after `MAX_INFERENCE_DISTANCE` blocks or at a block that can't be fetched.
+    pub async fn resolve_or_fetch<F>(&self, block: BlockHeader, mut fetch: F) -> ResolvedTimestamp
+    where
+        F: AsyncFnMut(u32) -> Option<BlockHeader>,
+    {
+        let resolved = self.resolve(&block);
+        if resolved.exact {
+            return resolved;
+        }
+
+        let mut prev_header_hash = block.prev_header_hash;
+        let lowest = block.height.saturating_sub(MAX_INFERENCE_DISTANCE);
+        for height in (lowest..block.height).rev() {
+            let prev = match self.get(&prev_header_hash) {
+                Some(prev) if prev.height == height => prev,
+                _ => {
+                    let Some(prev) = fetch(height).await else {
+                        break;
+                    };
+                    if prev.header_hash != prev_header_hash {
+                        warn!(
+                            "Block {} fetched for its timestamp isn't the previous block of {}",
+                            height, block.height
+                        );
+                        break;
+                    }
+                    self.record(&prev);
+                    prev
+                }
+            };
+
+            if prev.timestamp.is_some() {
+                return ResolvedTimestamp::inferred(prev.timestamp);
+            }
+            prev_header_hash = prev.prev_header_hash;
+        }
+
+        resolved
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// A chain of headers from height 0, with timestamps at the given heights
+    fn chain(length: u32, timestamps: &[(u32, u32)]) -> Vec<BlockHeader> {
+        fork(&[], length, 0, timestamps)
+    }
+
+    /// Headers from the end of `base` up to `length`, with hashes marked by `fork_id`
+    fn fork(
+        base: &[BlockHeader],
+        length: u32,
+        fork_id: u8,
+        timestamps: &[(u32, u32)],
+    ) -> Vec<BlockHeader> {
+        let mut headers = base.to_vec();
+        for height in base.len() as u32..length {
+            let prev_header_hash = headers
+                .last()
+                .map(|prev| prev.header_hash)
+                .unwrap_or_default();
+            let mut header_hash = [(height % 256) as u8 + 1; 32];
+            header_hash[0] = fork_id;
+            headers.push(BlockHeader {
+                height,
+                header_hash: Bytes32::new(header_hash),
+                prev_header_hash,
+                timestamp: timestamps
+                    .iter()
+                    .find(|(at, _)| *at == height)
+                    .map(|(_, timestamp)| *timestamp),
+            });
+        }
+        headers
+    }
+
+    fn record_all(timestamps: &BlockTimestamps, headers: &[BlockHeader]) {
+        for header in headers {
+            timestamps.record(header);
+        }
+    }
+
+    #[test]
+    fn test_infers_from_nearest_earlier_transaction_block() {
+        let headers = chain(110, &[(100, 1_000), (104, 1_052)]);
+        let timestamps = BlockTimestamps::with_max_entries(16);
+        record_all(&timestamps, &headers[95..104]);
+
+        assert_eq!(
+            timestamps.resolve(&headers[103]),
+            ResolvedTimestamp {
+                timestamp: 1_000,
+                exact: false
+            }
+        );
+        assert_eq!(
+            timestamps.resolve(&headers[104]),
+            ResolvedTimestamp {
+                timestamp: 1_052,
+                exact: true
+            }
+        );
+        assert_eq!(timestamps.resolve(&headers[105]).timestamp, 1_052);
+        // The block's own timestamp isn't a candidate for itself
+        assert_eq!(timestamps.infer(&headers[104]), Some(1_000));
+    }
+
+    #[test]
+    fn test_unknown_without_earlier_transaction_block() {
+        let headers = chain(210, &[(200, 2_000)]);
+        let timestamps = BlockTimestamps::with_max_entries(16);
+        timestamps.record(&headers[200]);
+
+        assert_eq!(
+            timestamps.resolve(&headers[150]),
+            ResolvedTimestamp {
+                timestamp: 0,
+                exact: false
+            }
+        );
+        // Nor when a block in between hasn't been seen
+        assert_eq!(timestamps.infer(&headers[202]), None);
+    }
+
+    #[test]
+    fn test_ignores_other_chains() {
+        let base = chain(100, &[(90, 900)]);
+        let fork_a = fork(&base, 110, 0xaa, &[(102, 1_020)]);
+        let fork_b = fork(&base, 110, 0xbb, &[(104, 1_040)]);
+        let timestamps = BlockTimestamps::with_max_entries(64);
+        record_all(&timestamps, &base[90..]);
+        record_all(&timestamps, &fork_a[100..]);
+        record_all(&timestamps, &fork_b[100..104]);
+
+        assert_eq!(timestamps.infer(&fork_a[105]), Some(1_020));
+        // Block 102 on the other fork is at the same height but has no timestamp
+        assert_eq!(timestamps.infer(&fork_b[104]), Some(900));
+    }
+
+    #[test]
+    fn test_max_entries_evicts_lowest_heights() {
+        let headers = chain(40, &[(10, 100), (20, 200)]);
+        let timestamps = BlockTimestamps::with_max_entries(15);
+        record_all(&timestamps, &headers[10..30]);
+
+        // Blocks 10 to 14 were evicted
+        assert_eq!(timestamps.infer(&headers[18]), None);
+        assert_eq!(timestamps.infer(&headers[25]), Some(200));
+    }
+
+    #[test]
+    fn test_inference_distance_is_capped() {
+        let headers = chain(200, &[(100, 1_000)]);
+        let timestamps = BlockTimestamps::with_max_entries(128);
+        record_all(&timestamps, &headers[100..]);
+
+        let furthest = (100 + MAX_INFERENCE_DISTANCE) as usize;
+        assert_eq!(timestamps.infer(&headers[furthest]), Some(1_000));
+        assert_eq!(timestamps.infer(&headers[furthest + 1]), None);
+    }
+
+    #[tokio::test]
+    async fn test_fetches_back_to_a_transaction_block() {
+        let headers = chain(60, &[(40, 4_000), (50, 5_000)]);
+        let timestamps = BlockTimestamps::with_max_entries(16);
+        let mut fetched = Vec::new();
+
+        let resolved = timestamps
+            .resolve_or_fetch(headers[55], async |height| {
+                fetched.push(height);
+                Some(headers[height as usize])
+            })
+            .await;
+        assert_eq!(
+            resolved,
+            ResolvedTimestamp {
+                timestamp: 5_000,
+                exact: false
+            }
+        );
+        assert_eq!(fetched, vec![54, 53, 52, 51, 50]);
+
+        // The fetched blocks are cached for the blocks after them
+        let resolved = timestamps
+            .resolve_or_fetch(headers[52], async |_| unreachable!())
+            .await;
+        assert_eq!(resolved.timestamp, 5_000);
+
+        // Only the blocks that weren't seen are fetched
+        fetched.clear();
+        let resolved = timestamps
+            .resolve_or_fetch(headers[58], async |height| {
+                fetched.push(height);
+                Some(headers[height as usize])
+            })
+            .await;
+        assert_eq!(resolved.timestamp, 5_000);
+        assert_eq!(fetched, vec![57, 56]);
+    }
+
+    #[tokio::test]
+    async fn test_fetch_stays_on_the_chain_and_within_distance() {
+        // A block from another chain isn't used
+        let headers = chain(60, &[(50, 5_000)]);
+        let mut other_chain = headers[54];
+        other_chain.header_hash = Bytes32::new([0xee; 32]);
+        let timestamps = BlockTimestamps::with_max_entries(64);
+        let resolved = timestamps
+            .resolve_or_fetch(headers[55], async |height| {
+                Some(if height == 54 {
+                    other_chain
+                } else {
+                    headers[height as usize]
+                })
+            })
+            .await;
+        assert_eq!(resolved.timestamp, 0);
+
+        // Nor is a transaction block too far below
+        let headers = chain(60, &[(10, 1_000)]);
+        let timestamps = BlockTimestamps::with_max_entries(64);
+        let mut fetched = 0;
+        let resolved = timestamps
+            .resolve_or_fetch(headers[55], async |height| {
+                fetched += 1;
+                Some(headers[height as usize])
+            })
+            .await;
+        assert_eq!(resolved.timestamp, 0);
+        assert_eq!(fetched, MAX_INFERENCE_DISTANCE);
+    }
+}